@@ -0,0 +1,90 @@
+use maycoon::core::app::update::Update;
+use maycoon::core::app::windows::{WindowKey, Windows};
+use maycoon::core::app::MayApp;
+use maycoon::core::config::{MayConfig, WindowConfig};
+use maycoon::core::layout::{AlignItems, Dimension, FlexDirection, LayoutStyle};
+use maycoon::core::state::Val;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::widgets::button::Button;
+use maycoon::widgets::container::Container;
+use maycoon::widgets::text::Text;
+
+#[derive(State)]
+struct MyState {
+    count: i32,
+    windows: Windows,
+    counter: Option<WindowKey>,
+}
+
+fn main() {
+    let app = MayApp::new(MayConfig::default());
+    let windows = app.windows();
+
+    app.run(
+        MyState {
+            count: 0,
+            windows,
+            counter: None,
+        },
+        Container::new(vec![
+            Val::new_val(Box::new(
+                Button::new(Text::new("Open Counter".to_string())).with_on_pressed(
+                    |state: &mut MyState| {
+                        // only open one counter window at a time
+                        if state.counter.is_some_and(|key| state.windows.is_open(key)) {
+                            return Update::empty();
+                        }
+
+                        state.counter = Some(state.windows.open(
+                            WindowConfig {
+                                title: "Counter".to_string(),
+                                ..Default::default()
+                            },
+                            counter(),
+                        ));
+
+                        Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(Text::new(val!(|state: &MyState| format!(
+                "Count: {}",
+                state.count
+            ))))),
+        ])
+        .with_layout_style(layout_style()),
+    );
+}
+
+fn counter() -> Container<MyState> {
+    Container::new(vec![
+        Val::new_val(Box::new(
+            Button::new(Text::new("Increase".to_string())).with_on_pressed(
+                |state: &mut MyState| {
+                    state.count += 1;
+                    Update::DRAW
+                },
+            ),
+        )),
+        Val::new_val(Box::new(
+            Button::new(Text::new("Close".to_string())).with_on_pressed(|state: &mut MyState| {
+                if let Some(key) = state.counter.take() {
+                    state.windows.close(key);
+                }
+
+                Update::empty()
+            }),
+        )),
+    ])
+    .with_layout_style(layout_style())
+}
+
+fn layout_style() -> LayoutStyle {
+    LayoutStyle {
+        size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        flex_direction: FlexDirection::Column,
+        align_items: Some(AlignItems::Center),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,63 @@
+use maycoon::core::app::update::Update;
+use maycoon::core::app::MayApp;
+use maycoon::core::config::MayConfig;
+use maycoon::core::layout::{Dimension, FlexDirection, LayoutStyle, Overflow};
+use maycoon::core::state::Val;
+use maycoon::core::widget::Widget;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::widgets::button::Button;
+use maycoon::widgets::container::Container;
+use maycoon::widgets::scroll_view::ScrollView;
+use maycoon::widgets::text::Text;
+
+#[derive(State)]
+struct MyState {
+    scroll_to: Option<Vector2<f32>>,
+}
+
+fn main() {
+    let lines = (1..=100)
+        .map(|i| Val::new_val(Box::new(Text::new(format!("Line {i}"))) as Box<dyn Widget<MyState>>))
+        .collect();
+
+    MayApp::new(MayConfig::default()).run(
+        MyState { scroll_to: None },
+        Container::new(vec![
+            Val::new_val(Box::new(
+                Button::new(Text::new("Back to top".to_string())).with_on_pressed(
+                    |state: &mut MyState| {
+                        state.scroll_to = Some(Vector2::new(0.0, 0.0));
+
+                        // evaluate again, so the scroll view notices the request
+                        Update::EVAL | Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(
+                ScrollView::new(Container::new(lines).with_layout_style(LayoutStyle {
+                    flex_direction: FlexDirection::Column,
+                    ..Default::default()
+                }))
+                .with_drag_scroll(true)
+                .with_scroll_to(val!(|state: &MyState| state.scroll_to))
+                .with_on_scroll(|state, _| {
+                    // allow requesting the same offset again
+                    state.scroll_to = None;
+                    Update::empty()
+                })
+                .with_layout_style(LayoutStyle {
+                    flex_grow: 1.0,
+                    flex_direction: FlexDirection::Column,
+                    overflow: (Overflow::Scroll, Overflow::Scroll),
+                    ..Default::default()
+                }),
+            )),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+    );
+}
@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use maycoon::core::app::update::Update;
+use maycoon::core::app::MayApp;
+use maycoon::core::config::MayConfig;
+use maycoon::core::layout::{AlignItems, Dimension, FlexDirection, LayoutStyle};
+use maycoon::core::state::Val;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::widgets::container::Container;
+use maycoon::widgets::text::Text;
+
+#[derive(State)]
+struct MyState {
+    seconds: u32,
+    readings: Vec<u32>,
+}
+
+fn main() {
+    let app = MayApp::new(MayConfig::default());
+    let tasks = app.tasks();
+
+    // count the seconds since the app started
+    tasks.set_interval(Duration::from_secs(1), |state: &mut MyState| {
+        state.seconds += 1;
+        Update::DRAW
+    });
+
+    // stream readings from a background source into the state
+    tasks.spawn({
+        let tasks = tasks.clone();
+
+        async move {
+            for reading in 0..100 {
+                std::thread::sleep(Duration::from_millis(250));
+
+                tasks.post(move |state: &mut MyState| {
+                    state.readings.push(reading * reading % 17);
+                    Update::DRAW
+                });
+            }
+        }
+    });
+
+    app.run(
+        MyState {
+            seconds: 0,
+            readings: Vec::new(),
+        },
+        Container::new(vec![
+            Val::new_val(Box::new(Text::new(val!(|state: &MyState| format!(
+                "Running for {} seconds",
+                state.seconds
+            ))))),
+            Val::new_val(Box::new(Text::new(val!(|state: &MyState| format!(
+                "Received {} readings, last: {}",
+                state.readings.len(),
+                state
+                    .readings
+                    .last()
+                    .map_or(String::from("-"), |r| r.to_string())
+            ))))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        }),
+    );
+}
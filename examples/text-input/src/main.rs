@@ -0,0 +1,54 @@
+use maycoon::core::app::update::Update;
+use maycoon::core::app::MayApp;
+use maycoon::core::config::MayConfig;
+use maycoon::core::layout::{AlignItems, Dimension, FlexDirection, LayoutStyle};
+use maycoon::core::state::Val;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::widgets::container::Container;
+use maycoon::widgets::text::Text;
+use maycoon::widgets::text_area::TextArea;
+use maycoon::widgets::text_input::TextInput;
+
+#[derive(State)]
+struct MyState {
+    name: String,
+    notes: String,
+}
+
+fn main() {
+    MayApp::new(MayConfig::default()).run(
+        MyState {
+            name: String::new(),
+            notes: String::new(),
+        },
+        Container::new(vec![
+            Val::new_val(Box::new(
+                TextInput::new(val!(|state: &MyState| state.name.clone())).with_on_change(
+                    |state, text| {
+                        state.name = text;
+                        Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(Text::new(val!(|state: &MyState| format!(
+                "Hello, {}!",
+                state.name
+            ))))),
+            Val::new_val(Box::new(
+                TextArea::new(val!(|state: &MyState| state.notes.clone())).with_on_change(
+                    |state, text| {
+                        state.notes = text;
+                        Update::DRAW
+                    },
+                ),
+            )),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        }),
+    );
+}
@@ -0,0 +1,75 @@
+use maycoon::core::app::update::Update;
+use maycoon::core::app::MayApp;
+use maycoon::core::config::{MayConfig, RenderConfig, WindowConfig};
+use maycoon::core::layout::{AlignItems, Dimension, FlexDirection, LayoutStyle};
+use maycoon::core::state::Val;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::theme::theme::file::FileTheme;
+use maycoon::widgets::button::Button;
+use maycoon::widgets::checkbox::Checkbox;
+use maycoon::widgets::container::Container;
+use maycoon::widgets::schema::schema;
+use maycoon::widgets::slider::Slider;
+use maycoon::widgets::text::Text;
+
+#[derive(State)]
+struct MyState {
+    checked: bool,
+    value: f32,
+}
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/theme.toml");
+    let theme = FileTheme::load(path)
+        .expect("Failed to load theme")
+        .with_schema(&schema());
+
+    for issue in schema().validate(&theme) {
+        eprintln!("Theme issue: {}", issue);
+    }
+
+    MayApp::new(MayConfig {
+        window: WindowConfig::default(),
+        render: RenderConfig::default(),
+        theme,
+        follow_system_theme: false,
+    })
+    // edit theme.toml while the app is running to see the changes
+    .with_theme_reload(path, |path| {
+        FileTheme::load(path).map(|theme| theme.with_schema(&schema()))
+    })
+    .run(
+        MyState {
+            checked: false,
+            value: 0.5,
+        },
+        Container::new(vec![
+            Val::new_val(Box::new(Text::new("Themed from a file".to_string()))),
+            Val::new_val(Box::new(
+                Button::new(Text::new("Toggle".to_string())).with_on_pressed(
+                    |state: &mut MyState| {
+                        state.checked = !state.checked;
+                        Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(Checkbox::new(val!(
+                |state: &MyState| state.checked
+            )))),
+            Val::new_val(Box::new(Slider::new(
+                val!(|state: &MyState| state.value),
+                |state, new| {
+                    state.value = new;
+                    Update::DRAW
+                },
+            ))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        }),
+    );
+}
@@ -0,0 +1,36 @@
+use maycoon::core::app::MayApp;
+use maycoon::core::config::MayConfig;
+use maycoon::core::layout::{Dimension, LayoutStyle, Overflow};
+use maycoon::core::widget::Widget;
+use maycoon::macros::{val, State};
+use maycoon::math::Vector2;
+use maycoon::widgets::text::Text;
+use maycoon::widgets::virtual_list::VirtualList;
+
+#[derive(State)]
+struct MyState {
+    lines: Vec<String>,
+}
+
+fn main() {
+    MayApp::new(MayConfig::default()).run(
+        MyState {
+            lines: (1..=100_000)
+                .map(|i| format!("[{i:06}] Something happened"))
+                .collect(),
+        },
+        VirtualList::new(val!(|state: &MyState| state.lines.len()), |_, index| {
+            Box::new(
+                Text::new(val!(move |state: &MyState| state.lines[index].clone()))
+                    .with_font_size(20.0),
+            ) as Box<dyn Widget<MyState>>
+        })
+        .with_row_height(26.0)
+        .with_drag_scroll(true)
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            overflow: (Overflow::Scroll, Overflow::Scroll),
+            ..Default::default()
+        }),
+    );
+}
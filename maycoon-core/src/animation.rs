@@ -0,0 +1,321 @@
+use std::time::Duration;
+
+use nalgebra::Vector2;
+use vello::peniko::Color;
+
+use crate::app::info::AppInfo;
+use crate::app::update::Update;
+
+/// An animation, that is advanced using the frame time of the window.
+///
+/// Widgets should call [Animation::animate] in every [Widget::update] and return the update flags,
+/// so the window keeps redrawing until the animation settles.
+///
+/// [Widget::update]: crate::widget::Widget::update
+pub trait Animation {
+    /// Advance the animation by the given time.
+    ///
+    /// Returns if the animation was running, so its value may have changed.
+    fn advance(&mut self, delta: Duration) -> bool;
+
+    /// Returns if the animation is still running.
+    fn is_running(&self) -> bool;
+
+    /// Advance the animation by the frame time of the given app info.
+    ///
+    /// Returns [Update::DRAW] and [Update::EVAL] while the animation is running, to request continuous redraws.
+    fn animate(&mut self, info: &AppInfo) -> Update {
+        if self.advance(info.delta_time) {
+            Update::DRAW | Update::EVAL
+        } else {
+            Update::empty()
+        }
+    }
+}
+
+/// A value, that can be linearly interpolated.
+pub trait Lerp: Clone {
+    /// Interpolate between this value and the other value.
+    ///
+    /// A factor of `0.0` returns this value and a factor of `1.0` returns the other value.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+}
+
+impl Lerp for Vector2<f32> {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let channel = |a: u8, b: u8| (a as f32).lerp(&(b as f32), t).round() as u8;
+
+        Color::rgba8(
+            channel(self.r, other.r),
+            channel(self.g, other.g),
+            channel(self.b, other.b),
+            channel(self.a, other.a),
+        )
+    }
+}
+
+/// An easing curve, that maps the linear progress of a [Tween] to the progress of its value.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Easing {
+    /// Constant speed.
+    Linear,
+    /// Starts slow and accelerates.
+    EaseIn,
+    /// Starts fast and decelerates.
+    #[default]
+    EaseOut,
+    /// Starts slow, accelerates and decelerates at the end.
+    EaseInOut,
+    /// A custom easing function, that maps a progress from `0.0` to `1.0`.
+    Custom(fn(f32) -> f32),
+}
+
+impl Easing {
+    /// Apply the easing curve to the given progress between `0.0` and `1.0`.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            },
+            Easing::Custom(f) => f(t),
+        }
+    }
+}
+
+/// A value, that transitions to its target in a fixed duration using an [Easing] curve.
+///
+/// ```
+/// use std::time::Duration;
+/// use maycoon_core::animation::{Animation, Tween};
+///
+/// let mut tween = Tween::new(0.0, Duration::from_millis(100));
+///
+/// tween.set(10.0);
+/// tween.advance(Duration::from_millis(50));
+/// assert!(tween.is_running());
+///
+/// tween.advance(Duration::from_millis(50));
+/// assert_eq!(tween.value(), 10.0);
+/// assert!(!tween.is_running());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Tween<T: Lerp + PartialEq> {
+    from: T,
+    to: T,
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+}
+
+impl<T: Lerp + PartialEq> Tween<T> {
+    /// Create a new settled tween with the given value and transition duration.
+    pub fn new(value: T, duration: Duration) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            duration,
+            elapsed: duration,
+            easing: Easing::default(),
+        }
+    }
+
+    /// Set the easing curve of the tween. Defaults to [Easing::EaseOut].
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Start a transition from the current value to the given target.
+    ///
+    /// Does nothing, if the target didn't change.
+    pub fn set(&mut self, target: T) {
+        if target != self.to {
+            self.from = self.value();
+            self.to = target;
+            self.elapsed = Duration::ZERO;
+        }
+    }
+
+    /// Set the value immediately, without a transition.
+    pub fn jump(&mut self, value: T) {
+        self.from = value.clone();
+        self.to = value;
+        self.elapsed = self.duration;
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> T {
+        if self.duration.is_zero() {
+            return self.to.clone();
+        }
+
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+
+        self.from.lerp(&self.to, self.easing.apply(t))
+    }
+
+    /// Returns the target value.
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+}
+
+impl<T: Lerp + PartialEq> Animation for Tween<T> {
+    fn advance(&mut self, delta: Duration) -> bool {
+        let running = self.is_running();
+
+        self.elapsed = (self.elapsed + delta).min(self.duration);
+
+        running
+    }
+
+    fn is_running(&self) -> bool {
+        self.elapsed < self.duration
+    }
+}
+
+/// A value, that follows its target using a damped spring.
+///
+/// Springs keep their velocity, when the target changes during a transition, which makes them feel natural
+/// for interactive animations like dragging.
+///
+/// ```
+/// use std::time::Duration;
+/// use maycoon_core::animation::{Animation, Spring};
+///
+/// let mut spring = Spring::new(0.0);
+///
+/// spring.set(1.0);
+///
+/// while spring.is_running() {
+///     spring.advance(Duration::from_millis(16));
+/// }
+///
+/// assert_eq!(spring.value(), 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spring {
+    value: f32,
+    target: f32,
+    velocity: f32,
+    stiffness: f32,
+    damping: f32,
+    precision: f32,
+}
+
+impl Spring {
+    /// Create a new settled spring with the given value.
+    pub fn new(value: f32) -> Self {
+        Self {
+            value,
+            target: value,
+            velocity: 0.0,
+            stiffness: 170.0,
+            damping: 26.0,
+            precision: 0.001,
+        }
+    }
+
+    /// Set the stiffness of the spring. Stiffer springs move faster. Defaults to `170`.
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Set the damping of the spring. Lower damping makes the spring bounce. Defaults to `26`.
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Set the distance to the target, at which the spring settles. Defaults to `0.001`.
+    pub fn with_precision(mut self, precision: f32) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Set the target of the spring. The spring keeps its current velocity.
+    pub fn set(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    /// Set the value immediately and stop the spring.
+    pub fn jump(&mut self, value: f32) {
+        self.value = value;
+        self.target = value;
+        self.velocity = 0.0;
+    }
+
+    /// Returns the current value.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Returns the target value.
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Returns the current velocity in units per second.
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+}
+
+impl Animation for Spring {
+    fn advance(&mut self, delta: Duration) -> bool {
+        if !self.is_running() {
+            return false;
+        }
+
+        let mut remaining = delta.as_secs_f32();
+
+        // integrate in small steps, so the spring stays stable with long frame times
+        while remaining > 0.0 {
+            let step = remaining.min(1.0 / 240.0);
+            let force = -self.stiffness * (self.value - self.target) - self.damping * self.velocity;
+
+            self.velocity += force * step;
+            self.value += self.velocity * step;
+
+            remaining -= step;
+        }
+
+        if !self.is_running() {
+            self.jump(self.target);
+        }
+
+        true
+    }
+
+    fn is_running(&self) -> bool {
+        (self.value - self.target).abs() > self.precision || self.velocity.abs() > self.precision
+    }
+}
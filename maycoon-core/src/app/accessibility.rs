@@ -0,0 +1,318 @@
+use accesskit::{
+    Action, ActionData, ActionRequest, Node, NodeId, Rect, Role, Toggled, Tree, TreeUpdate,
+};
+use nalgebra::Vector2;
+
+use crate::app::focus::FocusId;
+use crate::app::info::AppInfo;
+use crate::layout::LayoutNode;
+use crate::state::State;
+use crate::widget::Widget;
+
+/// The id of the window node, which is the root of the accessibility tree.
+const WINDOW: NodeId = NodeId(0);
+
+/// The highest bit marks the ids of focusable nodes, so they don't collide with the ids of other nodes.
+const FOCUSABLE: u64 = 1 << 63;
+
+/// A node of the widget tree, as seen by assistive technologies (e.g. screen readers).
+///
+/// Returned by [Widget::accessibility]. The children must match the children of the [StyleNode] returned by [Widget::layout_style].
+/// The bounds of the node are taken from its [LayoutNode].
+///
+/// Nodes with a [FocusId] can be focused by assistive technologies and receive [AccessAction]s through [AppInfo::access_actions].
+///
+/// [StyleNode]: crate::layout::StyleNode
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    /// The role of the widget.
+    pub role: Role,
+    /// The name of the widget, if it's not given by its children (e.g. the text of a button).
+    pub label: Option<String>,
+    /// The text content of the widget (e.g. the text of a label or text input).
+    pub value: Option<String>,
+    /// The numeric value of the widget (e.g. of a slider).
+    pub numeric: Option<NumericValue>,
+    /// If the widget is checked, if it can be toggled.
+    pub toggled: Option<bool>,
+    /// The focus id of the widget, if it's focusable.
+    pub focus: Option<FocusId>,
+    /// The supported actions of the widget.
+    pub actions: Vec<Action>,
+    /// The translation of the children, if the widget draws them at another position than their layout (e.g. when scrolling).
+    pub offset: Vector2<f32>,
+    /// The nodes of the child widgets.
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    /// Create a new node with the given role without children.
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            label: None,
+            value: None,
+            numeric: None,
+            toggled: None,
+            focus: None,
+            actions: Vec::new(),
+            offset: Vector2::new(0.0, 0.0),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the name of the widget.
+    pub fn with_label(mut self, label: impl ToString) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Set the text content of the widget.
+    pub fn with_value(mut self, value: impl ToString) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    /// Set the numeric value of the widget.
+    pub fn with_numeric(mut self, numeric: NumericValue) -> Self {
+        self.numeric = Some(numeric);
+        self
+    }
+
+    /// Set if the widget is checked.
+    pub fn with_toggled(mut self, toggled: bool) -> Self {
+        self.toggled = Some(toggled);
+        self
+    }
+
+    /// Make the widget focusable with the given focus id. Adds the [Action::Focus] action.
+    pub fn with_focus(mut self, focus: FocusId) -> Self {
+        self.focus = Some(focus);
+        self.with_action(Action::Focus)
+    }
+
+    /// Add a supported action.
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Set the translation of the children.
+    pub fn with_offset(mut self, offset: Vector2<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set the nodes of the child widgets.
+    pub fn with_children(mut self, children: Vec<AccessNode>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+/// A numeric value inside a range, like the value of a slider.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NumericValue {
+    /// The current value.
+    pub value: f64,
+    /// The minimum value.
+    pub min: f64,
+    /// The maximum value.
+    pub max: f64,
+    /// The amount the value changes by [Action::Increment] and [Action::Decrement].
+    pub step: f64,
+}
+
+/// An action requested by assistive technologies, targeting a focusable widget.
+///
+/// See [AppInfo::access_actions].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum AccessAction {
+    /// Click or toggle the widget.
+    Click,
+    /// Focus the widget. Gets applied to the [FocusManager](crate::app::focus::FocusManager) automatically.
+    Focus,
+    /// Increment the value of the widget by one step.
+    Increment,
+    /// Decrement the value of the widget by one step.
+    Decrement,
+    /// Set the numeric value of the widget.
+    SetValue(f64),
+}
+
+impl AccessAction {
+    /// Convert the given request of an accessibility adapter into the target widget and action.
+    ///
+    /// Returns [None] if the target is not focusable or the action is not supported.
+    pub fn from_request(request: &ActionRequest) -> Option<(FocusId, Self)> {
+        let target = FocusId::from_raw(request.target.0.checked_sub(FOCUSABLE)?);
+
+        let action = match (request.action, &request.data) {
+            (Action::Click, _) => Self::Click,
+            (Action::Focus, _) => Self::Focus,
+            (Action::Increment, _) => Self::Increment,
+            (Action::Decrement, _) => Self::Decrement,
+            (Action::SetValue, Some(ActionData::NumericValue(value))) => Self::SetValue(*value),
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                Self::SetValue(value.trim().parse().ok()?)
+            },
+            _ => return None,
+        };
+
+        Some((target, action))
+    }
+}
+
+/// Returns the id of the accessibility node of the widget with the given focus id.
+pub fn node_id(focus: FocusId) -> NodeId {
+    NodeId(FOCUSABLE | focus.raw())
+}
+
+/// Apply the [AccessAction::Focus] requests of the given info.
+pub(crate) fn request_focus(info: &AppInfo) {
+    for (id, action) in &info.access_actions {
+        if *action == AccessAction::Focus {
+            info.focus.request_focus(*id);
+        }
+    }
+}
+
+/// Build the full accessibility tree of the given root widget inside a window with the given title.
+///
+/// The tree update can be passed to an [accesskit] adapter or inspected in tests.
+pub fn tree_update<S: State>(
+    widget: &mut dyn Widget<S>,
+    layout: &LayoutNode,
+    state: &S,
+    info: &AppInfo,
+    title: &str,
+) -> TreeUpdate {
+    window_tree_update(widget, layout, Vec::new(), state, info, title)
+}
+
+/// Build the full accessibility tree of the given root widget and the given overlays above it.
+///
+/// The overlays are given as their accessibility node, layout and position inside the window, ordered from bottom to top.
+pub(crate) fn window_tree_update<S: State>(
+    widget: &mut dyn Widget<S>,
+    layout: &LayoutNode,
+    overlays: Vec<(AccessNode, &LayoutNode, Vector2<f32>)>,
+    state: &S,
+    info: &AppInfo,
+    title: &str,
+) -> TreeUpdate {
+    let root = widget.accessibility(state);
+    let mut nodes = Vec::new();
+    let mut next = 1;
+
+    let mut children = vec![build_node(
+        root,
+        layout,
+        Vector2::new(0.0, 0.0),
+        &mut next,
+        &mut nodes,
+    )];
+
+    // overlays are placed above the widget tree
+    for (access, layout, position) in overlays {
+        children.push(build_node(access, layout, position, &mut next, &mut nodes));
+    }
+
+    let mut window = Node::new(Role::Window);
+
+    window.set_label(title);
+    window.set_children(children);
+    nodes.push((WINDOW, window));
+
+    // the focused widget may not be part of the tree (e.g. if it was removed)
+    let focus = info
+        .focus
+        .focused()
+        .map(node_id)
+        .filter(|id| nodes.iter().any(|(node, _)| node == id))
+        .unwrap_or(WINDOW);
+
+    TreeUpdate {
+        nodes,
+        tree: Some(Tree {
+            root: WINDOW,
+            toolkit_name: Some(String::from("maycoon")),
+            toolkit_version: Some(String::from(env!("CARGO_PKG_VERSION"))),
+        }),
+        focus,
+    }
+}
+
+/// Build the given node and its children and return its id.
+///
+/// The position of the node is relative to the given origin.
+fn build_node(
+    access: AccessNode,
+    layout: &LayoutNode,
+    origin: Vector2<f32>,
+    next: &mut u64,
+    nodes: &mut Vec<(NodeId, Node)>,
+) -> NodeId {
+    let id = match access.focus {
+        Some(focus) => node_id(focus),
+
+        None => {
+            *next += 1;
+            NodeId(*next - 1)
+        },
+    };
+
+    let l = &layout.layout;
+    let x = origin.x + l.location.x;
+    let y = origin.y + l.location.y;
+
+    let mut node = Node::new(access.role);
+
+    node.set_bounds(Rect::new(
+        x as f64,
+        y as f64,
+        (x + l.size.width) as f64,
+        (y + l.size.height) as f64,
+    ));
+
+    if let Some(label) = access.label {
+        node.set_label(label);
+    }
+
+    if let Some(value) = access.value {
+        node.set_value(value);
+    }
+
+    if let Some(numeric) = access.numeric {
+        node.set_numeric_value(numeric.value);
+        node.set_min_numeric_value(numeric.min);
+        node.set_max_numeric_value(numeric.max);
+        node.set_numeric_value_step(numeric.step);
+    }
+
+    if let Some(toggled) = access.toggled {
+        node.set_toggled(if toggled {
+            Toggled::True
+        } else {
+            Toggled::False
+        });
+    }
+
+    for action in access.actions {
+        node.add_action(action);
+    }
+
+    let origin = Vector2::new(x, y) + access.offset;
+
+    let children = access
+        .children
+        .into_iter()
+        .zip(&layout.children)
+        .map(|(child, layout)| build_node(child, layout, origin, next, nodes))
+        .collect::<Vec<_>>();
+
+    node.set_children(children);
+    nodes.push((id, node));
+
+    id
+}
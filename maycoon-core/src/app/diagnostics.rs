@@ -1,8 +1,207 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use nalgebra::Vector2;
+use peniko::{Brush, Color, Fill};
+use vello::kurbo::{Affine, RoundedRect};
+use vello::Scene;
+
+use crate::app::font_ctx::FontContext;
+use crate::text::{ShapedText, TextSpan};
+
+/// The number of recent frames, that are used to compute the [FrameTimes].
+const HISTORY: usize = 240;
+
 /// Contains diagnostics data for the application.
+///
+/// Every window collects its own diagnostics. The values describe the previous update of the window,
+/// since the current one is still running.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub struct Diagnostics {
-    /// The updates since the last second. Use `updates_per_sec` for the average updates per second.
+    /// The updates since the last second. Use `updates_per_sec` for the updates per second.
     pub updates: usize,
-    /// The average updates per second.
+    /// The updates during the last full second.
     pub updates_per_sec: usize,
+    /// The rendered frames since the last second. Use `frames_per_sec` for the frames per second.
+    pub frames: usize,
+    /// The rendered frames during the last full second.
+    pub frames_per_sec: usize,
+    /// The time spent in the phases of the last update.
+    pub timings: FrameTimings,
+    /// Statistics about the time of the recent rendered frames.
+    ///
+    /// Updates without a rendered frame (e.g. when moving the cursor over a static widget) are not included.
+    pub frame_times: FrameTimes,
+    /// The number of nodes in the layout tree of the root widget.
+    pub nodes: usize,
+    /// The number of paths in the scene of the last rendered frame.
+    pub scene_paths: usize,
+    /// The size of the encoded scene of the last rendered frame in bytes.
+    pub scene_bytes: usize,
+}
+
+/// The time spent in the phases of an update.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FrameTimings {
+    /// Updating the widgets and overlays with the input events.
+    pub update: Duration,
+    /// Collecting the layout styles of the widgets and updating the layout tree.
+    pub collect: Duration,
+    /// Computing the layout (including measuring text and other content).
+    pub layout: Duration,
+    /// Rendering the widgets into the scene.
+    pub render: Duration,
+    /// Rasterizing the scene on the GPU and presenting it.
+    pub submit: Duration,
+}
+
+impl FrameTimings {
+    /// Returns the total time of all phases.
+    pub fn total(&self) -> Duration {
+        self.update + self.collect + self.layout + self.render + self.submit
+    }
+}
+
+/// Percentiles of the total time of the recent rendered frames.
+///
+/// The time of a frame is the [total](FrameTimings::total) time of the update, that rendered it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct FrameTimes {
+    /// The median frame time.
+    pub p50: Duration,
+    /// 90% of the frames were faster than this.
+    pub p90: Duration,
+    /// 99% of the frames were faster than this.
+    pub p99: Duration,
+    /// The slowest frame.
+    pub max: Duration,
+}
+
+/// Collects the [Diagnostics] of a window.
+pub(crate) struct Profiler {
+    diagnostics: Diagnostics,
+    history: VecDeque<Duration>,
+    second: Instant,
+}
+
+impl Profiler {
+    /// Create a new profiler without any recorded updates or frames.
+    pub(crate) fn new() -> Self {
+        Self {
+            diagnostics: Diagnostics::default(),
+            history: VecDeque::with_capacity(HISTORY),
+            second: Instant::now(),
+        }
+    }
+
+    /// Returns the collected diagnostics.
+    pub(crate) fn diagnostics(&self) -> Diagnostics {
+        self.diagnostics
+    }
+
+    /// Record an update with the given phase timings and number of layout nodes.
+    pub(crate) fn record(&mut self, timings: FrameTimings, nodes: usize) {
+        let diagnostics = &mut self.diagnostics;
+
+        diagnostics.timings = timings;
+        diagnostics.nodes = nodes;
+        diagnostics.updates += 1;
+
+        // roll the counters over every second
+        if self.second.elapsed() >= Duration::from_secs(1) {
+            self.second = Instant::now();
+
+            diagnostics.updates_per_sec = diagnostics.updates;
+            diagnostics.frames_per_sec = diagnostics.frames;
+
+            diagnostics.updates = 0;
+            diagnostics.frames = 0;
+        }
+    }
+
+    /// Record a rendered frame with the given scene and the total time it took.
+    pub(crate) fn record_frame(&mut self, scene: &Scene, time: Duration) {
+        let encoding = scene.encoding();
+
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(time);
+
+        let mut sorted = self.history.iter().copied().collect::<Vec<_>>();
+
+        sorted.sort_unstable();
+
+        let percentile = |p: usize| sorted[(sorted.len() - 1) * p / 100];
+
+        self.diagnostics.frame_times = FrameTimes {
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: percentile(100),
+        };
+
+        self.diagnostics.frames += 1;
+        self.diagnostics.scene_paths = encoding.n_paths as usize;
+        self.diagnostics.scene_bytes = size_of_val(encoding.path_tags.as_slice())
+            + size_of_val(encoding.path_data.as_slice())
+            + size_of_val(encoding.draw_tags.as_slice())
+            + size_of_val(encoding.draw_data.as_slice())
+            + size_of_val(encoding.transforms.as_slice())
+            + size_of_val(encoding.styles.as_slice());
+    }
+}
+
+/// Draw the given diagnostics as an overlay into the top left corner of the scene.
+pub(crate) fn draw_overlay(
+    scene: &mut Scene,
+    diagnostics: &Diagnostics,
+    font_context: &FontContext,
+) {
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let timings = &diagnostics.timings;
+    let frame_times = &diagnostics.frame_times;
+
+    let text = format!(
+        "{} fps, {} updates/s\n\
+         update {:.2} ms, collect {:.2} ms, layout {:.2} ms, render {:.2} ms, submit {:.2} ms\n\
+         p50 {:.2} ms, p90 {:.2} ms, p99 {:.2} ms, max {:.2} ms\n\
+         {} nodes, {} paths, {:.1} KiB scene",
+        diagnostics.frames_per_sec,
+        diagnostics.updates_per_sec,
+        ms(timings.update),
+        ms(timings.collect),
+        ms(timings.layout),
+        ms(timings.render),
+        ms(timings.submit),
+        ms(frame_times.p50),
+        ms(frame_times.p90),
+        ms(frame_times.p99),
+        ms(frame_times.max),
+        diagnostics.nodes,
+        diagnostics.scene_paths,
+        diagnostics.scene_bytes as f64 / 1024.0,
+    );
+
+    let shaped = ShapedText::new(
+        &[TextSpan::new(text)],
+        font_context,
+        font_context.default_font(),
+        12.0,
+    );
+    let layout = shaped.layout(None);
+    let size = layout.size();
+
+    scene.fill(
+        Fill::NonZero,
+        Affine::IDENTITY,
+        Color::rgba8(0, 0, 0, 180),
+        None,
+        &RoundedRect::new(4.0, 4.0, size.x as f64 + 20.0, size.y as f64 + 20.0, 4.0),
+    );
+
+    layout.draw(scene, Vector2::new(12.0, 12.0), true, |_| {
+        Brush::Solid(Color::WHITE)
+    });
 }
@@ -0,0 +1,255 @@
+use std::cell::{Cell, RefCell};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use winit::event::{DeviceId, ElementState, MouseButton};
+use winit::keyboard::{Key, ModifiersState, NamedKey};
+
+use crate::app::info::KeyInput;
+use crate::instance::WidgetKey;
+
+/// Identifies a focusable widget.
+///
+/// The id is made of the [WidgetKey]s of the [focus scopes](FocusManager::push_scope) around the widget
+/// and the position of the widget inside its innermost scope,
+/// so the focus stays on the same widget, when keyed siblings or overlays are added or removed.
+/// Widgets outside of any scope are identified by their position in the widget tree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FocusId(u64);
+
+impl FocusId {
+    /// Create a focus id from its raw value. Raw values only use the lower 63 bits.
+    pub(crate) fn from_raw(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    /// Returns the raw value of this id. Raw values only use the lower 63 bits.
+    pub(crate) fn raw(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A focus scope with the number of widgets, that were registered inside of it.
+#[derive(Debug)]
+struct FocusScope {
+    id: u64,
+    count: u64,
+}
+
+/// Manages the keyboard focus of widgets.
+///
+/// Focusable widgets need to call [FocusManager::register] in every [Widget::update]
+/// to get their [FocusId] and use [FocusManager::is_focused] to check if they are focused.
+/// Widgets with keyed children (e.g. lists) should wrap the update of each child in a [focus scope](FocusManager::push_scope),
+/// so the children keep their focus ids, when the list changes.
+///
+/// Pressing `Tab` moves the focus to the next focusable widget and `Shift+Tab` to the previous one.
+/// The focus order is the order in which widgets call [FocusManager::register] during an update,
+/// which is the order of the widget tree followed by the overlays.
+/// Inside [modal overlays](FocusManager::trap), `Tab` only moves between the widgets of the overlay.
+/// Clicking outside any focusable widget clears the focus.
+///
+/// [Widget::update]: crate::widget::Widget::update
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    focused: Cell<Option<FocusId>>,
+    count: Cell<u64>,
+    scopes: RefCell<Vec<FocusScope>>,
+    order: RefCell<Vec<FocusId>>,
+    last_order: RefCell<Vec<FocusId>>,
+    trap: Cell<Option<usize>>,
+    last_trap: Cell<Option<usize>>,
+    request: Cell<Option<Option<FocusId>>>,
+}
+
+impl FocusManager {
+    /// Register a focusable widget and return its [FocusId].
+    ///
+    /// Must be called by every focusable widget in every update, even if it's not focused.
+    pub fn register(&self) -> FocusId {
+        let id = match self.scopes.borrow_mut().last_mut() {
+            Some(scope) => {
+                scope.count += 1;
+                hash((scope.id, scope.count - 1))
+            },
+
+            None => {
+                let id = self.count.get();
+                self.count.set(id + 1);
+                id
+            },
+        };
+
+        self.order.borrow_mut().push(FocusId(id));
+
+        FocusId(id)
+    }
+
+    /// Register the following widgets inside a new scope with the given key, until the next call to [FocusManager::pop_scope].
+    ///
+    /// Scopes can be nested. The key must be unique inside the parent scope.
+    pub fn push_scope(&self, key: WidgetKey) {
+        let mut scopes = self.scopes.borrow_mut();
+        let parent = scopes.last().map_or(0, |scope| scope.id);
+
+        scopes.push(FocusScope {
+            id: hash((parent, key)),
+            count: 0,
+        });
+    }
+
+    /// Leave the scope of the last call to [FocusManager::push_scope].
+    pub fn pop_scope(&self) {
+        self.scopes.borrow_mut().pop();
+    }
+
+    /// Trap `Tab` traversal inside the widgets, that get registered after this call during the current update.
+    ///
+    /// Used for modal overlays, so the focus can't move to the blocked widgets below them.
+    pub fn trap(&self) {
+        self.trap.set(Some(self.order.borrow().len()));
+    }
+
+    /// Returns if the widget with the given id is focused.
+    pub fn is_focused(&self, id: FocusId) -> bool {
+        self.focused.get() == Some(id)
+    }
+
+    /// Returns the currently focused widget or [None] if nothing is focused.
+    pub fn focused(&self) -> Option<FocusId> {
+        self.focused.get()
+    }
+
+    /// Returns the position of the widget with the given id in the focus order of the last update
+    /// or [None] if the widget wasn't registered.
+    pub fn position(&self, id: FocusId) -> Option<usize> {
+        self.last_order
+            .borrow()
+            .iter()
+            .position(|other| *other == id)
+    }
+
+    /// Request to focus the widget with the given id.
+    ///
+    /// The focus changes after the current update.
+    pub fn request_focus(&self, id: FocusId) {
+        self.request.set(Some(Some(id)));
+    }
+
+    /// Request to clear the focus, so no widget is focused.
+    ///
+    /// The focus changes after the current update.
+    pub fn clear_focus(&self) {
+        self.request.set(Some(None));
+    }
+
+    /// Move the focus to the next focusable widget in the focus order. Wraps around at the end.
+    pub fn focus_next(&self) {
+        self.traverse(|i, count| i.map_or(0, |i| (i + 1) % count));
+    }
+
+    /// Move the focus to the previous focusable widget in the focus order. Wraps around at the start.
+    pub fn focus_prev(&self) {
+        self.traverse(|i, count| i.map_or(count - 1, |i| (i + count - 1) % count));
+    }
+
+    /// Move the focus to the widget at the position returned by `step`,
+    /// given the position of the focused widget and the number of widgets, that can be focused.
+    fn traverse(&self, step: impl FnOnce(Option<usize>, usize) -> usize) {
+        let order = self.order.borrow();
+        let last_order = self.last_order.borrow();
+
+        // during an update, not all widgets may be registered yet
+        let (order, trap) = if order.len() >= last_order.len() {
+            (&order, self.trap.get())
+        } else {
+            (&last_order, self.last_trap.get())
+        };
+
+        let order = &order[trap.unwrap_or(0).min(order.len())..];
+
+        if !order.is_empty() {
+            let focused = self
+                .focused
+                .get()
+                .and_then(|focused| order.iter().position(|id| *id == focused));
+
+            self.focused.set(Some(order[step(focused, order.len())]));
+        }
+    }
+
+    /// Prepare for a new update and apply `Tab` and `Shift+Tab` traversal from the given key events.
+    ///
+    /// Returns if the focus changed.
+    pub(crate) fn begin_update(
+        &self,
+        keys: &[(DeviceId, KeyInput)],
+        modifiers: ModifiersState,
+    ) -> bool {
+        let old = self.focused.get();
+
+        for (_, key) in keys {
+            if key.state == ElementState::Pressed && key.logical_key == Key::Named(NamedKey::Tab) {
+                if modifiers.shift_key() {
+                    self.focus_prev();
+                } else {
+                    self.focus_next();
+                }
+            }
+        }
+
+        self.count.set(0);
+        self.scopes.borrow_mut().clear();
+
+        old != self.focused.get()
+    }
+
+    /// Finish the update and apply focus requests.
+    ///
+    /// Pressing a mouse button without any focus requests clears the focus.
+    /// If the layout changes after this update, new widgets may not have been updated yet,
+    /// so a missing focused widget only loses the focus, if it's still missing in the next update.
+    ///
+    /// Returns if the focus changed.
+    pub(crate) fn end_update(
+        &self,
+        buttons: &[(DeviceId, MouseButton, ElementState)],
+        relayout: bool,
+    ) -> bool {
+        let old = self.focused.get();
+
+        if let Some(request) = self.request.take() {
+            self.focused.set(request);
+        } else if buttons
+            .iter()
+            .any(|(_, _, state)| *state == ElementState::Pressed)
+        {
+            self.focused.set(None);
+        }
+
+        let order = self.order.take();
+
+        // the focused widget may have been removed
+        if !relayout
+            && self
+                .focused
+                .get()
+                .is_some_and(|focused| !order.contains(&focused))
+        {
+            self.focused.set(None);
+        }
+
+        self.last_order.replace(order);
+        self.last_trap.set(self.trap.take());
+
+        old != self.focused.get()
+    }
+}
+
+/// Hash the given value into the lower 63 bits of a raw [FocusId].
+fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+
+    value.hash(&mut hasher);
+
+    hasher.finish() >> 1
+}
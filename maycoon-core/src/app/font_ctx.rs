@@ -1,4 +1,7 @@
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
+use font_kit::family_name::FamilyName;
+use font_kit::properties::Properties;
+use font_kit::source::SystemSource;
 use peniko::{Blob, Font};
 
 /// The font context. Contains font data.
@@ -6,12 +9,27 @@ use peniko::{Blob, Font};
 pub struct FontContext {
     default: Font,
     fonts: DashMap<String, Font>,
+    fallbacks: Vec<Font>,
+    generation: u64,
+    missing: DashSet<String>,
 }
 
 impl FontContext {
+    /// Create a new font context with the given default font.
+    pub fn new(default: Font) -> Self {
+        Self {
+            default,
+            fonts: DashMap::new(),
+            fallbacks: Vec::new(),
+            generation: 0,
+            missing: DashSet::new(),
+        }
+    }
+
     /// Insert a font with a custom name.
     pub fn insert(&mut self, name: impl ToString, font: Font) {
         self.fonts.insert(name.to_string(), font);
+        self.generation += 1;
     }
 
     /// Get a font by a specified name. Returns [None] if the font could not be found.
@@ -19,11 +37,48 @@ impl FontContext {
         self.fonts.get(&name.to_string()).map(|el| el.clone())
     }
 
+    /// Get a font by a specified name or the default font, if the font could not be found.
+    ///
+    /// Font names can come from themes or style overrides, so a missing font only logs a warning (once per name).
+    pub fn get_or_default(&self, name: impl ToString) -> Font {
+        let name = name.to_string();
+
+        self.get(&name).unwrap_or_else(|| {
+            if self.missing.insert(name.clone()) {
+                log::warn!("Font {} not found, using the default font", name);
+            }
+
+            self.default.clone()
+        })
+    }
+
     /// Removes a font. Returns [None] if the font does not exist.
     pub fn remove(&mut self, name: impl ToString) -> Option<()> {
+        self.generation += 1;
         self.fonts.remove(&name.to_string()).map(|_| ())
     }
 
+    /// Add a fallback font.
+    ///
+    /// Fallback fonts are used for characters, that the requested font does not support (e.g. emojis or CJK characters).
+    /// They are tried in the order they were added.
+    pub fn add_fallback(&mut self, font: Font) {
+        self.fallbacks.push(font);
+        self.generation += 1;
+    }
+
+    /// Returns the fallback fonts.
+    pub fn fallbacks(&self) -> &[Font] {
+        &self.fallbacks
+    }
+
+    /// Returns a counter, that changes whenever fonts are inserted, removed or added as fallback.
+    ///
+    /// Widgets can compare it to know when to shape their text again (e.g. when a font got reloaded).
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     /// Returns the default font. [Roboto](https://fonts.google.com/specimen/Roboto) by default.
     pub fn default_font(&self) -> &Font {
         &self.default
@@ -32,18 +87,19 @@ impl FontContext {
 
 impl Default for FontContext {
     fn default() -> Self {
+        let source = SystemSource::new();
+
         // TODO: better way to get default font
-        let default_font = font_kit::source::SystemSource::new()
+        // fall back to any sans-serif font, if Arial is not installed (e.g. on CI machines)
+        let default_font = source
             .select_by_postscript_name("ArialMT")
+            .or_else(|_| source.select_best_match(&[FamilyName::SansSerif], &Properties::new()))
             .expect("Failed to select default font")
             .load()
             .expect("Failed to load default font")
             .copy_font_data()
             .expect("Failed to copy default font");
 
-        Self {
-            default: Font::new(Blob::new(default_font), 0),
-            fonts: DashMap::new(),
-        }
+        Self::new(Font::new(Blob::new(default_font), 0))
     }
 }
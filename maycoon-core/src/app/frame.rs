@@ -0,0 +1,273 @@
+use std::time::Instant;
+
+use accesskit::TreeUpdate;
+use nalgebra::Vector2;
+use taffy::TaffyResult;
+use vello::Scene;
+
+use maycoon_theme::theme::Theme;
+
+use crate::app::accessibility;
+use crate::app::diagnostics;
+use crate::app::diagnostics::{FrameTimings, Profiler};
+use crate::app::info::AppInfo;
+use crate::app::inspector;
+use crate::app::inspector::Inspector;
+use crate::app::overlay::OverlayLayer;
+use crate::app::update::Update;
+use crate::layout::{LayoutNode, LayoutTree};
+use crate::state::State;
+use crate::widget::Widget;
+
+/// The widget tree of a window with everything needed to update and render it, independent of the actual window.
+///
+/// Used by the [AppHandler](crate::app::handler::AppHandler) for real windows and by the [HeadlessApp](crate::app::headless::HeadlessApp),
+/// so both run the same frame pipeline:
+/// [WindowFrame::update] lays out, updates and lays out the widgets again, if requested, and updates the accessibility tree.
+/// Afterward, [WindowFrame::render] draws the widgets, overlays, inspector and diagnostics
+/// and [WindowFrame::finish] records the diagnostics and resets the input for the next frame.
+pub(crate) struct WindowFrame<S: State> {
+    /// The title of the window, used for the accessibility tree and inspector dumps.
+    pub(crate) title: String,
+    pub(crate) layout: LayoutTree,
+    pub(crate) overlays: OverlayLayer<S>,
+    pub(crate) inspector: Inspector,
+    pub(crate) info: AppInfo,
+    pub(crate) profiler: Profiler,
+    /// The size of the window.
+    pub(crate) size: Vector2<f32>,
+    /// The update flags of the current frame, which were requested so far.
+    pub(crate) update: Update,
+    /// If assistive technologies requested the full accessibility tree.
+    pub(crate) access_requested: bool,
+    /// If the diagnostics should be drawn on top of the window content.
+    pub(crate) show_diagnostics: bool,
+}
+
+impl<S: State> WindowFrame<S> {
+    /// Create a new frame of a window with the given title, size and app info.
+    ///
+    /// The first update fully lays out and renders the widget tree.
+    pub(crate) fn new(
+        title: impl ToString,
+        size: Vector2<f32>,
+        info: AppInfo,
+        inspector: bool,
+    ) -> Self {
+        Self {
+            title: title.to_string(),
+            layout: LayoutTree::new(size).expect("Failed to create layout tree"),
+            overlays: OverlayLayer::new(size),
+            inspector: Inspector::new(inspector),
+            info,
+            profiler: Profiler::new(),
+            size,
+            update: Update::FORCE,
+            access_requested: false,
+            show_diagnostics: false,
+        }
+    }
+
+    /// Resize the window. The layout is computed again on the next update.
+    pub(crate) fn resize(&mut self, size: Vector2<f32>) {
+        self.size = size;
+
+        self.layout
+            .resize(size)
+            .expect("Failed to set window node style");
+
+        self.overlays.resize(size);
+        self.update.insert(Update::DRAW | Update::LAYOUT);
+    }
+
+    /// Compute the layout of the given widget tree, but only if something changed (e.g. the window got resized).
+    ///
+    /// Builds the layout tree from the root widget first, if it is not set up yet (e.g. during the first update).
+    pub(crate) fn compute_layout(
+        &mut self,
+        widget: &mut dyn Widget<S>,
+        state: &S,
+        timings: &mut FrameTimings,
+    ) -> TaffyResult<&LayoutNode> {
+        if self.layout.is_empty() {
+            let start = Instant::now();
+            let style = widget.layout_style(state);
+
+            self.layout.update(&style)?;
+            self.update.insert(Update::FORCE);
+
+            timings.collect += start.elapsed();
+        }
+
+        let start = Instant::now();
+
+        self.layout.compute(&self.info)?;
+
+        timings.layout += start.elapsed();
+
+        Ok(self.layout.layout().unwrap())
+    }
+
+    /// Update the given widget tree with the collected input events.
+    ///
+    /// The widgets are laid out again, if requested. The accessibility tree is passed to the `access` function,
+    /// if anything changed or assistive technologies requested it.
+    ///
+    /// Returns the update flags, that were requested by the widgets and overlays.
+    /// All flags of the frame are in [WindowFrame::update].
+    pub(crate) fn update(
+        &mut self,
+        widget: &mut dyn Widget<S>,
+        state: &mut S,
+        theme: &dyn Theme,
+        timings: &mut FrameTimings,
+        access: impl FnOnce(&mut dyn FnMut() -> TreeUpdate),
+    ) -> Update {
+        self.info.diagnostics = self.profiler.diagnostics();
+
+        self.compute_layout(widget, state, timings)
+            .expect("Failed to compute layout");
+
+        let start = Instant::now();
+
+        // apply keyboard focus traversal
+        if self
+            .info
+            .focus
+            .begin_update(&self.info.keys, self.info.modifiers)
+        {
+            self.update.insert(Update::DRAW);
+        }
+
+        // focus widgets, that were focused by assistive technologies
+        accessibility::request_focus(&self.info);
+
+        // toggle the widget inspector
+        self.update.insert(self.inspector.update(&self.info));
+
+        // widgets inherit the style of the theme
+        self.info.style.begin_update(theme.globals().root());
+
+        // overlays receive input before the widget tree
+        let layout = self.layout.layout().unwrap();
+
+        let widget_update = self.overlays.update(state, &self.info, |state, info| {
+            widget.update(layout, state, info)
+        });
+
+        self.update.insert(widget_update);
+
+        // open the overlays, that were requested during the update
+        self.update.insert(self.overlays.apply(state, &self.info));
+
+        // apply focus requests
+        if self.info.focus.end_update(
+            &self.info.buttons,
+            self.update.intersects(Update::LAYOUT | Update::FORCE),
+        ) {
+            self.update.insert(Update::DRAW);
+        }
+
+        // enable the input method, if requested by the focused widget
+        self.info.input_method.end_update(&self.info.focus);
+
+        timings.update += start.elapsed();
+
+        // only the changed nodes get laid out again
+        if self.update.intersects(Update::LAYOUT | Update::FORCE) {
+            let start = Instant::now();
+            let style = widget.layout_style(state);
+
+            self.layout
+                .update(&style)
+                .expect("Failed to update layout tree");
+
+            timings.collect += start.elapsed();
+
+            let start = Instant::now();
+
+            self.layout
+                .compute(&self.info)
+                .expect("Failed to compute layout");
+
+            timings.layout += start.elapsed();
+        }
+
+        // keep the accessibility tree up to date
+        if self.access_requested || !self.update.is_empty() {
+            self.access_requested = false;
+
+            let layout = self.layout.layout().unwrap();
+
+            access(&mut || {
+                accessibility::window_tree_update(
+                    widget,
+                    layout,
+                    self.overlays.accessibility(state),
+                    state,
+                    &self.info,
+                    &self.title,
+                )
+            });
+        }
+
+        if self.inspector.take_dump() {
+            log::info!(
+                "Widget tree of {}:\n{}",
+                self.title,
+                inspector::dump_tree(widget, &self.layout, state)
+            );
+        }
+
+        widget_update
+    }
+
+    /// Render the given widget tree, the overlays, the inspector and the diagnostics into the given scene.
+    ///
+    /// The scene is not cleared before.
+    pub(crate) fn render(
+        &mut self,
+        scene: &mut Scene,
+        widget: &mut dyn Widget<S>,
+        state: &S,
+        theme: &mut dyn Theme,
+        timings: &mut FrameTimings,
+    ) {
+        let start = Instant::now();
+
+        widget.render(
+            scene,
+            theme,
+            &self.info,
+            self.layout.layout().unwrap(),
+            state,
+        );
+
+        // overlays are rendered above the widget tree
+        self.overlays.render(scene, theme, &self.info, state);
+
+        if self.inspector.is_enabled() {
+            self.inspector
+                .render(scene, widget, &self.layout, state, &self.info, self.size);
+        }
+
+        if self.show_diagnostics {
+            diagnostics::draw_overlay(scene, &self.info.diagnostics, &self.info.font_context);
+        }
+
+        timings.render += start.elapsed();
+    }
+
+    /// Finish the frame with the given timings and reset the input and update flags for the next frame.
+    ///
+    /// Returns the time of the next update, that was requested by widgets.
+    pub(crate) fn finish(&mut self, timings: FrameTimings) -> Option<Instant> {
+        self.profiler.record(timings, self.layout.node_count());
+        self.info.diagnostics = self.profiler.diagnostics();
+
+        self.info.reset();
+        self.update = Update::empty();
+
+        self.info.scheduler.take_deadline()
+    }
+}
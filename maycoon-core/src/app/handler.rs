@@ -1,354 +1,588 @@
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use accesskit_winit::Adapter;
 use nalgebra::Vector2;
-use taffy::{
-    AvailableSpace, Dimension, NodeId, PrintTree, Size, Style, TaffyResult, TaffyTree,
-    TraversePartialTree,
-};
+use vello::kurbo::Rect;
 use vello::util::{RenderContext, RenderSurface};
 use vello::{AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
 use winit::application::ApplicationHandler;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::WindowEvent;
 use winit::event_loop::ActiveEventLoop;
-use winit::window::{Window, WindowAttributes, WindowId};
+use winit::window::{Window, WindowId};
 
-use maycoon_theme::theme::Theme;
+use maycoon_theme::theme::{ColorScheme, Theme};
 
+use crate::app::accessibility::AccessAction;
+use crate::app::diagnostics::FrameTimings;
 use crate::app::font_ctx::FontContext;
+use crate::app::frame::WindowFrame;
 use crate::app::info::AppInfo;
+use crate::app::reload::HotReload;
+use crate::app::scheduler;
+use crate::app::tasks::{TaskQueue, Tasks};
 use crate::app::update::Update;
-use crate::config::MayConfig;
-use crate::layout::{LayoutNode, StyleNode};
+use crate::app::windows::{WindowKey, WindowRequest, Windows};
+use crate::config::{MayConfig, WindowConfig};
 use crate::state::State;
 use crate::widget::Widget;
 
+/// The frame time of the first update after the window was idle (one frame at 60 FPS).
+const IDLE_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// A user event of the application event loop.
+#[derive(Debug)]
+pub enum AppEvent {
+    /// New messages or timers of the [Tasks] handle are available.
+    Wake,
+    /// An event of the accessibility adapter of a window.
+    Access(accesskit_winit::Event),
+}
+
+impl From<accesskit_winit::Event> for AppEvent {
+    fn from(event: accesskit_winit::Event) -> Self {
+        Self::Access(event)
+    }
+}
+
 /// The core application handler. You should use [MayApp](crate::app::MayApp) instead for running applications.
-pub struct AppHandler<'a, T, W, S>
+///
+/// Manages the main window and all additional windows opened using [Windows].
+pub struct AppHandler<'a, T, S>
 where
     T: Theme,
-    W: Widget<S>,
     S: State,
 {
     config: MayConfig<T>,
-    attrs: WindowAttributes,
-    window: Option<Arc<Window>>,
-    renderer: Option<Renderer>,
-    scene: Scene,
-    surface: Option<RenderSurface<'a>>,
-    taffy: TaffyTree,
-    window_node: NodeId,
-    widget: W,
+    windows: Vec<AppWindow<'a, S>>,
+    pending: Vec<(WindowKey, WindowConfig, Box<dyn Widget<S>>)>,
+    handle: Windows,
+    tasks: Tasks,
+    queue: TaskQueue,
     state: S,
-    info: AppInfo,
+    font_context: FontContext,
+    reload: HotReload<T>,
     render_ctx: Option<RenderContext>,
-    update: Update,
-    last_update: Instant,
 }
 
-impl<'a, T, W, S> AppHandler<'a, T, W, S>
+/// A window of the application with its own root widget, layout and surface.
+struct AppWindow<'a, S: State> {
+    key: WindowKey,
+    config: WindowConfig,
+    window: Arc<Window>,
+    surface: RenderSurface<'a>,
+    renderer: Renderer,
+    scene: Scene,
+    widget: Box<dyn Widget<S>>,
+    frame: WindowFrame<S>,
+    adapter: Adapter,
+    last_frame: Instant,
+    animating: bool,
+    frame_time: Duration,
+    next_frame: Option<Instant>,
+    redraw_requested: bool,
+    ime_area: Option<Rect>,
+}
+
+impl<S: State> AppWindow<'_, S> {
+    /// Request a redraw of the window, if not already requested.
+    fn request_redraw(&mut self) {
+        if !self.redraw_requested {
+            self.redraw_requested = true;
+            self.window.request_redraw();
+        }
+    }
+
+    /// Schedule a redraw of the window at the given time.
+    fn schedule(&mut self, at: Instant) {
+        self.next_frame = Some(self.next_frame.map_or(at, |next| next.min(at)));
+    }
+}
+
+impl<'a, T, S> AppHandler<'a, T, S>
 where
     T: Theme,
-    W: Widget<S>,
     S: State,
 {
-    /// Create a new handler with given window attributes, config, widget and state.
-    pub fn new(
-        attrs: WindowAttributes,
+    /// Create a new handler with given config, main widget, state, font context, window handle and tasks.
+    ///
+    /// The main window gets created on resume.
+    pub fn new<W: Widget<S> + 'static>(
         config: MayConfig<T>,
         widget: W,
         state: S,
         font_context: FontContext,
+        windows: Windows,
+        tasks: Tasks,
+        queue: TaskQueue,
     ) -> Self {
-        let mut taffy = TaffyTree::with_capacity(16);
-
-        // gets configured on resume
-        let window_node = taffy
-            .new_leaf(Style::default())
-            .expect("Failed to create window node");
-
         Self {
-            attrs,
-            window: None,
-            renderer: None,
+            pending: vec![(
+                WindowKey::MAIN,
+                config.window.clone(),
+                Box::new(widget) as Box<dyn Widget<S>>,
+            )],
             config,
-            scene: Scene::new(),
-            surface: None,
-            taffy,
+            windows: Vec::new(),
+            handle: windows,
+            tasks,
+            queue,
             state,
-            widget,
-            info: AppInfo {
-                font_context,
-                ..Default::default()
-            },
-            window_node,
+            font_context,
+            reload: HotReload::new(),
             render_ctx: None,
-            update: Update::empty(),
-            last_update: Instant::now(),
         }
     }
 
-    /// Add the parent node and its children to the layout tree.
-    fn layout_widget(&mut self, parent: NodeId, style: &StyleNode) -> TaffyResult<()> {
-        let node = self.taffy.new_leaf(style.style.clone().into())?;
+    /// Reload the theme and fonts of the app, when their files change.
+    pub fn with_hot_reload(mut self, reload: HotReload<T>) -> Self {
+        self.reload = reload;
+        self
+    }
 
-        self.taffy.add_child(parent, node)?;
+    /// Create a window with the given key, config and root widget.
+    fn create_window(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        key: WindowKey,
+        config: WindowConfig,
+        widget: Box<dyn Widget<S>>,
+    ) {
+        let render_ctx = self.render_ctx.get_or_insert_with(RenderContext::new);
+
+        // the accessibility adapter must be created before the window is shown
+        let window = Arc::new(
+            event_loop
+                .create_window(config.attributes().with_visible(false))
+                .expect("Failed to create window"),
+        );
 
-        for child in &style.children {
-            self.layout_widget(node, child)?;
+        let adapter = Adapter::with_event_loop_proxy(
+            event_loop,
+            &window,
+            self.tasks.proxy().expect("Event loop proxy not set"),
+        );
+
+        window.set_visible(config.visible);
+
+        if self.config.follow_system_theme {
+            if let Some(theme) = window.theme() {
+                self.config.theme.set_color_scheme(color_scheme(theme));
+            }
         }
 
-        Ok(())
-    }
+        let size = window.inner_size();
+
+        // pace continuous updates with the refresh rate of the monitor
+        let frame_time = window
+            .current_monitor()
+            .and_then(|monitor| monitor.refresh_rate_millihertz())
+            .map_or(IDLE_FRAME_TIME, |rate| {
+                Duration::from_secs_f64(1000.0 / rate as f64)
+            });
+
+        let surface = futures_lite::future::block_on(async {
+            render_ctx
+                .create_surface(
+                    window.clone(),
+                    size.width,
+                    size.height,
+                    self.config.render.present_mode,
+                )
+                .await
+        })
+        .expect("Failed to create surface");
+
+        // use the device, that was selected for the surface
+        let device_handle = &render_ctx.devices[surface.dev_id];
+
+        let renderer = Renderer::new(
+            &device_handle.device,
+            RendererOptions {
+                surface_format: Some(surface.format),
+                use_cpu: self.config.render.cpu,
+                antialiasing_support: match self.config.render.antialiasing {
+                    AaConfig::Area => AaSupport::area_only(),
+
+                    AaConfig::Msaa8 => AaSupport {
+                        area: false,
+                        msaa8: true,
+                        msaa16: false,
+                    },
 
-    /// Compute the layout of the root node and its children.
-    fn compute_layout(&mut self) -> TaffyResult<()> {
-        self.taffy.compute_layout(
-            self.window_node,
-            taffy::Size::<AvailableSpace> {
-                width: AvailableSpace::Definite(
-                    self.window.as_ref().unwrap().inner_size().width as f32,
-                ),
-                height: AvailableSpace::Definite(
-                    self.window.as_ref().unwrap().inner_size().height as f32,
-                ),
+                    AaConfig::Msaa16 => AaSupport {
+                        area: false,
+                        msaa8: false,
+                        msaa16: true,
+                    },
+                },
+                num_init_threads: None,
             },
-        )?;
-        Ok(())
+        )
+        .expect("Failed to create renderer");
+
+        let mut info = AppInfo::new(self.font_context.clone());
+
+        info.windows = self.handle.clone();
+        info.tasks = self.tasks.clone();
+        info.window = key;
+
+        self.handle.set_open(key, true);
+
+        let mut frame = WindowFrame::new(
+            &config.title,
+            Vector2::new(size.width as f32, size.height as f32),
+            info,
+            self.config.render.inspector,
+        );
+
+        frame.show_diagnostics = self.config.render.show_diagnostics;
+
+        self.windows.push(AppWindow {
+            key,
+            config,
+            window,
+            surface,
+            renderer,
+            scene: Scene::new(),
+            widget,
+            frame,
+            adapter,
+            last_frame: Instant::now(),
+            animating: false,
+            frame_time,
+            next_frame: None,
+            redraw_requested: false,
+            ime_area: None,
+        });
     }
 
-    /// Collection the computed layout of the given node and its children. Make sure to call [AppHandler::compute_layout] before, to not get dirty results.
-    fn collect_layout(&mut self, node: NodeId, style: &StyleNode) -> TaffyResult<LayoutNode> {
-        let mut children = Vec::with_capacity(style.children.len());
+    /// Close the window with the given key. Closing the main window exits the application.
+    fn close_window(&mut self, event_loop: &ActiveEventLoop, key: WindowKey) {
+        if key == WindowKey::MAIN {
+            if let Some(render_ctx) = self.render_ctx.as_mut() {
+                for handle in &render_ctx.devices {
+                    handle.device.destroy();
+                }
+            }
 
-        for (i, child) in style.children.iter().enumerate() {
-            children.push(self.collect_layout(self.taffy.child_at_index(node, i)?, child)?);
+            event_loop.exit();
+        } else {
+            self.windows.retain(|window| window.key != key);
+            self.pending.retain(|(k, _, _)| *k != key);
+            self.handle.set_open(key, false);
         }
+    }
 
-        Ok(LayoutNode {
-            layout: *self.taffy.get_final_layout(node),
-            children,
-        })
+    /// Open and close windows as requested using the [Windows] handle.
+    fn process_requests(&mut self, event_loop: &ActiveEventLoop) {
+        for request in self.handle.take_requests() {
+            match request {
+                WindowRequest::Open(key, config, widget) => {
+                    let widget = widget.into_widget();
+
+                    // windows can only be created while the app is not suspended
+                    if self.render_ctx.is_some() {
+                        self.create_window(event_loop, key, *config, widget);
+                    } else {
+                        self.pending.push((key, *config, widget));
+                    }
+                },
+
+                WindowRequest::Close(key) => self.close_window(event_loop, key),
+            }
+        }
     }
 
-    /// Request a window redraw.
-    fn request_redraw(&self) {
-        if let Some(window) = self.window.as_ref() {
-            window.request_redraw();
+    /// Run the posted messages and due timers and update all windows, if the state changed.
+    fn run_tasks(&mut self) {
+        let update = self.queue.run(&mut self.state);
+
+        if !update.is_empty() {
+            for window in &mut self.windows {
+                window.frame.update.insert(update | Update::DRAW);
+                window.request_redraw();
+            }
         }
     }
 
-    /// Update the app and process events.
-    fn update(&mut self, _: &ActiveEventLoop) {
-        // completely layout widgets if taffy is not set up yet (e.g. during first update)
-        if self.taffy.child_count(self.window_node) == 0 {
-            let style = self.widget.layout_style(&self.state);
+    /// Swap the theme and fonts, if their files changed, and fully update all windows.
+    fn hot_reload(&mut self) {
+        let reloaded = self.reload.poll(Instant::now());
+
+        for err in &reloaded.errors {
+            log::error!("{}", err);
+        }
+
+        if reloaded.is_empty() {
+            return;
+        }
+
+        if let Some(theme) = reloaded.theme {
+            self.config.theme = theme;
+
+            // keep the color scheme of the system
+            if self.config.follow_system_theme {
+                if let Some(theme) = self
+                    .windows
+                    .first()
+                    .and_then(|window| window.window.theme())
+                {
+                    self.config.theme.set_color_scheme(color_scheme(theme));
+                }
+            }
+        }
 
-            self.layout_widget(self.window_node, &style)
-                .expect("Failed to layout window");
+        let fonts_changed = !reloaded.fonts.is_empty();
 
-            self.compute_layout().expect("Failed to compute layout");
+        for (name, font) in reloaded.fonts {
+            for window in &mut self.windows {
+                window.frame.info.font_context.insert(&name, font.clone());
+            }
 
-            self.update.insert(Update::FORCE);
+            self.font_context.insert(name, font);
         }
 
-        let style = self.widget.layout_style(&self.state);
+        for window in &mut self.windows {
+            // all text needs to be measured again with the new fonts
+            if fonts_changed {
+                window
+                    .frame
+                    .layout
+                    .clear()
+                    .expect("Failed to clear layout tree");
+            }
 
-        let mut layout_node = self
-            .collect_layout(
-                self.taffy.child_at_index(self.window_node, 0).unwrap(),
-                &style,
-            )
-            .expect("Failed to collect layout");
+            window.frame.update.insert(Update::FORCE);
+            window.request_redraw();
+        }
+    }
 
-        // update call to check if app should re-evaluate
-        self.update.insert(
-            self.widget
-                .update(&layout_node, &mut self.state, &self.info),
-        );
+    /// Update the window with the given index and process events.
+    fn update(&mut self, index: usize, event_loop: &ActiveEventLoop) {
+        let window = &mut self.windows[index];
 
-        // check if app should re-evaluate layout
-        if self.update.intersects(Update::LAYOUT | Update::FORCE) {
-            // clear all nodes (except root window node)
-            self.taffy
-                .set_children(self.window_node, &[])
-                .expect("Failed to set children");
+        window.redraw_requested = false;
 
-            let style = self.widget.layout_style(&self.state);
+        let mut timings = FrameTimings::default();
 
-            self.layout_widget(self.window_node, &style)
-                .expect("Failed to layout window");
+        // advance the frame clock, but don't count the time, in which the window was idle
+        let now = Instant::now();
 
-            self.compute_layout().expect("Failed to compute layout");
+        window.frame.info.delta_time = if window.animating {
+            now - window.last_frame
+        } else {
+            (now - window.last_frame).min(IDLE_FRAME_TIME)
+        };
 
-            layout_node = self
-                .collect_layout(
-                    self.taffy.child_at_index(self.window_node, 0).unwrap(),
-                    &style,
-                )
-                .expect("Failed to collect layout");
+        window.last_frame = now;
+
+        let adapter = &mut window.adapter;
+
+        let widget_update = window.frame.update(
+            window.widget.as_mut(),
+            &mut self.state,
+            &self.config.theme,
+            &mut timings,
+            // only build the accessibility tree, if assistive technologies are active
+            |tree| adapter.update_if_active(tree),
+        );
+
+        // enable the input method only while a text input requests it
+        let ime_area = window.frame.info.input_method.area();
+
+        if ime_area != window.ime_area {
+            if ime_area.is_some() != window.ime_area.is_some() {
+                window.window.set_ime_allowed(ime_area.is_some());
+            }
+
+            if let Some(area) = ime_area {
+                window.window.set_ime_cursor_area(
+                    PhysicalPosition::new(area.x0, area.y0),
+                    PhysicalSize::new(area.width(), area.height()),
+                );
+            }
+
+            window.ime_area = ime_area;
         }
 
+        let update = window.frame.update;
+        let mut presented = false;
+
         // check if app should redraw
-        if self.update.intersects(Update::FORCE | Update::DRAW) {
+        if update.intersects(Update::FORCE | Update::DRAW) {
             // clear scene
-            self.scene.reset();
+            window.scene.reset();
 
-            self.widget.render(
-                &mut self.scene,
-                &mut self.config.theme,
-                &self.info,
-                &layout_node,
+            window.frame.render(
+                &mut window.scene,
+                window.widget.as_mut(),
                 &self.state,
+                &mut self.config.theme,
+                &mut timings,
             );
 
-            let renderer = self.renderer.as_mut().expect("Renderer not initialized");
+            let start = Instant::now();
+
             let render_ctx = self
                 .render_ctx
                 .as_ref()
                 .expect("Render context not initialized");
-            let surface = self.surface.as_ref().expect("Surface not initialized");
-            let window = self.window.as_ref().expect("Window not initialized");
 
-            let device_handle = render_ctx.devices.first().expect("No devices available");
+            let device_handle = &render_ctx.devices[window.surface.dev_id];
+            let size = window.window.inner_size();
 
             // check surface validity
-            if window.inner_size().width != 0 && window.inner_size().height != 0 {
-                let surface_texture = surface
+            if size.width != 0 && size.height != 0 {
+                let surface_texture = window
+                    .surface
                     .surface
                     .get_current_texture()
                     .expect("Failed to get surface texture");
 
                 // make sure winit knows that the surface texture is ready to be presented
-                window.pre_present_notify();
+                window.window.pre_present_notify();
 
                 // TODO: this panics if canvas didn't change (no operation was done) in debug mode
-                renderer
+                window
+                    .renderer
                     .render_to_surface(
                         &device_handle.device,
                         &device_handle.queue,
-                        &self.scene,
+                        &window.scene,
                         &surface_texture,
                         &RenderParams {
                             base_color: self.config.theme.window_background(),
-                            width: window.inner_size().width,
-                            height: window.inner_size().height,
+                            width: size.width,
+                            height: size.height,
                             antialiasing_method: self.config.render.antialiasing,
                         },
                     )
                     .expect("Failed to render to surface");
 
                 surface_texture.present();
+
+                presented = true;
+            }
+
+            timings.submit = start.elapsed();
+
+            if presented {
+                window
+                    .frame
+                    .profiler
+                    .record_frame(&window.scene, timings.total());
             }
         }
 
+        // wake up for updates, that were requested by widgets
+        if let Some(deadline) = window.frame.finish(timings) {
+            window.schedule(deadline);
+        }
+
         // check if app should re-evaluate
-        if self.update.intersects(Update::EVAL | Update::FORCE) {
-            if let Some(window) = self.window.as_ref() {
+        window.animating = update.contains(Update::EVAL);
+
+        if window.animating {
+            if presented && self.config.render.is_vsync() {
+                // presenting already waited for the next frame
                 window.request_redraw();
+            } else {
+                let next = window.last_frame + window.frame_time;
+
+                window.schedule(next);
             }
         }
 
-        // reset AppInfo and update states
-        self.info.reset();
-        self.update = Update::empty();
-
-        // update diagnostics
-        if self.last_update.elapsed() >= Duration::from_secs(1) {
-            self.last_update = Instant::now();
+        // all windows share the same state, so the other windows redraw, if it may have changed
+        if widget_update.intersects(Update::EVAL | Update::LAYOUT | Update::FORCE) {
+            let shared = Update::DRAW | (widget_update & (Update::LAYOUT | Update::FORCE));
 
-            // calc avg updates per sec through updates per sec NOW divided by 2
-            self.info.diagnostics.updates_per_sec =
-                (self.info.diagnostics.updates_per_sec + self.info.diagnostics.updates) / 2;
-
-            // reset current updates per seconds
-            self.info.diagnostics.updates = 0;
-        } else {
-            // increase updates per sec NOW by 1
-            self.info.diagnostics.updates += 1;
+            for (i, other) in self.windows.iter_mut().enumerate() {
+                if i != index {
+                    other.frame.update.insert(shared);
+                    other.request_redraw();
+                }
+            }
         }
+
+        self.process_requests(event_loop);
     }
 }
 
-impl<'a, T, W, S> ApplicationHandler for AppHandler<'a, T, W, S>
+impl<'a, T, S> ApplicationHandler<AppEvent> for AppHandler<'a, T, S>
 where
     T: Theme,
-    W: Widget<S>,
     S: State,
 {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let mut render_ctx = RenderContext::new();
+        self.render_ctx = Some(RenderContext::new());
 
-        self.window = Some(Arc::new(
-            event_loop
-                .create_window(self.attrs.clone())
-                .expect("Failed to create window"),
-        ));
-
-        self.taffy
-            .set_style(
-                self.window_node,
-                Style {
-                    size: Size::<Dimension> {
-                        width: Dimension::Length(
-                            self.window.as_ref().unwrap().inner_size().width as f32,
-                        ),
-                        height: Dimension::Length(
-                            self.window.as_ref().unwrap().inner_size().height as f32,
-                        ),
-                    },
-                    ..Default::default()
-                },
-            )
-            .expect("Failed to set window node style");
-
-        self.surface = Some(
-            futures_lite::future::block_on(async {
-                render_ctx
-                    .create_surface(
-                        self.window.clone().unwrap(),
-                        self.window.as_ref().unwrap().inner_size().width,
-                        self.window.as_ref().unwrap().inner_size().height,
-                        self.config.render.present_mode,
-                    )
-                    .await
-            })
-            .expect("Failed to create surface"),
-        );
+        // create the main window and all windows, that were requested while suspended
+        for (key, config, widget) in std::mem::take(&mut self.pending) {
+            self.create_window(event_loop, key, config, widget);
+        }
 
-        // select first device available
-        // TODO: support device filters
-        let device_handle = render_ctx.devices.first().expect("Failed to select device");
-
-        self.renderer = Some(
-            Renderer::new(
-                &device_handle.device,
-                RendererOptions {
-                    surface_format: Some(self.surface.as_ref().unwrap().format),
-                    use_cpu: self.config.render.cpu,
-                    antialiasing_support: match self.config.render.antialiasing {
-                        AaConfig::Area => AaSupport::area_only(),
-
-                        AaConfig::Msaa8 => AaSupport {
-                            area: false,
-                            msaa8: true,
-                            msaa16: false,
-                        },
+        self.process_requests(event_loop);
+    }
 
-                        AaConfig::Msaa16 => AaSupport {
-                            area: false,
-                            msaa8: false,
-                            msaa16: true,
-                        },
+    fn user_event(&mut self, _: &ActiveEventLoop, event: AppEvent) {
+        match event {
+            // new messages or timers are available
+            AppEvent::Wake => self.run_tasks(),
+
+            AppEvent::Access(event) => {
+                let Some(window) = self
+                    .windows
+                    .iter_mut()
+                    .find(|window| window.window.id() == event.window_id)
+                else {
+                    return;
+                };
+
+                match event.window_event {
+                    accesskit_winit::WindowEvent::InitialTreeRequested => {
+                        window.frame.access_requested = true;
+                        window.request_redraw();
                     },
-                    num_init_threads: None,
+
+                    accesskit_winit::WindowEvent::ActionRequested(request) => {
+                        if let Some(action) = AccessAction::from_request(&request) {
+                            window.frame.info.access_actions.push(action);
+                            window.request_redraw();
+                        }
+                    },
+
+                    accesskit_winit::WindowEvent::AccessibilityDeactivated => (),
+                }
+            },
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.run_tasks();
+        self.hot_reload();
+
+        let now = Instant::now();
+
+        let mut times = vec![self.queue.deadline(), self.reload.deadline()];
+
+        // redraw the windows, that are due, and sleep until the next scheduled frame
+        for window in &mut self.windows {
+            match window.next_frame {
+                Some(at) if at <= now => {
+                    window.next_frame = None;
+                    window.request_redraw();
                 },
-            )
-            .expect("Failed to create renderer"),
-        );
 
-        self.render_ctx = Some(render_ctx);
-        self.update = Update::FORCE;
+                Some(at) => times.push(Some(at)),
+
+                None => (),
+            }
+        }
+
+        event_loop.set_control_flow(scheduler::control_flow(times));
     }
 
     fn window_event(
@@ -357,93 +591,120 @@ where
         window_id: WindowId,
         event: WindowEvent,
     ) {
-        if let Some(window) = &self.window {
-            if window.id() == window_id {
-                match event {
-                    WindowEvent::Resized(new_size) => {
-                        if new_size.width != 0 && new_size.height != 0 {
-                            if let Some(ctx) = &self.render_ctx {
-                                if let Some(surface) = &mut self.surface {
-                                    ctx.resize_surface(surface, new_size.width, new_size.height);
-                                }
-                            }
-
-                            self.taffy
-                                .set_style(
-                                    self.window_node,
-                                    Style {
-                                        size: Size::<Dimension> {
-                                            width: Dimension::Length(new_size.width as f32),
-                                            height: Dimension::Length(new_size.height as f32),
-                                        },
-                                        ..Default::default()
-                                    },
-                                )
-                                .expect("Failed to set window node style");
-
-                            self.request_redraw();
-
-                            self.update.insert(Update::DRAW | Update::LAYOUT);
-                        }
-                    },
+        let Some(index) = self
+            .windows
+            .iter()
+            .position(|window| window.window.id() == window_id)
+        else {
+            return;
+        };
+
+        let window = &mut self.windows[index];
+
+        window.adapter.process_event(&window.window, &event);
+
+        match event {
+            WindowEvent::Resized(new_size) if new_size.width != 0 && new_size.height != 0 => {
+                if let Some(ctx) = &self.render_ctx {
+                    ctx.resize_surface(&mut window.surface, new_size.width, new_size.height);
+                }
 
-                    WindowEvent::CloseRequested => {
-                        if let Some(render_ctx) = self.render_ctx.as_mut() {
-                            for handle in &render_ctx.devices {
-                                handle.device.destroy();
-                            }
-                        }
+                window
+                    .frame
+                    .resize(Vector2::new(new_size.width as f32, new_size.height as f32));
 
-                        if self.config.window.close_on_request {
-                            event_loop.exit();
-                        }
-                    },
+                window.request_redraw();
+            },
 
-                    WindowEvent::RedrawRequested => {
-                        self.update(event_loop);
-                    },
+            WindowEvent::ThemeChanged(theme) if self.config.follow_system_theme => {
+                let changed = self.config.theme.set_color_scheme(color_scheme(theme));
 
-                    WindowEvent::CursorLeft { .. } => {
-                        self.info.cursor_pos = None;
-                        self.request_redraw();
-                    },
+                // only redraw, if the theme actually changed
+                if changed {
+                    // all windows share the same theme
+                    for window in &mut self.windows {
+                        window.frame.update.insert(Update::DRAW);
+                        window.request_redraw();
+                    }
+                }
+            },
 
-                    WindowEvent::CursorMoved { position, .. } => {
-                        self.info.cursor_pos = Some(Vector2::new(position.x, position.y));
-                        self.request_redraw();
-                    },
+            WindowEvent::CloseRequested if window.config.close_on_request => {
+                let key = window.key;
 
-                    WindowEvent::KeyboardInput {
-                        event,
-                        device_id,
-                        is_synthetic,
-                    } => {
-                        if !is_synthetic {
-                            self.info.keys.push((device_id, event));
-                            self.request_redraw();
-                        }
-                    },
+                self.close_window(event_loop, key);
+            },
 
-                    WindowEvent::MouseInput {
-                        device_id,
-                        button,
-                        state,
-                    } => {
-                        self.info.buttons.push((device_id, button, state));
-                        self.request_redraw();
-                    },
+            WindowEvent::RedrawRequested => {
+                self.update(index, event_loop);
+            },
 
-                    _ => (),
-                }
-            }
+            WindowEvent::CursorLeft { .. } => {
+                window.frame.info.cursor_pos = None;
+                window.request_redraw();
+            },
+
+            WindowEvent::CursorMoved { position, .. } => {
+                window.frame.info.cursor_pos = Some(Vector2::new(position.x, position.y));
+                window.request_redraw();
+            },
+
+            WindowEvent::KeyboardInput {
+                event,
+                device_id,
+                is_synthetic,
+            } if !is_synthetic => {
+                window.frame.info.keys.push((device_id, event.into()));
+                window.request_redraw();
+            },
+
+            WindowEvent::Ime(ime) => {
+                window.frame.info.ime.push(ime);
+                window.request_redraw();
+            },
+
+            WindowEvent::ModifiersChanged(modifiers) => {
+                window.frame.info.modifiers = modifiers.state();
+            },
+
+            WindowEvent::MouseInput {
+                device_id,
+                button,
+                state,
+            } => {
+                window.frame.info.buttons.push((device_id, button, state));
+                window.request_redraw();
+            },
+
+            WindowEvent::MouseWheel {
+                device_id,
+                delta,
+                phase,
+            } => {
+                window.frame.info.wheel.push((device_id, delta, phase));
+                window.request_redraw();
+            },
+
+            _ => (),
         }
     }
 
     fn suspended(&mut self, _: &ActiveEventLoop) {
-        self.window = None;
-        self.surface = None;
+        // keep the root widgets, so the windows can be created again on resume
+        for window in self.windows.drain(..) {
+            self.handle.set_open(window.key, false);
+            self.pending
+                .push((window.key, window.config, window.widget));
+        }
+
         self.render_ctx = None;
-        self.renderer = None;
-        self.info.reset();
+    }
+}
+
+/// Returns the color scheme of the given window theme.
+fn color_scheme(theme: winit::window::Theme) -> ColorScheme {
+    match theme {
+        winit::window::Theme::Light => ColorScheme::Light,
+        winit::window::Theme::Dark => ColorScheme::Dark,
     }
 }
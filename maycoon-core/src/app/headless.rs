@@ -0,0 +1,540 @@
+use std::time::{Duration, Instant};
+
+use accesskit::{ActionRequest, TreeUpdate};
+use nalgebra::Vector2;
+use taffy::TaffyResult;
+use vello::Scene;
+use winit::event::{DeviceId, ElementState, Ime, MouseButton, MouseScrollDelta, TouchPhase};
+use winit::event_loop::ControlFlow;
+use winit::keyboard::{Key, ModifiersState};
+
+use maycoon_theme::theme::{ColorScheme, Theme};
+
+use crate::app::accessibility;
+use crate::app::accessibility::AccessAction;
+use crate::app::diagnostics::FrameTimings;
+use crate::app::font_ctx::FontContext;
+use crate::app::frame::WindowFrame;
+use crate::app::info::{AppInfo, KeyInput};
+use crate::app::inspector;
+use crate::app::offscreen::{OffscreenError, OffscreenRenderer, RgbaImage};
+use crate::app::reload::{HotReload, ReloadError};
+use crate::app::scheduler;
+use crate::app::tasks;
+use crate::app::tasks::TaskQueue;
+use crate::app::update::Update;
+use crate::app::windows::{WindowKey, WindowRequest};
+use crate::config::WindowConfig;
+use crate::layout::LayoutNode;
+use crate::state::State;
+use crate::widget::Widget;
+
+/// A headless application, that drives a widget tree without a window or GPU.
+///
+/// Useful for testing widgets: inject input events (cursor movement, mouse buttons and keys),
+/// step the app using [HeadlessApp::update] and assert on the resulting state and layout.
+///
+/// Like a real window, the root widget gets laid out inside a "window" node of the given size.
+/// Input events are collected until the next [HeadlessApp::update] call and then passed to the widget tree.
+/// Messages and due timers of the [Tasks] handle (see [AppInfo::tasks]) also run at the start of every update.
+/// Windows opened and closed using the [Windows] handle (see [AppInfo::windows]) are tracked after every update,
+/// but only the main window is laid out and updated.
+///
+/// [Tasks]: crate::app::tasks::Tasks
+/// [Windows]: crate::app::windows::Windows
+///
+/// ```
+/// use maycoon_core::app::headless::HeadlessApp;
+/// use maycoon_core::app::update::Update;
+/// use maycoon_core::layout::{LayoutNode, LayoutStyle, StyleNode};
+/// use maycoon_core::state::State;
+/// use maycoon_core::widget::Widget;
+/// use maycoon_core::app::info::AppInfo;
+/// use maycoon_core::window::MouseButton;
+/// use maycoon_theme::id::WidgetId;
+/// use maycoon_theme::theme::celeste::CelesteTheme;
+/// use maycoon_theme::theme::Theme;
+/// use nalgebra::Vector2;
+/// # use vello::Scene;
+///
+/// struct Clicks(u32);
+///
+/// impl State for Clicks {}
+///
+/// struct ClickArea;
+///
+/// impl Widget<Clicks> for ClickArea {
+///     fn render(&mut self, _: &mut Scene, _: &mut dyn Theme, _: &AppInfo, _: &LayoutNode, _: &Clicks) {}
+///
+///     fn layout_style(&mut self, _: &Clicks) -> StyleNode {
+///         StyleNode {
+///             style: LayoutStyle::default(),
+///             children: Vec::new(),
+///             key: None,
+///             measure: None,
+///         }
+///     }
+///
+///     fn update(&mut self, _: &LayoutNode, state: &mut Clicks, info: &AppInfo) -> Update {
+///         state.0 += info.buttons.len() as u32;
+///         Update::empty()
+///     }
+///
+///     fn widget_id(&mut self) -> WidgetId {
+///         WidgetId::new("example", "ClickArea")
+///     }
+/// }
+///
+/// let mut app = HeadlessApp::new(CelesteTheme::light(), ClickArea, Clicks(0), Vector2::new(100.0, 100.0));
+///
+/// app.click(Vector2::new(50.0, 50.0), MouseButton::Left);
+///
+/// assert_eq!(app.state().0, 2);
+/// assert_eq!(app.layout().layout.size.height, 100.0);
+/// ```
+pub struct HeadlessApp<T, W, S>
+where
+    T: Theme,
+    W: Widget<S>,
+    S: State,
+{
+    theme: T,
+    widget: W,
+    frame: WindowFrame<S>,
+    queue: TaskQueue,
+    state: S,
+    access: Option<TreeUpdate>,
+    device: DeviceId,
+    windows: Vec<(WindowKey, WindowConfig, Box<dyn Widget<S>>)>,
+    exited: bool,
+    next_frame: Option<Instant>,
+    color_scheme: Option<ColorScheme>,
+}
+
+impl<T, W, S> HeadlessApp<T, W, S>
+where
+    T: Theme,
+    W: Widget<S>,
+    S: State,
+{
+    /// Create a new headless app with the given theme, root widget, state and window size.
+    ///
+    /// Uses the default [FontContext]. Use [HeadlessApp::with_font_context] to use a custom one.
+    pub fn new(theme: T, widget: W, state: S, size: Vector2<f32>) -> Self {
+        Self::with_font_context(theme, widget, state, size, FontContext::default())
+    }
+
+    /// Create a new headless app with the given theme, root widget, state, window size and font context.
+    pub fn with_font_context(
+        theme: T,
+        widget: W,
+        state: S,
+        size: Vector2<f32>,
+        font_context: FontContext,
+    ) -> Self {
+        let (tasks, queue) = tasks::channel();
+        let mut info = AppInfo::new(font_context);
+
+        info.tasks = tasks;
+        info.windows.set_open(WindowKey::MAIN, true);
+
+        Self {
+            theme,
+            widget,
+            frame: WindowFrame::new("", size, info, true),
+            queue,
+            state,
+            access: None,
+            device: DeviceId::dummy(),
+            windows: Vec::new(),
+            exited: false,
+            next_frame: None,
+            color_scheme: None,
+        }
+    }
+
+    /// Draw the [Diagnostics](crate::app::diagnostics::Diagnostics) on top of the rendered content,
+    /// like a window does with [RenderConfig::show_diagnostics](crate::config::RenderConfig::show_diagnostics).
+    pub fn with_diagnostics(mut self, show: bool) -> Self {
+        self.frame.show_diagnostics = show;
+        self
+    }
+
+    /// Resize the virtual window. The layout is re-computed on the next update.
+    pub fn resize(&mut self, size: Vector2<f32>) {
+        self.frame.resize(size);
+    }
+
+    /// Move the cursor to the given position inside the window.
+    pub fn move_cursor(&mut self, pos: Vector2<f64>) {
+        self.frame.info.cursor_pos = Some(pos);
+    }
+
+    /// Let the cursor leave the window.
+    pub fn leave_cursor(&mut self) {
+        self.frame.info.cursor_pos = None;
+    }
+
+    /// Inject a mouse button event.
+    pub fn mouse_input(&mut self, button: MouseButton, state: ElementState) {
+        self.frame.info.buttons.push((self.device, button, state));
+    }
+
+    /// Inject a mouse wheel or touchpad scroll event.
+    pub fn mouse_wheel(&mut self, delta: MouseScrollDelta) {
+        self.frame
+            .info
+            .wheel
+            .push((self.device, delta, TouchPhase::Moved));
+    }
+
+    /// Set the currently pressed modifier keys.
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.frame.info.modifiers = modifiers;
+    }
+
+    /// Inject a key event.
+    pub fn key_input(&mut self, key: KeyInput) {
+        self.frame.info.keys.push((self.device, key));
+    }
+
+    /// Inject an input method (IME) event.
+    pub fn ime_input(&mut self, ime: Ime) {
+        self.frame.info.ime.push(ime);
+    }
+
+    /// Inject a key press and release of the given logical key and update the app in between.
+    pub fn press_key(&mut self, key: Key) -> Update {
+        self.key_input(KeyInput::new(key.clone(), ElementState::Pressed));
+        let update = self.update();
+
+        self.key_input(KeyInput::new(key, ElementState::Released));
+        update | self.update()
+    }
+
+    /// Inject a key press for every character of the given text and update the app once.
+    pub fn type_text(&mut self, text: &str) -> Update {
+        for c in text.chars() {
+            self.key_input(KeyInput::new(
+                Key::Character(c.to_string().into()),
+                ElementState::Pressed,
+            ));
+        }
+
+        self.update()
+    }
+
+    /// Move the cursor to the given position and click the given mouse button.
+    ///
+    /// This updates the app three times: After moving the cursor, pressing and releasing the button.
+    pub fn click(&mut self, pos: Vector2<f64>, button: MouseButton) -> Update {
+        self.move_cursor(pos);
+        let mut update = self.update();
+
+        self.mouse_input(button, ElementState::Pressed);
+        update |= self.update();
+
+        self.mouse_input(button, ElementState::Released);
+        update | self.update()
+    }
+
+    /// Let the given time pass and update the app, like a window would do during an animation.
+    ///
+    /// The frame time of the update (see [AppInfo::delta_time]) is the given duration.
+    pub fn advance(&mut self, delta: Duration) -> Update {
+        self.frame.info.delta_time = delta;
+
+        let update = self.update();
+
+        self.frame.info.delta_time = Duration::ZERO;
+
+        update
+    }
+
+    /// Update the app and process all injected events, like a window would do on a redraw request.
+    ///
+    /// Runs the same frame pipeline as a real window, except for rendering (see [HeadlessApp::render]).
+    /// The frame time of the update is zero. Use [HeadlessApp::advance] to advance animations.
+    ///
+    /// Returns the update flags of the frame, including the ones requested by the widget tree.
+    pub fn update(&mut self) -> Update {
+        // this is the scheduled update, if it's due
+        if self.next_frame.is_some_and(|at| at <= Instant::now()) {
+            self.next_frame = None;
+        }
+
+        // run posted messages and due timers first, like the event loop does before a redraw
+        let task_update = self.queue.run(&mut self.state);
+
+        if !task_update.is_empty() {
+            self.frame.update.insert(task_update | Update::DRAW);
+        }
+
+        let mut timings = FrameTimings::default();
+        let access = &mut self.access;
+
+        let widget_update = self.frame.update(
+            &mut self.widget,
+            &mut self.state,
+            &self.theme,
+            &mut timings,
+            |tree| *access = Some(tree()),
+        );
+
+        let update = self.frame.update | widget_update;
+
+        // wake up for updates, that were requested by widgets
+        if let Some(deadline) = self.frame.finish(timings) {
+            self.next_frame = Some(self.next_frame.map_or(deadline, |next| next.min(deadline)));
+        }
+
+        self.process_requests();
+
+        update
+    }
+
+    /// Open and close windows as requested using the [Windows](crate::app::windows::Windows) handle.
+    fn process_requests(&mut self) {
+        for request in self.frame.info.windows.take_requests() {
+            match request {
+                WindowRequest::Open(key, config, widget) => {
+                    self.windows.push((key, *config, widget.into_widget()));
+                    self.frame.info.windows.set_open(key, true);
+                },
+
+                WindowRequest::Close(key) => {
+                    if key == WindowKey::MAIN {
+                        self.exited = true;
+                    }
+
+                    self.windows.retain(|(k, _, _)| *k != key);
+                    self.frame.info.windows.set_open(key, false);
+                },
+            }
+        }
+    }
+
+    /// Compute the layout of the widget tree.
+    ///
+    /// Builds the layout tree from the root widget first, if it is not set up yet.
+    fn compute_layout(&mut self) -> TaffyResult<&LayoutNode> {
+        self.frame
+            .compute_layout(&mut self.widget, &self.state, &mut FrameTimings::default())
+    }
+
+    /// Returns the computed layout of the root widget.
+    ///
+    /// Computes the layout first, if the app was not updated yet.
+    pub fn layout(&mut self) -> &LayoutNode {
+        self.compute_layout().expect("Failed to layout window")
+    }
+
+    /// Render the current widget tree into an image of the window size, using the given offscreen renderer.
+    ///
+    /// Renders the same content as a window, including overlays, the inspector and the diagnostics, if enabled.
+    /// Computes the layout first, if the app was not updated yet.
+    pub fn render(
+        &mut self,
+        renderer: &mut OffscreenRenderer,
+    ) -> Result<RgbaImage, OffscreenError> {
+        self.compute_layout()?;
+
+        let mut scene = Scene::new();
+        let mut timings = FrameTimings::default();
+
+        self.frame.render(
+            &mut scene,
+            &mut self.widget,
+            &self.state,
+            &mut self.theme,
+            &mut timings,
+        );
+
+        // the frame includes the last update
+        let time = self.frame.profiler.diagnostics().timings.total() + timings.total();
+
+        self.frame.profiler.record_frame(&scene, time);
+        self.frame.info.diagnostics = self.frame.profiler.diagnostics();
+
+        let size = self.frame.size;
+
+        renderer.render_scene(
+            &scene,
+            self.theme.window_background(),
+            Vector2::new(size.x.ceil() as u32, size.y.ceil() as u32),
+        )
+    }
+
+    /// Print the widget tree with its layout style and computed layout into a string.
+    ///
+    /// Every line contains the [WidgetId](maycoon_theme::id::WidgetId), the computed location and size, the border and padding
+    /// and the display mode, position and size of the layout style.
+    /// The widget inspector logs the same tree when pressing `Ctrl+Shift+D`.
+    pub fn dump_tree(&mut self) -> String {
+        self.compute_layout().expect("Failed to layout window");
+
+        inspector::dump_tree(&mut self.widget, &self.frame.layout, &self.state)
+    }
+
+    /// Switch the preferred color scheme, like the operating system does when switching between light and dark mode.
+    ///
+    /// The color scheme is applied to reloaded themes as well. Returns [Update::DRAW], if the theme changed.
+    pub fn set_color_scheme(&mut self, scheme: ColorScheme) -> Update {
+        self.color_scheme = Some(scheme);
+
+        if self.theme.set_color_scheme(scheme) {
+            Update::DRAW
+        } else {
+            Update::empty()
+        }
+    }
+
+    /// Check the files watched by the given [HotReload] and apply the reloaded theme and fonts, like a window would do.
+    ///
+    /// Returns [Update::FORCE], if anything was reloaded.
+    /// Returns the errors of the changed files, that failed to load, after applying the other files.
+    pub fn reload(&mut self, reload: &mut HotReload<T>) -> Result<Update, Vec<ReloadError>> {
+        let reloaded = reload.check();
+
+        if reloaded.is_empty() {
+            return if reloaded.errors.is_empty() {
+                Ok(Update::empty())
+            } else {
+                Err(reloaded.errors)
+            };
+        }
+
+        if let Some(theme) = reloaded.theme {
+            self.theme = theme;
+
+            // keep the preferred color scheme
+            if let Some(scheme) = self.color_scheme {
+                self.theme.set_color_scheme(scheme);
+            }
+        }
+
+        if !reloaded.fonts.is_empty() {
+            for (name, font) in reloaded.fonts {
+                self.frame.info.font_context.insert(name, font);
+            }
+
+            // all text needs to be measured again with the new fonts
+            self.frame
+                .layout
+                .clear()
+                .expect("Failed to clear layout tree");
+        }
+
+        if reloaded.errors.is_empty() {
+            Ok(Update::FORCE)
+        } else {
+            Err(reloaded.errors)
+        }
+    }
+
+    /// Returns the accessibility tree, that was sent to assistive technologies during the last update, like a window would do.
+    ///
+    /// The tree is sent after every update, that changed anything. If the app was not updated yet, the tree is built now.
+    /// See [accessibility::tree_update] for more.
+    pub fn access_tree(&mut self) -> TreeUpdate {
+        if let Some(tree) = &self.access {
+            return tree.clone();
+        }
+
+        self.compute_layout().expect("Failed to layout window");
+
+        accessibility::tree_update(
+            &mut self.widget,
+            self.frame.layout.layout().unwrap(),
+            &self.state,
+            &self.frame.info,
+            "",
+        )
+    }
+
+    /// Inject an action request of assistive technologies (e.g. a screen reader).
+    ///
+    /// Requests, that don't target a focusable widget or have an unsupported action, are ignored.
+    pub fn access_action(&mut self, request: ActionRequest) {
+        if let Some(action) = AccessAction::from_request(&request) {
+            self.frame.info.access_actions.push(action);
+        }
+    }
+
+    /// Returns the size of the virtual window.
+    pub fn size(&self) -> Vector2<f32> {
+        self.frame.size
+    }
+
+    /// Returns a reference to the application state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the application state.
+    ///
+    /// If you change any layout relevant values, call [HeadlessApp::update] afterward.
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Returns a reference to the root widget.
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    /// Returns a mutable reference to the root widget.
+    pub fn widget_mut(&mut self) -> &mut W {
+        &mut self.widget
+    }
+
+    /// Returns the control flow, that the event loop would use to wait after the last update.
+    ///
+    /// The app sleeps until the earliest update requested using the [Scheduler](crate::app::scheduler::Scheduler)
+    /// or the next timer of the tasks, or until the next event, if there are none.
+    /// Continuous updates of animations (see [Update::EVAL]) are paced by the frame rate of the window and not included.
+    pub fn control_flow(&self) -> ControlFlow {
+        scheduler::control_flow([self.next_frame, self.queue.deadline()])
+    }
+
+    /// Returns the config of the additional window with the given key, if it's open.
+    pub fn window_config(&self, key: WindowKey) -> Option<&WindowConfig> {
+        self.windows
+            .iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, config, _)| config)
+    }
+
+    /// Returns the root widget of the additional window with the given key, if it's open.
+    pub fn window_widget_mut(&mut self, key: WindowKey) -> Option<&mut (dyn Widget<S> + 'static)> {
+        self.windows
+            .iter_mut()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, _, widget)| widget.as_mut())
+    }
+
+    /// Returns if the main window was closed, which exits a real application.
+    pub fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Returns a reference to the synthetic application information.
+    pub fn info(&self) -> &AppInfo {
+        &self.frame.info
+    }
+
+    /// Returns a mutable reference to the synthetic application information.
+    pub fn info_mut(&mut self) -> &mut AppInfo {
+        &mut self.frame.info
+    }
+
+    /// Returns a reference to the theme.
+    pub fn theme(&self) -> &T {
+        &self.theme
+    }
+
+    /// Returns a mutable reference to the theme.
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.theme
+    }
+}
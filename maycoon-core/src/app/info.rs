@@ -1,39 +1,187 @@
+use std::rc::Rc;
+use std::time::Duration;
+
 use nalgebra::Vector2;
-use winit::event::{DeviceId, ElementState, KeyEvent, MouseButton};
+use winit::event::{
+    DeviceId, ElementState, Ime, KeyEvent, MouseButton, MouseScrollDelta, TouchPhase,
+};
+use winit::keyboard::{Key, KeyLocation, ModifiersState, NativeKeyCode, PhysicalKey, SmolStr};
 
+use crate::app::accessibility::AccessAction;
 use crate::app::diagnostics::Diagnostics;
+use crate::app::focus::{FocusId, FocusManager};
 use crate::app::font_ctx::FontContext;
+use crate::app::input_method::InputMethod;
+use crate::app::overlay::Overlays;
+use crate::app::scheduler::Scheduler;
+use crate::app::style::StyleCascade;
+use crate::app::tasks::Tasks;
+use crate::app::windows::{WindowKey, Windows};
+use crate::layout::Layout;
 
 /// The application information container.
+///
+/// Cloning the container shares the [FocusManager], [Scheduler], [InputMethod], [StyleCascade], [Overlays], [Tasks] and [Windows], so widgets can pass modified copies
+/// (e.g. with a translated cursor position) to their children.
+#[derive(Clone)]
 pub struct AppInfo {
     /// The position of the cursor. If [None], the cursor left the window.
     pub cursor_pos: Option<Vector2<f64>>,
+    /// The position of the coordinate space of the current widget inside the window.
+    ///
+    /// Widgets, that translate their children (e.g. when scrolling), add the translation to the app info of their children.
+    /// Use [AppInfo::to_window] to convert the layout of a widget into window coordinates.
+    pub origin: Vector2<f32>,
     /// The fired key events.
-    pub keys: Vec<(DeviceId, KeyEvent)>,
+    pub keys: Vec<(DeviceId, KeyInput)>,
     /// The fired mouse button events.
     pub buttons: Vec<(DeviceId, MouseButton, ElementState)>,
+    /// The fired mouse wheel and touchpad scroll events.
+    pub wheel: Vec<(DeviceId, MouseScrollDelta, TouchPhase)>,
+    /// The fired input method (IME) events.
+    pub ime: Vec<Ime>,
+    /// The fired accessibility actions (e.g. from a screen reader) with their target widget.
+    ///
+    /// Use [AppInfo::access_actions_for] to get the actions of a focusable widget.
+    pub access_actions: Vec<(FocusId, AccessAction)>,
+    /// The currently pressed modifier keys.
+    pub modifiers: ModifiersState,
+    /// The keyboard focus manager.
+    pub focus: Rc<FocusManager>,
+    /// The scheduler for requesting future updates of the window.
+    pub scheduler: Rc<Scheduler>,
+    /// The input method of the window, which text input widgets enable while they're focused.
+    pub input_method: Rc<InputMethod>,
+    /// The inherited style values of the theme during updates.
+    ///
+    /// Use [StyleCascade::context] to read them (e.g. if the widget is disabled).
+    pub style: Rc<StyleCascade>,
     /// App Diagnostics.
     pub diagnostics: Diagnostics,
+    /// The time since the last update of the window. Use it to advance animations.
+    ///
+    /// If the window was idle before the current update, this is at most the duration of a single frame.
+    pub delta_time: Duration,
     /// The current font context.
     pub font_context: FontContext,
+    /// The handle to open and close overlays inside the current window.
+    pub overlays: Overlays,
+    /// The handle to open and close windows.
+    pub windows: Windows,
+    /// The handle to run background work and timers.
+    pub tasks: Tasks,
+    /// The key of the window, that is currently updated or rendered.
+    pub window: WindowKey,
 }
 
 impl AppInfo {
+    /// Create a new application information container with the given font context.
+    pub fn new(font_context: FontContext) -> Self {
+        Self {
+            cursor_pos: None,
+            origin: Vector2::zeros(),
+            keys: Vec::with_capacity(4),
+            buttons: Vec::with_capacity(2),
+            wheel: Vec::new(),
+            ime: Vec::new(),
+            access_actions: Vec::new(),
+            modifiers: ModifiersState::empty(),
+            focus: Rc::new(FocusManager::default()),
+            scheduler: Rc::new(Scheduler::default()),
+            input_method: Rc::new(InputMethod::default()),
+            style: Rc::new(StyleCascade::default()),
+            diagnostics: Diagnostics::default(),
+            delta_time: Duration::ZERO,
+            font_context,
+            overlays: Overlays::default(),
+            windows: Windows::default(),
+            tasks: Tasks::default(),
+            window: WindowKey::MAIN,
+        }
+    }
+
+    /// Convert the given layout of a widget from the coordinate space of the widget into window coordinates.
+    pub fn to_window(&self, layout: &Layout) -> Layout {
+        let mut layout = *layout;
+
+        layout.location.x += self.origin.x;
+        layout.location.y += self.origin.y;
+        layout
+    }
+
     /// Reset the application information for a new frame.
     pub fn reset(&mut self) {
         self.buttons.clear();
+        self.wheel.clear();
         self.keys.clear();
+        self.ime.clear();
+        self.access_actions.clear();
+    }
+
+    /// Returns the fired accessibility actions, that target the widget with the given focus id.
+    pub fn access_actions_for(&self, id: FocusId) -> impl Iterator<Item = AccessAction> + '_ {
+        self.access_actions
+            .iter()
+            .filter(move |(target, _)| *target == id)
+            .map(|(_, action)| *action)
     }
 }
 
 impl Default for AppInfo {
     fn default() -> Self {
+        Self::new(FontContext::default())
+    }
+}
+
+/// A keyboard input event.
+///
+/// Contains the same data as winit's [KeyEvent], but can also be constructed by hand (e.g. for testing).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyInput {
+    /// The physical key code of the pressed key.
+    pub physical_key: PhysicalKey,
+    /// The logical key, with keyboard layout and modifiers applied.
+    pub logical_key: Key,
+    /// The text that this key produces, if any.
+    pub text: Option<SmolStr>,
+    /// The location of the key on the keyboard.
+    pub location: KeyLocation,
+    /// If the key was pressed or released.
+    pub state: ElementState,
+    /// If this event is a repeat event, generated by holding down the key.
+    pub repeat: bool,
+}
+
+impl KeyInput {
+    /// Create a new key input from a logical key and an element state.
+    ///
+    /// The text is derived from the key, if it's a character. The physical key is unidentified.
+    pub fn new(logical_key: Key, state: ElementState) -> Self {
+        let text = match &logical_key {
+            Key::Character(c) => Some(c.clone()),
+            _ => None,
+        };
+
         Self {
-            cursor_pos: None,
-            keys: Vec::with_capacity(4),
-            buttons: Vec::with_capacity(2),
-            diagnostics: Diagnostics::default(),
-            font_context: FontContext::default(),
+            physical_key: PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+            logical_key,
+            text,
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+        }
+    }
+}
+
+impl From<KeyEvent> for KeyInput {
+    fn from(event: KeyEvent) -> Self {
+        Self {
+            physical_key: event.physical_key,
+            logical_key: event.logical_key,
+            text: event.text,
+            location: event.location,
+            state: event.state,
+            repeat: event.repeat,
         }
     }
 }
@@ -0,0 +1,42 @@
+use std::cell::Cell;
+
+use vello::kurbo::Rect;
+
+use crate::app::focus::{FocusId, FocusManager};
+
+/// Controls the input method (IME) of a window.
+///
+/// Input methods are only enabled while the focused widget requests them.
+/// Text input widgets [request](InputMethod::request) the input method in every update,
+/// together with the area of the caret in window coordinates, so the candidate window is placed next to the caret.
+/// The request only applies, if the widget is focused after the update.
+#[derive(Debug, Default)]
+pub struct InputMethod {
+    request: Cell<Option<(FocusId, Rect)>>,
+    area: Cell<Option<Rect>>,
+}
+
+impl InputMethod {
+    /// Enable the input method for the widget with the given focus id, with the caret at the given area in window coordinates.
+    ///
+    /// Must be called in every update, while the input method should stay enabled.
+    /// The input method is enabled after the update, if the widget is focused.
+    pub fn request(&self, focus: FocusId, area: Rect) {
+        self.request.set(Some((focus, area)));
+    }
+
+    /// Returns the caret area requested during the last update or [None] if the input method is disabled.
+    pub fn area(&self) -> Option<Rect> {
+        self.area.get()
+    }
+
+    /// Finish the update and apply the request of the focused widget.
+    pub(crate) fn end_update(&self, focus: &FocusManager) {
+        self.area.set(
+            self.request
+                .take()
+                .filter(|(id, _)| focus.is_focused(*id))
+                .map(|(_, area)| area),
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use std::fmt::Write;
+
+use nalgebra::Vector2;
+use peniko::{Brush, Color, Fill};
+use vello::kurbo::{Affine, BezPath, Point, Rect, RoundedRect, Shape, Stroke};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::Key;
+
+use maycoon_theme::id::WidgetId;
+
+use crate::app::info::AppInfo;
+use crate::app::update::Update;
+use crate::layout::{LayoutNode, LayoutTree, LengthPercentageAuto, TreeNode};
+use crate::state::State;
+use crate::text::{ShapedText, TextSpan};
+use crate::widget::Widget;
+
+/// The color of the margin box of the hovered node.
+const MARGIN: Color = Color::rgba8(246, 178, 107, 120);
+/// The color of the border box of the hovered node.
+const BORDER: Color = Color::rgba8(255, 229, 153, 140);
+/// The color of the padding box of the hovered node.
+const PADDING: Color = Color::rgba8(147, 196, 125, 120);
+/// The color of the content box of the hovered node.
+const CONTENT: Color = Color::rgba8(111, 168, 220, 120);
+/// The color of the outlines of all nodes.
+const OUTLINE: Color = Color::rgba8(111, 168, 220, 200);
+
+/// A node of the widget tree, as seen by the widget inspector.
+///
+/// Returned by [Widget::inspect]. The children must match the children of the [StyleNode](crate::layout::StyleNode) returned by [Widget::layout_style].
+#[derive(Clone, Debug, PartialEq)]
+pub struct InspectNode {
+    /// The id of the widget.
+    pub id: WidgetId,
+    /// The translation of the children, if the widget draws them at another position than their layout (e.g. when scrolling).
+    pub offset: Vector2<f32>,
+    /// The nodes of the child widgets.
+    pub children: Vec<InspectNode>,
+}
+
+impl InspectNode {
+    /// Create a new node of the widget with the given id without children.
+    pub fn new(id: WidgetId) -> Self {
+        Self {
+            id,
+            offset: Vector2::new(0.0, 0.0),
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the nodes of the child widgets.
+    pub fn with_children(mut self, children: Vec<InspectNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Set the translation of the children.
+    pub fn with_offset(mut self, offset: Vector2<f32>) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// The widget inspector of a window.
+///
+/// If available, `Ctrl+Shift+I` toggles the inspector, which outlines the layout of every widget
+/// and highlights the margin, border, padding and content of the hovered widget.
+/// While inspecting, `Ctrl+Shift+D` logs the widget tree with its layout (see [dump_tree]) at the info level.
+pub(crate) struct Inspector {
+    available: bool,
+    enabled: bool,
+    dump: bool,
+}
+
+impl Inspector {
+    /// Create a new disabled inspector. If not available, the inspector can't be toggled.
+    pub(crate) fn new(available: bool) -> Self {
+        Self {
+            available,
+            enabled: false,
+            dump: false,
+        }
+    }
+
+    /// Returns if the inspector is currently shown.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Handle the key chords of the inspector.
+    ///
+    /// Returns [Update::DRAW] if the inspector needs to be drawn again.
+    pub(crate) fn update(&mut self, info: &AppInfo) -> Update {
+        if !self.available {
+            return Update::empty();
+        }
+
+        let chord = |key: &str| {
+            info.modifiers.control_key()
+                && info.modifiers.shift_key()
+                && info.keys.iter().any(|(_, input)| {
+                    input.state == ElementState::Pressed
+                        && matches!(&input.logical_key, Key::Character(c) if c.eq_ignore_ascii_case(key))
+                })
+        };
+
+        let mut update = Update::empty();
+
+        if chord("i") {
+            self.enabled = !self.enabled;
+            update.insert(Update::DRAW);
+        }
+
+        if self.enabled {
+            self.dump |= chord("d");
+
+            // follow the cursor with the highlight
+            if info.cursor_pos.is_some() {
+                update.insert(Update::DRAW);
+            }
+        }
+
+        update
+    }
+
+    /// Returns if the widget tree should be logged and resets the request.
+    pub(crate) fn take_dump(&mut self) -> bool {
+        std::mem::take(&mut self.dump)
+    }
+
+    /// Draw the outlines of the given widget tree and highlight the hovered widget.
+    ///
+    /// The layout styles are taken from the given layout tree, which must be laid out.
+    pub(crate) fn render<S: State>(
+        &self,
+        scene: &mut Scene,
+        widget: &mut dyn Widget<S>,
+        layout: &LayoutTree,
+        state: &S,
+        info: &AppInfo,
+        size: Vector2<f32>,
+    ) {
+        let (Some(node), Some(layout)) = (layout.root(), layout.layout()) else {
+            return;
+        };
+
+        let tree = widget.inspect(state);
+        let mut boxes = Vec::new();
+
+        collect_boxes(
+            node,
+            layout,
+            Some(&tree),
+            Vector2::new(0.0, 0.0),
+            size.x,
+            &mut boxes,
+        );
+
+        for node in &boxes {
+            scene.stroke(
+                &Stroke::new(1.0),
+                Affine::IDENTITY,
+                OUTLINE,
+                None,
+                &node.border.inset(-0.5),
+            );
+        }
+
+        let Some(cursor) = info.cursor_pos else {
+            return;
+        };
+
+        // the last box is the top-most one
+        let Some(hovered) = boxes
+            .iter()
+            .rev()
+            .find(|node| node.border.contains(Point::new(cursor.x, cursor.y)))
+        else {
+            return;
+        };
+
+        let ring = |outer: Rect, inner: Rect| {
+            let mut path = BezPath::new();
+
+            path.extend(outer.path_elements(0.1));
+            path.extend(inner.path_elements(0.1));
+            path
+        };
+
+        scene.fill(
+            Fill::EvenOdd,
+            Affine::IDENTITY,
+            MARGIN,
+            None,
+            &ring(hovered.margin, hovered.border),
+        );
+        scene.fill(
+            Fill::EvenOdd,
+            Affine::IDENTITY,
+            BORDER,
+            None,
+            &ring(hovered.border, hovered.padding),
+        );
+        scene.fill(
+            Fill::EvenOdd,
+            Affine::IDENTITY,
+            PADDING,
+            None,
+            &ring(hovered.padding, hovered.content),
+        );
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            CONTENT,
+            None,
+            &hovered.content,
+        );
+
+        // show the widget id, size and location next to the cursor
+        let text = format!(
+            "{}\n{} × {} at ({}, {})",
+            hovered
+                .id
+                .as_ref()
+                .map_or(String::from("<unknown>"), |id| id.to_string()),
+            hovered.border.width(),
+            hovered.border.height(),
+            hovered.border.x0,
+            hovered.border.y0,
+        );
+
+        let shaped = ShapedText::new(
+            &[TextSpan::new(text)],
+            &info.font_context,
+            info.font_context.default_font(),
+            12.0,
+        );
+        let text_layout = shaped.layout(None);
+        let text_size = text_layout.size();
+
+        let width = text_size.x as f64 + 12.0;
+        let height = text_size.y as f64 + 12.0;
+        let x = (cursor.x + 12.0).min(size.x as f64 - width).max(0.0);
+        let y = if cursor.y + 16.0 + height > size.y as f64 {
+            (cursor.y - 8.0 - height).max(0.0)
+        } else {
+            cursor.y + 16.0
+        };
+
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::rgba8(0, 0, 0, 200),
+            None,
+            &RoundedRect::new(x, y, x + width, y + height, 4.0),
+        );
+
+        text_layout.draw(
+            scene,
+            Vector2::new(x as f32 + 6.0, y as f32 + 6.0),
+            true,
+            |_| Brush::Solid(Color::WHITE),
+        );
+    }
+}
+
+/// The boxes of a laid out node in window coordinates.
+struct NodeBoxes {
+    id: Option<WidgetId>,
+    margin: Rect,
+    border: Rect,
+    padding: Rect,
+    content: Rect,
+}
+
+/// Collect the boxes of the given node and its children in paint order.
+///
+/// The position of the node is relative to the given origin.
+/// The margins are resolved against the given width of the parent.
+fn collect_boxes(
+    node: &TreeNode,
+    layout: &LayoutNode,
+    tree: Option<&InspectNode>,
+    origin: Vector2<f32>,
+    parent_width: f32,
+    boxes: &mut Vec<NodeBoxes>,
+) {
+    let l = &layout.layout;
+    let resolve = |value: LengthPercentageAuto| match value {
+        LengthPercentageAuto::Length(length) => length,
+        LengthPercentageAuto::Percent(percent) => percent * parent_width,
+        LengthPercentageAuto::Auto => 0.0,
+    };
+
+    let x = (origin.x + l.location.x) as f64;
+    let y = (origin.y + l.location.y) as f64;
+    let border = Rect::new(x, y, x + l.size.width as f64, y + l.size.height as f64);
+    let padding = Rect::new(
+        border.x0 + l.border.left as f64,
+        border.y0 + l.border.top as f64,
+        border.x1 - l.border.right as f64,
+        border.y1 - l.border.bottom as f64,
+    );
+    let content = Rect::new(
+        padding.x0 + l.padding.left as f64,
+        padding.y0 + l.padding.top as f64,
+        padding.x1 - l.padding.right as f64,
+        padding.y1 - l.padding.bottom as f64,
+    );
+    let margin = Rect::new(
+        border.x0 - resolve(node.style.margin.left) as f64,
+        border.y0 - resolve(node.style.margin.top) as f64,
+        border.x1 + resolve(node.style.margin.right) as f64,
+        border.y1 + resolve(node.style.margin.bottom) as f64,
+    );
+
+    boxes.push(NodeBoxes {
+        id: tree.map(|tree| tree.id.clone()),
+        margin,
+        border,
+        padding,
+        content,
+    });
+
+    let offset = tree.map_or(Vector2::new(0.0, 0.0), |tree| tree.offset);
+    let origin = Vector2::new(x as f32, y as f32) + offset;
+
+    for (i, (node, layout)) in node.children.iter().zip(&layout.children).enumerate() {
+        let tree = tree.and_then(|tree| tree.children.get(i));
+
+        collect_boxes(node, layout, tree, origin, content.width() as f32, boxes);
+    }
+}
+
+/// Print the widget tree of the given root widget with its layout style and computed layout into a string.
+///
+/// Every line contains the [WidgetId], the computed location and size, the border and padding
+/// and the display mode, position and size of the layout style.
+///
+/// The layout styles are taken from the given layout tree, which must be laid out.
+/// The widget inspector logs this tree when pressing `Ctrl+Shift+D`.
+pub(crate) fn dump_tree<S: State>(
+    widget: &mut dyn Widget<S>,
+    layout: &LayoutTree,
+    state: &S,
+) -> String {
+    let mut out = String::new();
+
+    if let (Some(node), Some(layout)) = (layout.root(), layout.layout()) {
+        let tree = widget.inspect(state);
+
+        dump_node(node, layout, Some(&tree), "", "", &mut out);
+    }
+
+    out
+}
+
+/// Print the given node and its children.
+fn dump_node(
+    node: &TreeNode,
+    layout: &LayoutNode,
+    tree: Option<&InspectNode>,
+    prefix: &str,
+    children_prefix: &str,
+    out: &mut String,
+) {
+    let l = &layout.layout;
+
+    writeln!(
+        out,
+        "{prefix}{} [x: {}, y: {}, w: {}, h: {}, border: {} {} {} {}, padding: {} {} {} {}] \
+         (display: {:?}, position: {:?}, size: {:?} × {:?})",
+        tree.map_or(String::from("<unknown>"), |tree| tree.id.to_string()),
+        l.location.x,
+        l.location.y,
+        l.size.width,
+        l.size.height,
+        l.border.top,
+        l.border.right,
+        l.border.bottom,
+        l.border.left,
+        l.padding.top,
+        l.padding.right,
+        l.padding.bottom,
+        l.padding.left,
+        node.style.display,
+        node.style.position,
+        node.style.size.x,
+        node.style.size.y,
+    )
+    .unwrap();
+
+    let count = node.children.len().min(layout.children.len());
+
+    for (i, (node, layout)) in node.children.iter().zip(&layout.children).enumerate() {
+        let tree = tree.and_then(|tree| tree.children.get(i));
+        let last = i + 1 == count;
+
+        dump_node(
+            node,
+            layout,
+            tree,
+            &format!("{children_prefix}{}", if last { "└── " } else { "├── " }),
+            &format!("{children_prefix}{}", if last { "    " } else { "│   " }),
+            out,
+        );
+    }
+}
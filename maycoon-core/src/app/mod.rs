@@ -1,43 +1,96 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
 use peniko::Font;
-use winit::dpi::{LogicalPosition, LogicalSize, Position, Size};
-use winit::event_loop::EventLoopBuilder;
-use winit::window::WindowAttributes;
+use winit::event_loop::EventLoop;
 
 use maycoon_theme::theme::Theme;
 
 use crate::app::font_ctx::FontContext;
-use crate::app::handler::AppHandler;
+use crate::app::handler::{AppEvent, AppHandler};
+use crate::app::reload::HotReload;
+use crate::app::tasks::{TaskQueue, Tasks};
+use crate::app::windows::Windows;
 use crate::config::MayConfig;
 use crate::state::State;
 use crate::widget::Widget;
 
+/// Contains the accessibility tree for assistive technologies (e.g. screen readers).
+pub mod accessibility;
+
 /// Contains diagnostics data for the application.
 pub mod diagnostics;
 
+/// Contains the keyboard focus manager.
+pub mod focus;
+
 /// Contains the font context structure.
 pub mod font_ctx;
 
+/// Contains the [WindowFrame](frame::WindowFrame), which runs the frame pipeline of a window.
+pub(crate) mod frame;
+
 /// Contains the application handler.
 pub mod handler;
 
+/// Contains the headless application for testing widgets without a window.
+pub mod headless;
+
 /// Contains the application information structure.
 pub mod info;
 
+/// Contains the [InputMethod](input_method::InputMethod) handle for enabling input methods (IME).
+pub mod input_method;
+
+/// Contains the widget inspector for debugging layouts.
+pub mod inspector;
+
+/// Contains the offscreen renderer for rendering widgets into images.
+pub mod offscreen;
+
+/// Contains the [Overlays](overlay::Overlays) handle for popups, tooltips and modal dialogs.
+pub mod overlay;
+
+/// Contains the [HotReload](reload::HotReload) watcher for reloading theme and font files at runtime.
+pub mod reload;
+
+/// Contains the [Scheduler](scheduler::Scheduler) for requesting future updates.
+pub mod scheduler;
+
+/// Contains the [StyleCascade](style::StyleCascade) of inherited style values during updates.
+pub mod style;
+
+/// Contains the [Tasks](tasks::Tasks) handle for background work and timers.
+pub mod tasks;
+
 /// Contains the update mode bitflag.
 pub mod update;
 
+/// Contains the [Windows](windows::Windows) handle for opening and closing windows at runtime.
+pub mod windows;
+
 /// The core Application structure.
 pub struct MayApp<T: Theme> {
     config: MayConfig<T>,
     font_ctx: FontContext,
+    windows: Windows,
+    tasks: Tasks,
+    queue: TaskQueue,
+    reload: HotReload<T>,
 }
 
 impl<T: Theme> MayApp<T> {
     /// Create a new App with the given [MayConfig].
     pub fn new(config: MayConfig<T>) -> Self {
+        let (tasks, queue) = tasks::channel();
+
         Self {
             config,
             font_ctx: FontContext::default(),
+            windows: Windows::default(),
+            tasks,
+            queue,
+            reload: HotReload::new(),
         }
     }
 
@@ -47,71 +100,73 @@ impl<T: Theme> MayApp<T> {
         self
     }
 
+    /// Load a font from the given file, insert it into the font context and reload it, when the file changes.
+    ///
+    /// Panics if the font file cannot be read or doesn't contain a valid font.
+    pub fn with_font_reload(mut self, name: impl ToString, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let font = reload::load_font(&path).expect("Failed to load font");
+
+        self.font_ctx.insert(name.to_string(), font);
+        self.reload = self.reload.with_font(name, path);
+        self
+    }
+
+    /// Watch the given theme file and replace the theme of the app with the theme loaded by the given function, when the file changes.
+    ///
+    /// All windows get fully re-evaluated and redrawn with the new theme.
+    /// Meant for iterating on a theme during development, e.g. with `|path| FileTheme::load(path)` (see [FileTheme]).
+    ///
+    /// [FileTheme]: maycoon_theme::theme::file::FileTheme
+    pub fn with_theme_reload<E: Display>(
+        mut self,
+        path: impl Into<PathBuf>,
+        load: impl FnMut(&Path) -> Result<T, E> + 'static,
+    ) -> Self {
+        self.reload = self.reload.with_theme(path, load);
+        self
+    }
+
+    /// Returns the handle to open and close additional windows at runtime.
+    ///
+    /// Store it in your app state to open windows from widget callbacks.
+    pub fn windows(&self) -> Windows {
+        self.windows.clone()
+    }
+
+    /// Returns the handle to run background work and timers, that update the app state.
+    ///
+    /// The handle can be used before the app runs. Messages and timers are processed once the app started.
+    pub fn tasks(&self) -> Tasks {
+        self.tasks.clone()
+    }
+
     /// Run the application with given widget and state.
     pub fn run<S, W>(self, state: S, widget: W)
     where
         S: State,
-        W: Widget<S>,
+        W: Widget<S> + 'static,
     {
-        let event_loop = EventLoopBuilder::default()
+        let event_loop = EventLoop::<AppEvent>::with_user_event()
             .build()
             .expect("Failed to create event loop");
 
-        let mut attrs = WindowAttributes::default()
-            .with_inner_size(LogicalSize::new(
-                self.config.window.size.x,
-                self.config.window.size.y,
-            ))
-            .with_resizable(self.config.window.resizable)
-            .with_enabled_buttons(self.config.window.buttons)
-            .with_title(self.config.window.title.clone())
-            .with_maximized(self.config.window.maximized)
-            .with_visible(self.config.window.visible)
-            .with_transparent(self.config.window.transparent)
-            .with_blur(self.config.window.blur)
-            .with_decorations(self.config.window.decorations)
-            .with_window_icon(self.config.window.icon.clone())
-            .with_content_protected(self.config.window.content_protected)
-            .with_window_level(self.config.window.level)
-            .with_active(self.config.window.active)
-            .with_cursor(self.config.window.cursor.clone());
-
-        // since `with_max_inner_size()` doesn't support `Option` values, we need to manually set it
-        attrs.max_inner_size = self
-            .config
-            .window
-            .max_size
-            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
-
-        // since `with_min_inner_size()` doesn't support `Option` values, we need to manually set it
-        attrs.min_inner_size = self
-            .config
-            .window
-            .min_size
-            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
-
-        // since `with_position()` doesn't support `Option` values, we need to manually set it
-        attrs.position = self
-            .config
-            .window
-            .position
-            .map(|v| Position::Logical(LogicalPosition::new(v.x, v.y)));
-
-        // since `with_resize_increments()` doesn't support `Option` values, we need to manually set it
-        attrs.resize_increments = self
-            .config
-            .window
-            .resize_increments
-            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
+        // wake up the event loop for new messages, timers and accessibility events
+        self.tasks.set_proxy(event_loop.create_proxy());
 
         event_loop
-            .run_app(&mut AppHandler::new(
-                attrs,
-                self.config,
-                widget,
-                state,
-                self.font_ctx,
-            ))
+            .run_app(
+                &mut AppHandler::new(
+                    self.config,
+                    widget,
+                    state,
+                    self.font_ctx,
+                    self.windows,
+                    self.tasks,
+                    self.queue,
+                )
+                .with_hot_reload(self.reload),
+            )
             .expect("Failed to run event loop");
     }
 }
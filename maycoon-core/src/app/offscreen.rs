@@ -0,0 +1,365 @@
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc;
+
+use nalgebra::Vector2;
+use peniko::Color;
+use taffy::TaffyError;
+use vello::wgpu::{
+    BufferAsyncError, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Device,
+    DeviceDescriptor, Extent3d, Features, ImageCopyBuffer, ImageCopyTexture, ImageDataLayout,
+    Instance, InstanceDescriptor, Limits, Maintain, MapMode, Origin3d, PowerPreference, Queue,
+    RequestAdapterOptions, RequestDeviceError, TextureAspect, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureViewDescriptor, COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+use vello::{AaConfig, AaSupport, RenderParams, Renderer, RendererOptions, Scene};
+
+use maycoon_theme::theme::Theme;
+
+use crate::app::info::AppInfo;
+use crate::config::RenderConfig;
+use crate::layout;
+use crate::layout::LayoutNode;
+use crate::state::State;
+use crate::widget::Widget;
+
+/// Renders widgets into in-memory RGBA images, without any window or surface.
+///
+/// Useful for generating screenshots and thumbnails or for image based regression tests.
+///
+/// The renderer creates its own GPU device on creation, so re-use it for multiple renders if possible.
+/// If no hardware adapter is available (e.g. on CI machines), you can request a software/fallback adapter
+/// using [OffscreenRenderer::new].
+pub struct OffscreenRenderer {
+    device: Device,
+    queue: Queue,
+    renderer: Renderer,
+    scene: Scene,
+    antialiasing: AaConfig,
+}
+
+impl OffscreenRenderer {
+    /// Create a new offscreen renderer with the given render configuration.
+    ///
+    /// If `force_fallback_adapter` is `true`, only a software/fallback adapter (like llvmpipe or WARP) will be used.
+    ///
+    /// **NOTE:** The [RenderConfig::present_mode] is ignored, since there is no surface to present to.
+    pub fn new(
+        config: &RenderConfig,
+        force_fallback_adapter: bool,
+    ) -> Result<Self, OffscreenError> {
+        let instance = Instance::new(InstanceDescriptor::default());
+
+        let adapter =
+            futures_lite::future::block_on(instance.request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::default(),
+                force_fallback_adapter,
+                compatible_surface: None,
+            }))
+            .ok_or(OffscreenError::NoAdapter)?;
+
+        let (device, queue) = futures_lite::future::block_on(adapter.request_device(
+            &DeviceDescriptor {
+                label: Some("maycoon offscreen device"),
+                required_features: adapter.features() & Features::CLEAR_TEXTURE,
+                required_limits: Limits::default(),
+            },
+            None,
+        ))?;
+
+        let renderer = Renderer::new(
+            &device,
+            RendererOptions {
+                surface_format: None,
+                use_cpu: config.cpu,
+                antialiasing_support: match config.antialiasing {
+                    AaConfig::Area => AaSupport::area_only(),
+
+                    AaConfig::Msaa8 => AaSupport {
+                        area: false,
+                        msaa8: true,
+                        msaa16: false,
+                    },
+
+                    AaConfig::Msaa16 => AaSupport {
+                        area: false,
+                        msaa8: false,
+                        msaa16: true,
+                    },
+                },
+                num_init_threads: None,
+            },
+        )?;
+
+        Ok(Self {
+            device,
+            queue,
+            renderer,
+            scene: Scene::new(),
+            antialiasing: config.antialiasing,
+        })
+    }
+
+    /// Layout and render the given widget with the given state, theme and app info into an image of the given size.
+    ///
+    /// The widget is laid out like the root widget of a window with the given size.
+    pub fn render<S: State, W: Widget<S> + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        state: &S,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        size: Vector2<u32>,
+    ) -> Result<RgbaImage, OffscreenError> {
+        let style = widget.layout_style(state);
+
+        let layout_node = layout::compute_root_layout(&style, size.cast(), info)?;
+
+        self.render_with_layout(widget, &layout_node, state, theme, info, size)
+    }
+
+    /// Render the given widget with an already computed layout into an image of the given size.
+    pub fn render_with_layout<S: State, W: Widget<S> + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        layout_node: &LayoutNode,
+        state: &S,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        size: Vector2<u32>,
+    ) -> Result<RgbaImage, OffscreenError> {
+        let mut scene = std::mem::take(&mut self.scene);
+
+        scene.reset();
+
+        widget.render(&mut scene, theme, info, layout_node, state);
+
+        let image = self.render_scene(&scene, theme.window_background(), size);
+
+        self.scene = scene;
+
+        image
+    }
+
+    /// Rasterize the given scene with the given background color into an image of the given size.
+    pub fn render_scene(
+        &mut self,
+        scene: &Scene,
+        base_color: Color,
+        size: Vector2<u32>,
+    ) -> Result<RgbaImage, OffscreenError> {
+        let extent = Extent3d {
+            width: size.x.max(1),
+            height: size.y.max(1),
+            depth_or_array_layers: 1,
+        };
+
+        let texture = self.device.create_texture(&TextureDescriptor {
+            label: Some("maycoon offscreen texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        self.renderer.render_to_texture(
+            &self.device,
+            &self.queue,
+            scene,
+            &view,
+            &RenderParams {
+                base_color,
+                width: extent.width,
+                height: extent.height,
+                antialiasing_method: self.antialiasing,
+            },
+        )?;
+
+        // rows of the copy buffer need to be aligned
+        let row_bytes = extent.width * 4;
+        let padded_row_bytes =
+            row_bytes.div_ceil(COPY_BYTES_PER_ROW_ALIGNMENT) * COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("maycoon offscreen buffer"),
+            size: (padded_row_bytes * extent.height) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("maycoon offscreen copy"),
+            });
+
+        encoder.copy_texture_to_buffer(
+            ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            ImageCopyBuffer {
+                buffer: &buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+
+        self.device.poll(Maintain::Wait);
+
+        receiver.recv().map_err(|_| OffscreenError::Unmapped)??;
+
+        let mut data = Vec::with_capacity((row_bytes * extent.height) as usize);
+
+        for row in slice.get_mapped_range().chunks(padded_row_bytes as usize) {
+            data.extend_from_slice(&row[..row_bytes as usize]);
+        }
+
+        buffer.unmap();
+
+        Ok(RgbaImage::new(extent.width, extent.height, data))
+    }
+}
+
+/// An image with 8-bit RGBA pixels, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RgbaImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Create a new image from the given size and raw RGBA pixel data.
+    ///
+    /// Panics if the data length does not match the size.
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Self {
+        assert_eq!(
+            data.len(),
+            (width * height * 4) as usize,
+            "Image data does not match image size"
+        );
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Returns the width of the image.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the image.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the size of the image.
+    pub fn size(&self) -> Vector2<u32> {
+        Vector2::new(self.width, self.height)
+    }
+
+    /// Returns the RGBA pixel at the given position or [None] if it's out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let i = ((y * self.width + x) * 4) as usize;
+
+        Some([
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ])
+    }
+
+    /// Returns the raw RGBA pixel data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the raw RGBA pixel data and consumes the image.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// An error that occurred while rendering offscreen.
+#[derive(Debug)]
+pub enum OffscreenError {
+    /// No suitable GPU adapter was found.
+    NoAdapter,
+    /// The GPU device could not be created.
+    Device(RequestDeviceError),
+    /// The vello renderer failed.
+    Renderer(vello::Error),
+    /// The layout could not be computed.
+    Layout(TaffyError),
+    /// The rendered image could not be read back from the GPU.
+    Readback(BufferAsyncError),
+    /// The GPU dropped the read back request without an answer.
+    Unmapped,
+}
+
+impl Display for OffscreenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OffscreenError::NoAdapter => write!(f, "no suitable GPU adapter found"),
+            OffscreenError::Device(err) => write!(f, "failed to create GPU device: {}", err),
+            OffscreenError::Renderer(err) => write!(f, "failed to render scene: {}", err),
+            OffscreenError::Layout(err) => write!(f, "failed to compute layout: {}", err),
+            OffscreenError::Readback(err) => write!(f, "failed to read back image: {}", err),
+            OffscreenError::Unmapped => write!(f, "failed to read back image: buffer was dropped"),
+        }
+    }
+}
+
+impl std::error::Error for OffscreenError {}
+
+impl From<RequestDeviceError> for OffscreenError {
+    fn from(err: RequestDeviceError) -> Self {
+        Self::Device(err)
+    }
+}
+
+impl From<vello::Error> for OffscreenError {
+    fn from(err: vello::Error) -> Self {
+        Self::Renderer(err)
+    }
+}
+
+impl From<TaffyError> for OffscreenError {
+    fn from(err: TaffyError) -> Self {
+        Self::Layout(err)
+    }
+}
+
+impl From<BufferAsyncError> for OffscreenError {
+    fn from(err: BufferAsyncError) -> Self {
+        Self::Readback(err)
+    }
+}
@@ -0,0 +1,578 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::Vector2;
+use vello::kurbo::{Affine, Rect, RoundedRect, RoundedRectRadii, Vec2};
+use vello::peniko::{Brush, Color, Fill};
+use vello::Scene;
+use winit::event::ElementState;
+use winit::keyboard::{Key, NamedKey};
+
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::theme::Theme;
+
+use crate::app::accessibility::AccessNode;
+use crate::app::info::AppInfo;
+use crate::app::update::Update;
+use crate::instance::WidgetKey;
+use crate::layout::{Layout, LayoutNode, LayoutTree, Position};
+use crate::state::State;
+use crate::widget::Widget;
+
+/// The background color of overlays.
+pub const COLOR_BACKGROUND: StyleKey<Color> = StyleKey::new("color_background", |defaults| {
+    defaults.container().background()
+});
+
+/// The color, that dims the window behind modal overlays.
+pub const COLOR_BACKDROP: StyleKey<Color> =
+    StyleKey::new("color_backdrop", |_| Color::rgba8(0, 0, 0, 80));
+
+/// The style keys of the overlay layer.
+pub const STYLE_KEYS: &[KeyInfo] = &[COLOR_BACKGROUND.info(), COLOR_BACKDROP.info()];
+
+/// Identifies an open overlay.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OverlayKey(usize);
+
+/// Where an overlay is placed relative to its anchor.
+///
+/// If the overlay doesn't fit into the window, it gets placed on the opposite side or moved inside the window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Placement {
+    /// Below the anchor, aligned to its left edge.
+    #[default]
+    Below,
+    /// Above the anchor, aligned to its left edge.
+    Above,
+    /// Left of the anchor, aligned to its top edge.
+    Left,
+    /// Right of the anchor, aligned to its top edge.
+    Right,
+    /// Centered on the anchor.
+    Center,
+}
+
+/// Configures how an overlay is positioned and dismissed.
+#[derive(Copy, Clone, Debug)]
+pub struct OverlayConfig {
+    /// The layout of the widget in window coordinates, that the overlay is placed relative to.
+    ///
+    /// If [None], the overlay is placed relative to the window.
+    pub anchor: Option<Layout>,
+    /// Where the overlay is placed relative to the anchor.
+    pub placement: Placement,
+    /// The space between the anchor and the overlay.
+    pub gap: f32,
+    /// If the overlay is modal.
+    ///
+    /// Modal overlays dim the window and block all input to the widgets below.
+    pub modal: bool,
+    /// If the overlay should close, when a mouse button is pressed outside of it.
+    pub dismiss_on_outside_click: bool,
+    /// If the overlay should close, when `Escape` is pressed while it's the topmost overlay.
+    pub dismiss_on_escape: bool,
+}
+
+impl OverlayConfig {
+    /// Create a config for a popup (like a dropdown or context menu) below the given anchor layout.
+    ///
+    /// The anchor is the layout of a widget, which gets converted into window coordinates using [AppInfo::to_window],
+    /// so popups of widgets inside translated parents (e.g. a scroll view) are placed correctly.
+    pub fn popup(anchor: &Layout, info: &AppInfo) -> Self {
+        Self {
+            anchor: Some(info.to_window(anchor)),
+            ..Default::default()
+        }
+    }
+
+    /// Create a config for a modal dialog in the center of the window.
+    ///
+    /// The dialog can only be dismissed using `Escape` or by closing it.
+    pub fn modal() -> Self {
+        Self {
+            placement: Placement::Center,
+            modal: true,
+            dismiss_on_outside_click: false,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self {
+            anchor: None,
+            placement: Placement::default(),
+            gap: 4.0,
+            modal: false,
+            dismiss_on_outside_click: true,
+            dismiss_on_escape: true,
+        }
+    }
+}
+
+/// Opens and closes overlays, like popups, tooltips and modal dialogs, inside the current window.
+///
+/// Overlays are rendered above the widget tree of the window and receive input before it.
+/// Widgets can access the handle of their window using [AppInfo::overlays].
+/// New overlays are added after the current update and are placed above all open overlays.
+///
+/// ### Theming
+/// The overlay layer uses the `Overlay` style of the `maycoon-core` namespace:
+/// - `color_background` - The background color of overlays.
+/// - `color_backdrop` - The color, that dims the window behind modal overlays.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+#[derive(Clone, Default)]
+pub struct Overlays {
+    inner: Rc<RefCell<OverlaysInner>>,
+}
+
+#[derive(Default)]
+struct OverlaysInner {
+    next: usize,
+    open: Vec<OverlayKey>,
+    requests: Vec<OverlayRequest>,
+}
+
+/// A request to open or close an overlay.
+enum OverlayRequest {
+    /// Open a new overlay with the given key, config and widget.
+    ///
+    /// The widget is a `Box<dyn Widget<S>>` of the app state type.
+    Open(OverlayKey, OverlayConfig, Box<dyn Any>),
+    /// Close the overlay with the given key.
+    Close(OverlayKey),
+}
+
+impl Overlays {
+    /// Open a new overlay with the given config and widget.
+    ///
+    /// Returns the key of the new overlay. The widget must use the same state type as the app.
+    pub fn open<S: State, W: Widget<S> + 'static>(
+        &self,
+        config: OverlayConfig,
+        widget: W,
+    ) -> OverlayKey {
+        let mut inner = self.inner.borrow_mut();
+        let key = OverlayKey(inner.next);
+
+        inner.next += 1;
+        inner.open.push(key);
+        inner.requests.push(OverlayRequest::Open(
+            key,
+            config,
+            Box::new(Box::new(widget) as Box<dyn Widget<S>>),
+        ));
+
+        key
+    }
+
+    /// Close the overlay with the given key.
+    pub fn close(&self, key: OverlayKey) {
+        let mut inner = self.inner.borrow_mut();
+
+        inner.open.retain(|k| *k != key);
+        inner.requests.push(OverlayRequest::Close(key));
+    }
+
+    /// Returns if the overlay with the given key is open.
+    ///
+    /// Overlays can get dismissed by the user, so widgets should check this before closing or re-opening their overlays.
+    pub fn is_open(&self, key: OverlayKey) -> bool {
+        self.inner.borrow().open.contains(&key)
+    }
+
+    /// Returns if no overlay is open.
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().open.is_empty()
+    }
+
+    /// Take all pending requests.
+    fn take_requests(&self) -> Vec<OverlayRequest> {
+        std::mem::take(&mut self.inner.borrow_mut().requests)
+    }
+
+    /// Mark the overlay with the given key as closed.
+    fn set_closed(&self, key: OverlayKey) {
+        self.inner.borrow_mut().open.retain(|k| *k != key);
+    }
+}
+
+/// The overlays of a window, ordered from bottom to top.
+pub(crate) struct OverlayLayer<S: State> {
+    entries: Vec<OverlayEntry<S>>,
+    size: Vector2<f32>,
+}
+
+/// An open overlay with its own layout tree.
+struct OverlayEntry<S: State> {
+    key: OverlayKey,
+    config: OverlayConfig,
+    widget: Box<dyn Widget<S>>,
+    layout: LayoutTree,
+    position: Vector2<f32>,
+}
+
+impl<S: State> OverlayLayer<S> {
+    /// Create a new empty overlay layer for a window of the given size.
+    pub(crate) fn new(size: Vector2<f32>) -> Self {
+        Self {
+            entries: Vec::new(),
+            size,
+        }
+    }
+
+    /// Resize the window. The overlays are laid out again on the next update.
+    pub(crate) fn resize(&mut self, size: Vector2<f32>) {
+        self.size = size;
+
+        for entry in &mut self.entries {
+            entry.layout.resize(size).expect("Failed to resize overlay");
+        }
+    }
+
+    /// Open and close overlays as requested using the [Overlays] handle of the given app info.
+    ///
+    /// Returns [Update::DRAW] if any overlay was opened or closed.
+    pub(crate) fn apply(&mut self, state: &S, info: &AppInfo) -> Update {
+        let mut update = Update::empty();
+
+        for request in info.overlays.take_requests() {
+            match request {
+                OverlayRequest::Open(key, config, widget) => {
+                    let widget = *widget
+                        .downcast::<Box<dyn Widget<S>>>()
+                        .expect("The overlay widget must use the same state as the app");
+
+                    let mut entry = OverlayEntry {
+                        key,
+                        config,
+                        widget,
+                        layout: LayoutTree::new(self.size).expect("Failed to create overlay"),
+                        position: Vector2::zeros(),
+                    };
+
+                    entry.relayout(state, info, self.size);
+
+                    self.entries.push(entry);
+
+                    // the new overlay needs to be updated
+                    update.insert(Update::DRAW | Update::EVAL);
+                },
+
+                OverlayRequest::Close(key) => {
+                    self.entries.retain(|entry| entry.key != key);
+                    update.insert(Update::DRAW);
+                },
+            }
+        }
+
+        update
+    }
+
+    /// Update the widget tree of the window using the given `tree` function and the overlays above it.
+    ///
+    /// Overlays receive input before the widget tree, so they dismiss themselves on `Escape` or outside clicks
+    /// and remove the input they consume from the app info, that's passed to the widget tree.
+    /// The widget tree is updated before the overlay widgets, so its focus ids don't depend on the open overlays.
+    ///
+    /// Returns the update flags of the widget tree and the overlays.
+    pub(crate) fn update(
+        &mut self,
+        state: &mut S,
+        info: &AppInfo,
+        tree: impl FnOnce(&mut S, &AppInfo) -> Update,
+    ) -> Update {
+        let mut update = self.apply(state, info);
+
+        if self.entries.is_empty() {
+            return update | tree(state, info);
+        }
+
+        // the window got resized since the last update
+        for entry in &mut self.entries {
+            if entry.layout.layout().is_none() {
+                entry.relayout(state, info, self.size);
+            }
+        }
+
+        // dismiss the topmost overlay on escape
+        let escape = info.keys.iter().any(|(_, key)| {
+            key.state == ElementState::Pressed && key.logical_key == Key::Named(NamedKey::Escape)
+        });
+
+        let mut consumed = false;
+
+        if escape && self.entries.last().unwrap().config.dismiss_on_escape {
+            let entry = self.entries.pop().unwrap();
+
+            info.overlays.set_closed(entry.key);
+            update.insert(Update::DRAW);
+            consumed = true;
+        }
+
+        // dismiss overlays on outside clicks, until an overlay contains the cursor or a modal overlay blocks the click
+        if info
+            .buttons
+            .iter()
+            .any(|(_, _, state)| *state == ElementState::Pressed)
+        {
+            for i in (0..self.entries.len()).rev() {
+                let entry = &self.entries[i];
+
+                if entry.contains(info.cursor_pos) {
+                    break;
+                }
+
+                let modal = entry.config.modal;
+
+                if entry.config.dismiss_on_outside_click {
+                    info.overlays.set_closed(self.entries.remove(i).key);
+                    update.insert(Update::DRAW);
+                }
+
+                if modal {
+                    break;
+                }
+            }
+        }
+
+        let hovered = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.contains(info.cursor_pos));
+
+        let modal = self.entries.iter().any(|entry| entry.config.modal);
+
+        let mut widget_info = info.clone();
+
+        // overlays and modal overlays consume the mouse input
+        if hovered.is_some() || modal {
+            widget_info.cursor_pos = None;
+            widget_info.buttons.clear();
+            widget_info.wheel.clear();
+        }
+
+        if consumed || modal {
+            widget_info.keys.clear();
+            widget_info.ime.clear();
+        }
+
+        update.insert(tree(state, &widget_info));
+
+        let top = self.entries.len().checked_sub(1);
+
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            let mut child_info = info.clone();
+
+            child_info.origin = info.origin + entry.position;
+
+            // only the hovered overlay receives mouse input
+            if hovered == Some(i) {
+                child_info.cursor_pos = info.cursor_pos.map(|cursor| {
+                    cursor - Vector2::new(entry.position.x as f64, entry.position.y as f64)
+                });
+            } else {
+                child_info.cursor_pos = None;
+                child_info.buttons.clear();
+                child_info.wheel.clear();
+            }
+
+            // only the topmost overlay receives keyboard input
+            if top != Some(i) || consumed {
+                child_info.keys.clear();
+                child_info.ime.clear();
+            }
+
+            // the focus stays inside the topmost modal overlay and the overlays above it
+            if entry.config.modal {
+                info.focus.trap();
+            }
+
+            info.focus.push_scope(WidgetKey::new(entry.key));
+
+            let entry_update =
+                entry
+                    .widget
+                    .update(entry.layout.layout().unwrap(), state, &child_info);
+
+            info.focus.pop_scope();
+
+            if entry_update.intersects(Update::LAYOUT | Update::FORCE) {
+                entry.relayout(state, info, self.size);
+                update.insert(Update::DRAW);
+            }
+
+            update.insert(entry_update);
+        }
+
+        update
+    }
+
+    /// Returns the accessibility nodes of the overlays with their layout and position, ordered from bottom to top.
+    pub(crate) fn accessibility(
+        &mut self,
+        state: &S,
+    ) -> Vec<(AccessNode, &LayoutNode, Vector2<f32>)> {
+        self.entries
+            .iter_mut()
+            .filter_map(|entry| {
+                let layout = entry.layout.layout()?;
+
+                Some((entry.widget.accessibility(state), layout, entry.position))
+            })
+            .collect()
+    }
+
+    /// Render the overlays above the already rendered widget tree.
+    pub(crate) fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        state: &S,
+    ) {
+        let style = theme.style_of(WidgetId::new("maycoon-core", "Overlay"), None);
+        let defaults = theme.defaults();
+
+        let background = style.resolve(&COLOR_BACKGROUND, &defaults);
+        let backdrop = style.resolve(&COLOR_BACKDROP, &defaults);
+
+        for entry in &mut self.entries {
+            // the window got resized since the last update
+            if entry.layout.layout().is_none() {
+                entry.relayout(state, info, self.size);
+            }
+
+            let layout_node = entry.layout.layout().unwrap();
+
+            if entry.config.modal {
+                scene.fill(
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    &Brush::Solid(backdrop),
+                    None,
+                    &Rect::new(0.0, 0.0, self.size.x as f64, self.size.y as f64),
+                );
+            }
+
+            let transform =
+                Affine::translate(Vec2::new(entry.position.x as f64, entry.position.y as f64));
+
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                &Brush::Solid(background),
+                None,
+                &RoundedRect::from_rect(
+                    Rect::new(
+                        0.0,
+                        0.0,
+                        layout_node.layout.size.width as f64,
+                        layout_node.layout.size.height as f64,
+                    ),
+                    RoundedRectRadii::from_single_radius(8.0),
+                ),
+            );
+
+            let mut child_scene = Scene::new();
+
+            entry
+                .widget
+                .render(&mut child_scene, theme, info, layout_node, state);
+
+            scene.append(&child_scene, Some(transform));
+        }
+    }
+}
+
+impl<S: State> OverlayEntry<S> {
+    /// Layout the overlay widget and place it relative to its anchor.
+    fn relayout(&mut self, state: &S, info: &AppInfo, window: Vector2<f32>) {
+        let mut style = self.widget.layout_style(state);
+
+        // size the overlay to its content instead of the window
+        style.style.position = Position::Absolute;
+
+        self.layout
+            .update(&style)
+            .expect("Failed to layout overlay");
+
+        let layout = self
+            .layout
+            .compute(info)
+            .expect("Failed to compute overlay layout");
+
+        self.position = place(
+            &self.config,
+            Vector2::new(layout.layout.size.width, layout.layout.size.height),
+            window,
+        );
+    }
+
+    /// Returns if the given cursor position is inside the overlay.
+    fn contains(&self, cursor: Option<Vector2<f64>>) -> bool {
+        let (Some(cursor), Some(LayoutNode { layout, .. })) = (cursor, self.layout.layout()) else {
+            return false;
+        };
+
+        let cursor = cursor.cast::<f32>() - self.position;
+
+        cursor.x >= 0.0
+            && cursor.y >= 0.0
+            && cursor.x <= layout.size.width
+            && cursor.y <= layout.size.height
+    }
+}
+
+/// Compute the position of an overlay with the given size inside the window.
+fn place(config: &OverlayConfig, size: Vector2<f32>, window: Vector2<f32>) -> Vector2<f32> {
+    let (anchor_pos, anchor_size) = match &config.anchor {
+        Some(anchor) => (
+            Vector2::new(anchor.location.x, anchor.location.y),
+            Vector2::new(anchor.size.width, anchor.size.height),
+        ),
+        None => (Vector2::zeros(), window),
+    };
+
+    let gap = config.gap;
+
+    let below = anchor_pos.y + anchor_size.y + gap;
+    let above = anchor_pos.y - gap - size.y;
+    let left = anchor_pos.x - gap - size.x;
+    let right = anchor_pos.x + anchor_size.x + gap;
+
+    let position = match config.placement {
+        Placement::Below if below + size.y > window.y && above >= 0.0 => {
+            Vector2::new(anchor_pos.x, above)
+        },
+        Placement::Below => Vector2::new(anchor_pos.x, below),
+
+        Placement::Above if above < 0.0 && below + size.y <= window.y => {
+            Vector2::new(anchor_pos.x, below)
+        },
+        Placement::Above => Vector2::new(anchor_pos.x, above),
+
+        Placement::Left if left < 0.0 && right + size.x <= window.x => {
+            Vector2::new(right, anchor_pos.y)
+        },
+        Placement::Left => Vector2::new(left, anchor_pos.y),
+
+        Placement::Right if right + size.x > window.x && left >= 0.0 => {
+            Vector2::new(left, anchor_pos.y)
+        },
+        Placement::Right => Vector2::new(right, anchor_pos.y),
+
+        Placement::Center => anchor_pos + (anchor_size - size) / 2.0,
+    };
+
+    // keep the overlay inside the window
+    Vector2::new(
+        position.x.clamp(0.0, (window.x - size.x).max(0.0)),
+        position.y.clamp(0.0, (window.y - size.y).max(0.0)),
+    )
+}
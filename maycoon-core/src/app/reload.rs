@@ -0,0 +1,227 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use peniko::{Blob, Font};
+
+use maycoon_theme::theme::Theme;
+
+/// The default interval, in which the watched files are checked for changes.
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Loads a theme from a file.
+type ThemeLoader<T> = Box<dyn FnMut(&Path) -> Result<T, String>>;
+
+/// Watches theme and font files and reloads them, when they change on disk.
+///
+/// Meant for quickly iterating on the design of an app during development, without restarting it.
+/// Use [MayApp::with_theme_reload](crate::app::MayApp::with_theme_reload) and
+/// [MayApp::with_font_reload](crate::app::MayApp::with_font_reload) to reload files of a running app.
+///
+/// Files are watched by checking their modification time in the configured interval.
+/// If a file fails to load (e.g. because it's only saved halfway), the old theme or font is kept
+/// and the error is returned in [Reloaded::errors].
+pub struct HotReload<T: Theme> {
+    theme: Option<(WatchedFile, ThemeLoader<T>)>,
+    fonts: Vec<(String, WatchedFile)>,
+    interval: Duration,
+    next_check: Instant,
+}
+
+impl<T: Theme> HotReload<T> {
+    /// Create a new hot reloader, that does not watch any files.
+    pub fn new() -> Self {
+        Self {
+            theme: None,
+            fonts: Vec::new(),
+            interval: DEFAULT_INTERVAL,
+            next_check: Instant::now(),
+        }
+    }
+
+    /// Watch the given theme file and load it using the given function, when it changes.
+    ///
+    /// The theme is not loaded initially, so the app starts with the theme of its config.
+    pub fn with_theme<E: Display>(
+        mut self,
+        path: impl Into<PathBuf>,
+        mut load: impl FnMut(&Path) -> Result<T, E> + 'static,
+    ) -> Self {
+        self.theme = Some((
+            WatchedFile::new(path.into()),
+            Box::new(move |path| load(path).map_err(|err| err.to_string())),
+        ));
+        self
+    }
+
+    /// Watch the given font file and insert it into the font context with the given name, when it changes.
+    pub fn with_font(mut self, name: impl ToString, path: impl Into<PathBuf>) -> Self {
+        self.fonts
+            .push((name.to_string(), WatchedFile::new(path.into())));
+        self
+    }
+
+    /// Set the interval, in which the watched files are checked for changes. Defaults to 250 milliseconds.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns if no files are watched.
+    pub fn is_empty(&self) -> bool {
+        self.theme.is_none() && self.fonts.is_empty()
+    }
+
+    /// Returns when the files should be checked the next time or [None] if no files are watched.
+    pub fn deadline(&self) -> Option<Instant> {
+        (!self.is_empty()).then_some(self.next_check)
+    }
+
+    /// Check the files for changes, if the interval has passed since the last check.
+    pub fn poll(&mut self, now: Instant) -> Reloaded<T> {
+        if self.is_empty() || now < self.next_check {
+            return Reloaded::default();
+        }
+
+        self.next_check = now + self.interval;
+        self.check()
+    }
+
+    /// Check the files for changes right now and load the changed files.
+    pub fn check(&mut self) -> Reloaded<T> {
+        let mut reloaded = Reloaded::default();
+
+        if let Some((file, load)) = &mut self.theme {
+            if file.changed() {
+                match load(&file.path) {
+                    Ok(theme) => reloaded.theme = Some(theme),
+                    Err(message) => reloaded.errors.push(ReloadError {
+                        path: file.path.clone(),
+                        message,
+                    }),
+                }
+            }
+        }
+
+        for (name, file) in &mut self.fonts {
+            if file.changed() {
+                match load_font(&file.path) {
+                    Ok(font) => reloaded.fonts.push((name.clone(), font)),
+                    Err(err) => reloaded.errors.push(err),
+                }
+            }
+        }
+
+        reloaded
+    }
+}
+
+impl<T: Theme> Default for HotReload<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The theme and fonts, that were reloaded by [HotReload].
+pub struct Reloaded<T: Theme> {
+    /// The new theme, if the theme file changed.
+    pub theme: Option<T>,
+    /// The names and new data of the fonts, that changed.
+    pub fonts: Vec<(String, Font)>,
+    /// The errors of the files, that changed, but failed to load.
+    pub errors: Vec<ReloadError>,
+}
+
+impl<T: Theme> Reloaded<T> {
+    /// Returns if nothing was reloaded. Errors are not counted.
+    pub fn is_empty(&self) -> bool {
+        self.theme.is_none() && self.fonts.is_empty()
+    }
+}
+
+impl<T: Theme> Default for Reloaded<T> {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            fonts: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+/// A watched file, that changed, but failed to load.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReloadError {
+    /// The path of the file.
+    pub path: PathBuf,
+    /// The error message of the loader.
+    pub message: String,
+}
+
+impl Display for ReloadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to reload {}: {}",
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
+/// Load the font from the given file.
+///
+/// Returns an error, if the file can't be read or doesn't contain a valid font (e.g. because it's only saved halfway).
+pub(crate) fn load_font(path: &Path) -> Result<Font, ReloadError> {
+    let error = |message: String| ReloadError {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let data = std::fs::read(path).map_err(|err| error(err.to_string()))?;
+
+    if rustybuzz::Face::from_slice(&data, 0).is_none() {
+        return Err(error(String::from("Invalid font data")));
+    }
+
+    Ok(Font::new(Blob::new(Arc::new(data)), 0))
+}
+
+/// A file with the modification time and size, it had when it was checked the last time.
+struct WatchedFile {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl WatchedFile {
+    /// Start watching the given file, remembering its current modification time.
+    fn new(path: PathBuf) -> Self {
+        let stamp = stamp(&path);
+
+        Self { path, stamp }
+    }
+
+    /// Returns if the file changed since the last check.
+    ///
+    /// Files, that don't exist (e.g. while being replaced by an editor), are not counted as changed.
+    fn changed(&mut self) -> bool {
+        match stamp(&self.path) {
+            Some(stamp) if Some(stamp) != self.stamp => {
+                self.stamp = Some(stamp);
+                true
+            },
+
+            _ => false,
+        }
+    }
+}
+
+/// Returns the modification time and size of the given file.
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
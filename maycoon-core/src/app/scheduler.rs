@@ -0,0 +1,63 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use winit::event_loop::ControlFlow;
+
+/// Schedules future updates of a window, e.g. for timers or delayed animations.
+///
+/// The window sleeps until the earliest requested time, so idle apps don't use any CPU.
+/// To update again on the next frame (e.g. during an animation), return [Update::EVAL] instead.
+///
+/// ```
+/// use std::time::Duration;
+/// use maycoon_core::app::scheduler::Scheduler;
+///
+/// let scheduler = Scheduler::default();
+///
+/// scheduler.request_update_after(Duration::from_secs(5));
+/// scheduler.request_update_after(Duration::from_secs(1));
+///
+/// // the earliest request wins
+/// assert!(scheduler.deadline().unwrap() <= std::time::Instant::now() + Duration::from_secs(1));
+/// ```
+///
+/// [Update::EVAL]: crate::app::update::Update::EVAL
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    deadline: Cell<Option<Instant>>,
+}
+
+impl Scheduler {
+    /// Request an update of the window at the given time.
+    ///
+    /// If multiple updates are requested, the window is updated at the earliest time.
+    pub fn request_update_at(&self, at: Instant) {
+        self.deadline.set(Some(
+            self.deadline.get().map_or(at, |deadline| deadline.min(at)),
+        ));
+    }
+
+    /// Request an update of the window after the given delay.
+    pub fn request_update_after(&self, delay: Duration) {
+        self.request_update_at(Instant::now() + delay);
+    }
+
+    /// Returns the earliest requested update time or [None] if no update was requested.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline.get()
+    }
+
+    /// Take the earliest requested update time.
+    pub(crate) fn take_deadline(&self) -> Option<Instant> {
+        self.deadline.take()
+    }
+}
+
+/// Returns the control flow of the event loop, that sleeps until the earliest of the given times
+/// or until the next event, if there are none.
+pub(crate) fn control_flow(times: impl IntoIterator<Item = Option<Instant>>) -> ControlFlow {
+    match times.into_iter().flatten().min() {
+        Some(at) => ControlFlow::WaitUntil(at),
+        None => ControlFlow::Wait,
+    }
+}
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+
+use maycoon_theme::globals::{Globals, StyleContext, StyleOverrides};
+use maycoon_theme::theme::Theme;
+
+/// The style cascade of the theme [Globals] during updates.
+///
+/// Updates don't have access to the theme, so the inherited style values are tracked here as well.
+/// The cascade starts at the root context of the theme in every update.
+/// Widgets, which override the style of their children, update them using [StyleCascade::update_with_overrides]
+/// and render them using [StyleCascade::render_with_overrides], so the children inherit the same style in both passes.
+///
+/// ```
+/// use maycoon_core::app::style::StyleCascade;
+/// use maycoon_theme::globals::StyleOverrides;
+///
+/// let cascade = StyleCascade::default();
+///
+/// cascade.push(&StyleOverrides::new().with_disabled(true));
+/// assert!(cascade.context().disabled);
+///
+/// cascade.pop();
+/// assert!(!cascade.context().disabled);
+/// ```
+#[derive(Debug, Default)]
+pub struct StyleCascade {
+    globals: RefCell<Globals>,
+}
+
+impl StyleCascade {
+    /// Returns the inherited style values of the widget, that is currently updated.
+    pub fn context(&self) -> StyleContext {
+        self.globals.borrow().context().clone()
+    }
+
+    /// Apply the given overrides to the current context, until the next call to [StyleCascade::pop].
+    pub fn push(&self, overrides: &StyleOverrides) {
+        self.globals.borrow_mut().push(overrides);
+    }
+
+    /// Restore the context before the last call to [StyleCascade::push].
+    pub fn pop(&self) {
+        self.globals.borrow_mut().pop();
+    }
+
+    /// Update the children of a widget with the given overrides applied to this cascade.
+    ///
+    /// Use [StyleCascade::render_with_overrides] with the same overrides to render them.
+    pub fn update_with_overrides<R>(
+        &self,
+        overrides: &StyleOverrides,
+        update: impl FnOnce() -> R,
+    ) -> R {
+        self.push(overrides);
+
+        let result = update();
+
+        self.pop();
+
+        result
+    }
+
+    /// Render the children of a widget with the given overrides applied to the theme [Globals] and this cascade.
+    ///
+    /// Use [StyleCascade::update_with_overrides] with the same overrides to update them.
+    pub fn render_with_overrides<R>(
+        &self,
+        theme: &mut dyn Theme,
+        overrides: &StyleOverrides,
+        render: impl FnOnce(&mut dyn Theme) -> R,
+    ) -> R {
+        self.push(overrides);
+        theme.globals_mut().push(overrides);
+
+        let result = render(theme);
+
+        theme.globals_mut().pop();
+        self.pop();
+
+        result
+    }
+
+    /// Start a new update with the given root context of the theme.
+    pub(crate) fn begin_update(&self, root: &StyleContext) {
+        *self.globals.borrow_mut() = Globals::new(root.clone());
+    }
+}
@@ -0,0 +1,273 @@
+use std::any::Any;
+use std::future::Future;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use winit::event_loop::EventLoopProxy;
+
+use crate::app::handler::AppEvent;
+use crate::app::update::Update;
+use crate::state::State;
+
+/// A message, that updates the app state.
+type Message = Box<dyn FnOnce(&mut dyn Any) -> Update + Send>;
+
+/// The callback of a timer, that updates the app state.
+type TimerCallback = Box<dyn FnMut(&mut dyn Any) -> Update + Send>;
+
+/// Runs background work and feeds the results back into the app state.
+///
+/// The handle can be cloned and sent to other threads. Use [Tasks::post] to update the state from anywhere,
+/// [Tasks::spawn] to run futures in the background and [Tasks::set_timeout] or [Tasks::set_interval] for timers.
+/// Messages and timers run on the UI thread, before the next update of the windows.
+///
+/// Get the handle using [MayApp::tasks] or [AppInfo::tasks].
+///
+/// [MayApp::tasks]: crate::app::MayApp::tasks
+/// [AppInfo::tasks]: crate::app::info::AppInfo::tasks
+#[derive(Clone)]
+pub struct Tasks {
+    sender: Sender<Message>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// Identifies a timer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(usize);
+
+struct Shared {
+    next: usize,
+    timers: Vec<Timer>,
+    /// The due timers, that are currently running.
+    running: Vec<TimerId>,
+    /// The running timers, that were cancelled while running.
+    cancelled: Vec<TimerId>,
+    proxy: Option<EventLoopProxy<AppEvent>>,
+}
+
+struct Timer {
+    id: TimerId,
+    at: Instant,
+    period: Option<Duration>,
+    callback: TimerCallback,
+}
+
+impl Tasks {
+    /// Post a message, that updates the app state on the UI thread.
+    ///
+    /// The returned update flags are applied to all windows.
+    /// The message is dropped, if the app is not running anymore.
+    pub fn post<S: State>(&self, message: impl FnOnce(&mut S) -> Update + Send + 'static) {
+        let message: Message = Box::new(move |state| message(downcast(state)));
+
+        if self.sender.send(message).is_ok() {
+            self.wake();
+        }
+    }
+
+    /// Run the given future on a background thread.
+    ///
+    /// Use a clone of this handle inside the future to post its results using [Tasks::post].
+    pub fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        std::thread::spawn(move || futures_lite::future::block_on(future));
+    }
+
+    /// Call the given function once after the given delay.
+    pub fn set_timeout<S: State>(
+        &self,
+        delay: Duration,
+        callback: impl FnOnce(&mut S) -> Update + Send + 'static,
+    ) -> TimerId {
+        let mut callback = Some(callback);
+
+        self.add_timer(
+            delay,
+            None,
+            Box::new(move |state| match callback.take() {
+                Some(callback) => callback(downcast(state)),
+                None => Update::empty(),
+            }),
+        )
+    }
+
+    /// Call the given function repeatedly with the given period, until the timer gets cancelled.
+    pub fn set_interval<S: State>(
+        &self,
+        period: Duration,
+        mut callback: impl FnMut(&mut S) -> Update + Send + 'static,
+    ) -> TimerId {
+        self.add_timer(
+            period,
+            Some(period),
+            Box::new(move |state| callback(downcast(state))),
+        )
+    }
+
+    /// Cancel the timer with the given id.
+    pub fn cancel(&self, id: TimerId) {
+        let mut shared = self.shared.lock().unwrap();
+
+        match shared.timers.iter().position(|timer| timer.id == id) {
+            Some(i) => {
+                shared.timers.remove(i);
+            },
+
+            // the timer may be running right now
+            None if shared.running.contains(&id) => shared.cancelled.push(id),
+
+            None => (),
+        }
+    }
+
+    /// Add a new timer and wake up the event loop, so it knows about the new deadline.
+    fn add_timer(
+        &self,
+        delay: Duration,
+        period: Option<Duration>,
+        callback: TimerCallback,
+    ) -> TimerId {
+        let mut shared = self.shared.lock().unwrap();
+        let id = TimerId(shared.next);
+
+        shared.next += 1;
+        shared.timers.push(Timer {
+            id,
+            at: Instant::now() + delay,
+            period,
+            callback,
+        });
+
+        drop(shared);
+
+        self.wake();
+
+        id
+    }
+
+    /// Wake up the event loop, if it is running.
+    fn wake(&self) {
+        if let Some(proxy) = &self.shared.lock().unwrap().proxy {
+            let _ = proxy.send_event(AppEvent::Wake);
+        }
+    }
+
+    /// Set the event loop proxy, that gets woken up by new messages and timers.
+    pub(crate) fn set_proxy(&self, proxy: EventLoopProxy<AppEvent>) {
+        self.shared.lock().unwrap().proxy = Some(proxy);
+    }
+
+    /// Returns the event loop proxy, if the app is running.
+    pub(crate) fn proxy(&self) -> Option<EventLoopProxy<AppEvent>> {
+        self.shared.lock().unwrap().proxy.clone()
+    }
+}
+
+impl Default for Tasks {
+    /// Create a handle without a running app. Messages and timers are dropped.
+    fn default() -> Self {
+        channel().0
+    }
+}
+
+/// Receives the messages and runs the timers of a [Tasks] handle on the UI thread.
+///
+/// Gets created together with the [Tasks] handle of the app.
+pub struct TaskQueue {
+    receiver: Receiver<Message>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl TaskQueue {
+    /// Run all posted messages and due timers with the given state.
+    ///
+    /// Returns the combined update flags.
+    pub(crate) fn run<S: State>(&self, state: &mut S) -> Update {
+        let mut update = Update::empty();
+
+        for message in self.receiver.try_iter() {
+            update |= message(state);
+        }
+
+        let now = Instant::now();
+
+        // take out the due timers, so they can add or cancel timers themselves
+        let due = {
+            let mut shared = self.shared.lock().unwrap();
+            let (due, pending) = std::mem::take(&mut shared.timers)
+                .into_iter()
+                .partition::<Vec<_>, _>(|timer| timer.at <= now);
+
+            shared.timers = pending;
+            shared.running = due.iter().map(|timer| timer.id).collect();
+            due
+        };
+
+        for mut timer in due {
+            // the timer may have been cancelled by another due timer
+            if self.is_cancelled(timer.id) {
+                continue;
+            }
+
+            update |= (timer.callback)(state);
+
+            if let Some(period) = timer.period {
+                if !self.is_cancelled(timer.id) {
+                    // skip missed periods instead of catching up
+                    timer.at = (timer.at + period).max(now);
+                    self.shared.lock().unwrap().timers.push(timer);
+                }
+            }
+        }
+
+        let mut shared = self.shared.lock().unwrap();
+
+        shared.running.clear();
+        shared.cancelled.clear();
+
+        update
+    }
+
+    /// Returns if the given running timer was cancelled.
+    fn is_cancelled(&self, id: TimerId) -> bool {
+        self.shared.lock().unwrap().cancelled.contains(&id)
+    }
+
+    /// Returns the time of the next timer or [None] if there are no timers.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        self.shared
+            .lock()
+            .unwrap()
+            .timers
+            .iter()
+            .map(|timer| timer.at)
+            .min()
+    }
+}
+
+/// Create a new [Tasks] handle with its [TaskQueue].
+pub(crate) fn channel() -> (Tasks, TaskQueue) {
+    let (sender, receiver) = mpsc::channel();
+    let shared = Arc::new(Mutex::new(Shared {
+        next: 0,
+        timers: Vec::new(),
+        running: Vec::new(),
+        cancelled: Vec::new(),
+        proxy: None,
+    }));
+
+    (
+        Tasks {
+            sender,
+            shared: shared.clone(),
+        },
+        TaskQueue { receiver, shared },
+    )
+}
+
+/// Downcast the app state to the state type of a message or timer.
+fn downcast<S: State>(state: &mut dyn Any) -> &mut S {
+    state
+        .downcast_mut()
+        .expect("The task must use the same state as the app")
+}
@@ -4,13 +4,16 @@ bitflags! {
     /// Update bitflags to define which part of the App should Update.
     ///
     /// Possible values:
-    /// - **EVAL** - Re-evaluate the widget tree.
+    /// - **EVAL** - Re-evaluate the widget tree on the next frame (e.g. during animations).
     /// - **DRAW** - Re-draw the widget tree.
     /// - **LAYOUT** - Re-layout the widget tree.
     /// - **FORCE** - Force the App to re-evaluate, re-draw and re-layout the widget tree.
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub struct Update: u8 {
-        /// Re-evaluate the widget tree.
+        /// Re-evaluate the widget tree on the next frame.
+        ///
+        /// Windows only redraw, when something changed. Use the [Scheduler](crate::app::scheduler::Scheduler)
+        /// to update at a later time instead.
         const EVAL   = 0b00000001;
         /// Re-draw the widget tree.
         const DRAW   = 0b00000010;
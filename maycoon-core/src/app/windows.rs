@@ -0,0 +1,131 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::config::WindowConfig;
+use crate::state::State;
+use crate::widget::Widget;
+
+/// Identifies a window of the application.
+///
+/// The main window always has the key [WindowKey::MAIN].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowKey(usize);
+
+impl WindowKey {
+    /// The key of the main window, that gets created when the app starts.
+    pub const MAIN: WindowKey = WindowKey(0);
+}
+
+/// Opens and closes additional windows at runtime.
+///
+/// Every window has its own [WindowConfig] and root widget, but all windows share the same app state.
+/// The handle can be cloned and stored in the app state (see [MayApp::windows]) or accessed by widgets using [AppInfo::windows].
+/// Requests are applied after the current update.
+///
+/// [MayApp::windows]: crate::app::MayApp::windows
+/// [AppInfo::windows]: crate::app::info::AppInfo::windows
+#[derive(Clone)]
+pub struct Windows {
+    inner: Rc<RefCell<WindowsInner>>,
+}
+
+struct WindowsInner {
+    next: usize,
+    open: Vec<WindowKey>,
+    requests: Vec<WindowRequest>,
+}
+
+/// A request to open or close a window.
+pub(crate) enum WindowRequest {
+    /// Open a new window with the given key, config and root widget.
+    Open(WindowKey, Box<WindowConfig>, WindowWidget),
+    /// Close the window with the given key.
+    Close(WindowKey),
+}
+
+/// The root widget of a requested window.
+///
+/// Contains a `Box<dyn Widget<S>>` of the app state type, since the handle itself doesn't know the state type.
+pub(crate) struct WindowWidget(Box<dyn Any>);
+
+impl WindowWidget {
+    /// Returns the widget. Panics if the widget uses another state type than the app.
+    pub(crate) fn into_widget<S: State>(self) -> Box<dyn Widget<S>> {
+        *self
+            .0
+            .downcast::<Box<dyn Widget<S>>>()
+            .expect("The window widget must use the same state as the app")
+    }
+}
+
+impl Windows {
+    /// Request to open a new window with the given config and root widget.
+    ///
+    /// Returns the key of the new window. The widget must use the same state type as the app.
+    pub fn open<S: State, W: Widget<S> + 'static>(
+        &self,
+        config: WindowConfig,
+        widget: W,
+    ) -> WindowKey {
+        let mut inner = self.inner.borrow_mut();
+        let key = WindowKey(inner.next);
+
+        inner.next += 1;
+        inner.requests.push(WindowRequest::Open(
+            key,
+            Box::new(config),
+            WindowWidget(Box::new(Box::new(widget) as Box<dyn Widget<S>>)),
+        ));
+
+        key
+    }
+
+    /// Request to close the window with the given key.
+    ///
+    /// Closing the main window exits the application.
+    pub fn close(&self, key: WindowKey) {
+        self.inner
+            .borrow_mut()
+            .requests
+            .push(WindowRequest::Close(key));
+    }
+
+    /// Returns if the window with the given key is currently open.
+    pub fn is_open(&self, key: WindowKey) -> bool {
+        self.inner.borrow().open.contains(&key)
+    }
+
+    /// Returns the keys of all open windows.
+    pub fn open_windows(&self) -> Vec<WindowKey> {
+        self.inner.borrow().open.clone()
+    }
+
+    /// Take all pending requests.
+    pub(crate) fn take_requests(&self) -> Vec<WindowRequest> {
+        std::mem::take(&mut self.inner.borrow_mut().requests)
+    }
+
+    /// Mark the window with the given key as open or closed.
+    pub(crate) fn set_open(&self, key: WindowKey, open: bool) {
+        let mut inner = self.inner.borrow_mut();
+
+        inner.open.retain(|k| *k != key);
+
+        if open {
+            inner.open.push(key);
+        }
+    }
+}
+
+impl Default for Windows {
+    fn default() -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(WindowsInner {
+                next: WindowKey::MAIN.0 + 1,
+                open: Vec::new(),
+                requests: Vec::new(),
+            })),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use nalgebra::{Point2, Vector2};
 pub use vello::AaConfig;
 pub use wgpu_types::PresentMode;
+use winit::dpi::{LogicalPosition, LogicalSize, Position, Size};
+use winit::window::WindowAttributes;
 pub use winit::window::{
     BadIcon, Cursor, CursorIcon, CustomCursor, Icon as WindowIcon, WindowButtons, WindowLevel,
 };
@@ -18,6 +20,11 @@ pub struct MayConfig<T: Theme> {
     pub render: RenderConfig,
     /// Theme of the Application.
     pub theme: T,
+    /// If the theme should follow the light or dark mode of the operating system.
+    ///
+    /// The [ColorScheme](maycoon_theme::theme::ColorScheme) of the system gets applied to the theme
+    /// using [Theme::set_color_scheme], when the first window opens and whenever the system switches.
+    pub follow_system_theme: bool,
 }
 
 impl Default for MayConfig<CelesteTheme> {
@@ -26,6 +33,7 @@ impl Default for MayConfig<CelesteTheme> {
             window: WindowConfig::default(),
             render: RenderConfig::default(),
             theme: CelesteTheme::light(),
+            follow_system_theme: false,
         }
     }
 }
@@ -76,6 +84,49 @@ pub struct WindowConfig {
     pub close_on_request: bool,
 }
 
+impl WindowConfig {
+    /// Returns the winit window attributes of this config.
+    pub(crate) fn attributes(&self) -> WindowAttributes {
+        let mut attrs = WindowAttributes::default()
+            .with_inner_size(LogicalSize::new(self.size.x, self.size.y))
+            .with_resizable(self.resizable)
+            .with_enabled_buttons(self.buttons)
+            .with_title(self.title.clone())
+            .with_maximized(self.maximized)
+            .with_visible(self.visible)
+            .with_transparent(self.transparent)
+            .with_blur(self.blur)
+            .with_decorations(self.decorations)
+            .with_window_icon(self.icon.clone())
+            .with_content_protected(self.content_protected)
+            .with_window_level(self.level)
+            .with_active(self.active)
+            .with_cursor(self.cursor.clone());
+
+        // since `with_max_inner_size()` doesn't support `Option` values, we need to manually set it
+        attrs.max_inner_size = self
+            .max_size
+            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
+
+        // since `with_min_inner_size()` doesn't support `Option` values, we need to manually set it
+        attrs.min_inner_size = self
+            .min_size
+            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
+
+        // since `with_position()` doesn't support `Option` values, we need to manually set it
+        attrs.position = self
+            .position
+            .map(|v| Position::Logical(LogicalPosition::new(v.x, v.y)));
+
+        // since `with_resize_increments()` doesn't support `Option` values, we need to manually set it
+        attrs.resize_increments = self
+            .resize_increments
+            .map(|v| Size::Logical(LogicalSize::new(v.x, v.y)));
+
+        attrs
+    }
+}
+
 impl Default for WindowConfig {
     fn default() -> Self {
         Self {
@@ -114,7 +165,26 @@ pub struct RenderConfig {
     /// **NOTE:** The GPU is still used during rasterization.
     pub cpu: bool,
     /// The presentation mode of the window/surface.
+    ///
+    /// With vsync enabled, animations are paced by presenting frames.
+    /// Otherwise, they are paced using the refresh rate of the monitor.
     pub present_mode: PresentMode,
+    /// If the [Diagnostics](crate::app::diagnostics::Diagnostics) of a window should be drawn on top of its content.
+    pub show_diagnostics: bool,
+    /// If the widget inspector can be toggled using `Ctrl+Shift+I`. Enabled in debug builds by default.
+    ///
+    /// See [inspector](crate::app::inspector) for more.
+    pub inspector: bool,
+}
+
+impl RenderConfig {
+    /// Returns if presenting a frame waits for the vertical blank of the monitor.
+    pub(crate) fn is_vsync(&self) -> bool {
+        matches!(
+            self.present_mode,
+            PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed
+        )
+    }
 }
 
 impl Default for RenderConfig {
@@ -123,6 +193,8 @@ impl Default for RenderConfig {
             antialiasing: AaConfig::Area,
             cpu: false,
             present_mode: PresentMode::AutoNoVsync,
+            show_diagnostics: false,
+            inspector: cfg!(debug_assertions),
         }
     }
 }
@@ -0,0 +1,160 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::state::{State, Val};
+use crate::widget::Widget;
+
+/// A key, that identifies a widget instance among its siblings.
+///
+/// Keyed children keep their instance state, when the list of children changes (e.g. items get inserted, removed or reordered).
+/// Keys must be unique among the siblings.
+///
+/// ```
+/// use maycoon_core::instance::WidgetKey;
+///
+/// assert_eq!(WidgetKey::new("item-1"), WidgetKey::new("item-1"));
+/// assert_ne!(WidgetKey::new(1), WidgetKey::new(2));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidgetKey(u64);
+
+impl WidgetKey {
+    /// Create a new key from the given hashable value (e.g. the id of a list item).
+    pub fn new(value: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+
+        value.hash(&mut hasher);
+
+        Self(hasher.finish())
+    }
+}
+
+/// Stores the instance state of a widget and its children, while the widget gets rebuilt.
+///
+/// State dependent widgets (e.g. created using `val!()`) are rebuilt, whenever the state gets re-evaluated.
+/// Before that, the old widget saves its instance state (e.g. hover, drag or animation state) using [Widget::save_state],
+/// so the rebuilt widget can continue with it using [Widget::restore_state].
+///
+/// ```
+/// use maycoon_core::instance::{InstanceStore, WidgetKey};
+///
+/// let mut store = InstanceStore::new();
+///
+/// store.insert(true);
+/// store.child(WidgetKey::new(0)).insert(0.5f32);
+///
+/// assert_eq!(store.take::<bool>(), Some(true));
+/// assert_eq!(store.take_child(WidgetKey::new(0)).unwrap().take::<f32>(), Some(0.5));
+/// assert!(store.is_empty());
+/// ```
+#[derive(Default)]
+pub struct InstanceStore {
+    state: Option<Box<dyn Any>>,
+    children: HashMap<WidgetKey, InstanceStore>,
+}
+
+impl InstanceStore {
+    /// Create a new empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the state of the widget. Replaces the previously stored state.
+    pub fn insert<T: Any>(&mut self, state: T) {
+        self.state = Some(Box::new(state));
+    }
+
+    /// Take the stored state of the widget, if it has the given type.
+    pub fn take<T: Any>(&mut self) -> Option<T> {
+        match self.state.take()?.downcast() {
+            Ok(state) => Some(*state),
+
+            Err(state) => {
+                self.state = Some(state);
+                None
+            },
+        }
+    }
+
+    /// Returns the store of the child with the given key. Creates an empty store, if it doesn't exist.
+    pub fn child(&mut self, key: WidgetKey) -> &mut InstanceStore {
+        self.children.entry(key).or_default()
+    }
+
+    /// Remove and return the store of the child with the given key.
+    pub fn take_child(&mut self, key: WidgetKey) -> Option<InstanceStore> {
+        self.children.remove(&key)
+    }
+
+    /// Returns if neither the widget nor its children have stored any state.
+    pub fn is_empty(&self) -> bool {
+        self.state.is_none() && self.children.values().all(InstanceStore::is_empty)
+    }
+}
+
+/// Keyed child widgets. See [WidgetKey] for more.
+pub type KeyedChildren<S> = Vec<(WidgetKey, Box<dyn Widget<S>>)>;
+
+impl<S: State, W: Widget<S>> Val<S, W> {
+    /// Invalidate the widget and rebuild it, if it's state dependent.
+    ///
+    /// The instance state of the previous widget is moved into the rebuilt one.
+    /// Widgets should use this instead of [Val::invalidate] for their child widgets inside [Widget::update].
+    pub fn rebuild(&mut self, state: &S) {
+        if let Val::State {
+            value: Some(old), ..
+        } = self
+        {
+            let mut store = InstanceStore::new();
+
+            old.save_state(&mut store);
+
+            self.invalidate();
+            self.get_mut(state).restore_state(state, &mut store);
+        }
+    }
+}
+
+impl<S: State> Val<S, KeyedChildren<S>> {
+    /// Invalidate the keyed children and rebuild them, if they are state dependent.
+    ///
+    /// The instance state of every previous child is moved into the rebuilt child with the same key.
+    /// The state of removed children is dropped.
+    pub fn rebuild_keyed(&mut self, state: &S) {
+        let Val::State {
+            value: Some(old), ..
+        } = self
+        else {
+            return;
+        };
+
+        let mut store = InstanceStore::new();
+
+        save_keyed(old, &mut store);
+
+        self.invalidate();
+
+        restore_keyed(self.get_mut(state), state, &mut store);
+    }
+}
+
+/// Save the instance state of the given keyed children into the given store.
+pub fn save_keyed<S: State>(children: &mut KeyedChildren<S>, store: &mut InstanceStore) {
+    for (key, child) in children {
+        child.save_state(store.child(*key));
+    }
+}
+
+/// Restore the instance state of the given keyed children from the given store.
+pub fn restore_keyed<S: State>(
+    children: &mut KeyedChildren<S>,
+    state: &S,
+    store: &mut InstanceStore,
+) {
+    for (key, child) in children {
+        if let Some(mut child_store) = store.take_child(*key) {
+            child.restore_state(state, &mut child_store);
+        }
+    }
+}
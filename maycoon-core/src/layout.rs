@@ -1,9 +1,16 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
 use nalgebra::Vector2;
 pub use taffy::{
-    AlignContent, AlignItems, AlignSelf, Dimension, Display, FlexDirection, FlexWrap, GridAutoFlow,
-    GridPlacement, JustifyContent, JustifyItems, JustifySelf, Layout, LengthPercentage,
-    LengthPercentageAuto, Line, Overflow, Position, Rect,
+    AlignContent, AlignItems, AlignSelf, AvailableSpace, Dimension, Display, FlexDirection,
+    FlexWrap, GridAutoFlow, GridPlacement, JustifyContent, JustifyItems, JustifySelf, Layout,
+    LengthPercentage, LengthPercentageAuto, Line, Overflow, Position, Rect,
 };
+use taffy::{NodeId, TaffyError, TaffyResult, TaffyTree};
+
+use crate::app::info::AppInfo;
+use crate::instance::WidgetKey;
 
 /// Defines different aspects and properties of a widget layout.
 #[derive(Clone, PartialEq, Debug)]
@@ -203,4 +210,375 @@ pub struct StyleNode {
     pub style: LayoutStyle,
     /// The children of this node.
     pub children: Vec<StyleNode>,
+    /// The key of the widget among its siblings, if it's a keyed child (see [WidgetKey]).
+    ///
+    /// Keyed nodes keep their cached layout, when siblings are inserted, removed or reordered.
+    /// Nodes without a key are matched by their position.
+    pub key: Option<WidgetKey>,
+    /// The function to measure the content size of this node, if it has no children.
+    ///
+    /// Use this for widgets with an intrinsic size, that depends on the available space (like wrapping text).
+    pub measure: Option<MeasureFn>,
+}
+
+/// A function to measure the content size of a leaf node.
+///
+/// Gets called during layout with the already known dimensions and the available space of the node
+/// and returns the size of the content.
+pub type MeasureFn =
+    Rc<dyn Fn(Vector2<Option<f32>>, Vector2<AvailableSpace>, &AppInfo) -> Vector2<f32>>;
+
+/// Caches a [MeasureFn] together with the values it was created from.
+///
+/// The layout only measures a node again, if its measure function changes,
+/// so widgets should return the same function as long as their content stays the same.
+///
+/// ```
+/// use std::rc::Rc;
+/// use maycoon_core::layout::MeasureCache;
+/// use nalgebra::Vector2;
+///
+/// let mut cache = MeasureCache::default();
+///
+/// let a = cache.get(42.0, |&size| Rc::new(move |_, _, _| Vector2::new(size, size)));
+/// let b = cache.get(42.0, |&size| Rc::new(move |_, _, _| Vector2::new(size, size)));
+///
+/// assert!(Rc::ptr_eq(&a, &b));
+/// ```
+pub struct MeasureCache<K: PartialEq> {
+    cached: Option<(K, MeasureFn)>,
+}
+
+impl<K: PartialEq> MeasureCache<K> {
+    /// Returns the cached measure function, if the key did not change.
+    /// Otherwise, creates a new measure function from the key using the given function.
+    pub fn get(&mut self, key: K, new: impl FnOnce(&K) -> MeasureFn) -> MeasureFn {
+        match &self.cached {
+            Some((cached, measure)) if *cached == key => measure.clone(),
+
+            _ => {
+                let measure = new(&key);
+
+                self.cached = Some((key, measure.clone()));
+
+                measure
+            },
+        }
+    }
+
+    /// Returns the key of the cached measure function, if any.
+    ///
+    /// Compare it with the current key to check if the widget needs to be laid out again.
+    pub fn key(&self) -> Option<&K> {
+        self.cached.as_ref().map(|(key, _)| key)
+    }
+}
+
+impl<K: PartialEq> Default for MeasureCache<K> {
+    fn default() -> Self {
+        Self { cached: None }
+    }
+}
+
+/// A persistent layout tree for a widget tree inside a window.
+///
+/// Keeps a taffy node for every [StyleNode] and only updates the nodes, that changed since the last layout.
+/// Unchanged subtrees keep their cached layout, so computing the layout of large widget trees stays cheap.
+///
+/// Nodes are matched by their [key](StyleNode::key) or their position among their siblings, if they have no key.
+/// Measure functions are compared by pointer, so a node is only measured again,
+/// if its widget returns a different [MeasureFn].
+pub(crate) struct LayoutTree {
+    taffy: TaffyTree<MeasureFn>,
+    window: NodeId,
+    size: Vector2<f32>,
+    root: Option<TreeNode>,
+    layout: Option<LayoutNode>,
+}
+
+/// A node of the [LayoutTree] with the style it was created from.
+pub(crate) struct TreeNode {
+    id: NodeId,
+    key: Option<WidgetKey>,
+    pub(crate) style: LayoutStyle,
+    measure: Option<MeasureFn>,
+    pub(crate) children: Vec<TreeNode>,
+}
+
+impl LayoutTree {
+    /// Create a new empty layout tree with a window of the given size.
+    pub(crate) fn new(size: Vector2<f32>) -> TaffyResult<Self> {
+        let mut taffy = TaffyTree::with_capacity(16);
+        let window = taffy.new_leaf(window_style(size))?;
+
+        Ok(Self {
+            taffy,
+            window,
+            size,
+            root: None,
+            layout: None,
+        })
+    }
+
+    /// Resize the window. The layout needs to be computed again.
+    pub(crate) fn resize(&mut self, size: Vector2<f32>) -> TaffyResult<()> {
+        self.size = size;
+        self.layout = None;
+        self.taffy.set_style(self.window, window_style(size))
+    }
+
+    /// Update the tree to match the given style node of the root widget.
+    ///
+    /// Only changed nodes are marked dirty. The layout needs to be computed again.
+    pub(crate) fn update(&mut self, style: &StyleNode) -> TaffyResult<()> {
+        self.layout = None;
+
+        match &mut self.root {
+            Some(root) => update_node(&mut self.taffy, root, style),
+
+            None => {
+                let root = create_node(&mut self.taffy, style)?;
+
+                self.taffy.set_children(self.window, &[root.id])?;
+                self.root = Some(root);
+
+                Ok(())
+            },
+        }
+    }
+
+    /// Remove all nodes of the widgets, so the whole tree gets laid out and measured again on the next update.
+    pub(crate) fn clear(&mut self) -> TaffyResult<()> {
+        *self = Self::new(self.size)?;
+
+        Ok(())
+    }
+
+    /// Compute the layout of the tree, if necessary, and return the layout of the root widget.
+    ///
+    /// Calls the measure functions of dirty nodes with the given app info.
+    pub(crate) fn compute(&mut self, info: &AppInfo) -> TaffyResult<&LayoutNode> {
+        let root = self
+            .root
+            .as_ref()
+            .ok_or(TaffyError::ChildIndexOutOfBounds {
+                parent: self.window,
+                child_index: 0,
+                child_count: 0,
+            })?;
+
+        if self.layout.is_none() {
+            self.taffy.compute_layout_with_measure(
+                self.window,
+                taffy::Size {
+                    width: AvailableSpace::Definite(self.size.x),
+                    height: AvailableSpace::Definite(self.size.y),
+                },
+                |known, available, _, measure, _| match measure {
+                    Some(measure) => {
+                        let size = measure(
+                            Vector2::new(known.width, known.height),
+                            Vector2::new(available.width, available.height),
+                            info,
+                        );
+
+                        taffy::Size {
+                            width: size.x,
+                            height: size.y,
+                        }
+                    },
+
+                    None => taffy::Size::ZERO,
+                },
+            )?;
+
+            self.layout = Some(collect_layout(&self.taffy, root));
+        }
+
+        Ok(self.layout.as_ref().unwrap())
+    }
+
+    /// Returns the last computed layout of the root widget or [None] if the layout needs to be computed.
+    pub(crate) fn layout(&self) -> Option<&LayoutNode> {
+        self.layout.as_ref()
+    }
+
+    /// Returns the node of the root widget with the styles of the last update or [None] if the tree is empty.
+    ///
+    /// Lets the widget inspector read the layout styles without asking the widgets for them again.
+    pub(crate) fn root(&self) -> Option<&TreeNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns the number of nodes in the tree, without the window node.
+    pub(crate) fn node_count(&self) -> usize {
+        self.root.as_ref().map_or(0, count_nodes)
+    }
+
+    /// Returns if the tree contains the root widget.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+}
+
+/// The style of the window node.
+fn window_style(size: Vector2<f32>) -> taffy::Style {
+    taffy::Style {
+        size: taffy::Size {
+            width: Dimension::Length(size.x),
+            height: Dimension::Length(size.y),
+        },
+        ..Default::default()
+    }
+}
+
+/// Create a new node with its children from the given style node.
+fn create_node(taffy: &mut TaffyTree<MeasureFn>, style: &StyleNode) -> TaffyResult<TreeNode> {
+    let id = match &style.measure {
+        Some(measure) => {
+            taffy.new_leaf_with_context(style.style.clone().into(), measure.clone())?
+        },
+        None => taffy.new_leaf(style.style.clone().into())?,
+    };
+
+    let children = style
+        .children
+        .iter()
+        .map(|child| create_node(taffy, child))
+        .collect::<TaffyResult<Vec<_>>>()?;
+
+    taffy.set_children(
+        id,
+        &children.iter().map(|child| child.id).collect::<Vec<_>>(),
+    )?;
+
+    Ok(TreeNode {
+        id,
+        key: style.key,
+        style: style.style.clone(),
+        measure: style.measure.clone(),
+        children,
+    })
+}
+
+/// Update the given node to match the given style node and mark it dirty, if it changed.
+fn update_node(
+    taffy: &mut TaffyTree<MeasureFn>,
+    node: &mut TreeNode,
+    style: &StyleNode,
+) -> TaffyResult<()> {
+    if node.style != style.style {
+        node.style = style.style.clone();
+        taffy.set_style(node.id, style.style.clone().into())?;
+    }
+
+    let measure_changed = match (&node.measure, &style.measure) {
+        (Some(old), Some(new)) => !Rc::ptr_eq(old, new),
+        (None, None) => false,
+        _ => true,
+    };
+
+    if measure_changed {
+        node.measure = style.measure.clone();
+        taffy.set_node_context(node.id, style.measure.clone())?;
+    }
+
+    let old_ids = node
+        .children
+        .iter()
+        .map(|child| child.id)
+        .collect::<Vec<_>>();
+
+    let mut old = std::mem::take(&mut node.children)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+
+    let keyed = old
+        .iter()
+        .enumerate()
+        .filter_map(|(i, child)| Some((child.as_ref()?.key?, i)))
+        .collect::<HashMap<_, _>>();
+
+    for (i, child_style) in style.children.iter().enumerate() {
+        // keyed children are matched by key, others by position
+        let matching = match child_style.key {
+            Some(key) => keyed.get(&key).copied(),
+
+            None => old
+                .get(i)
+                .and_then(|child| child.as_ref())
+                .is_some_and(|child| child.key.is_none())
+                .then_some(i),
+        };
+
+        let child = match matching.and_then(|i| old[i].take()) {
+            Some(mut child) => {
+                update_node(taffy, &mut child, child_style)?;
+                child
+            },
+
+            None => create_node(taffy, child_style)?,
+        };
+
+        node.children.push(child);
+    }
+
+    for removed in old.into_iter().flatten() {
+        remove_node(taffy, removed)?;
+    }
+
+    let ids = node
+        .children
+        .iter()
+        .map(|child| child.id)
+        .collect::<Vec<_>>();
+
+    if ids != old_ids {
+        taffy.set_children(node.id, &ids)?;
+    }
+
+    Ok(())
+}
+
+/// Count the given node and its children.
+fn count_nodes(node: &TreeNode) -> usize {
+    1 + node.children.iter().map(count_nodes).sum::<usize>()
+}
+
+/// Remove the given node and its children from the tree.
+fn remove_node(taffy: &mut TaffyTree<MeasureFn>, node: TreeNode) -> TaffyResult<()> {
+    for child in node.children {
+        remove_node(taffy, child)?;
+    }
+
+    taffy.remove(node.id)?;
+
+    Ok(())
+}
+
+/// Collect the computed layout of the given node and its children.
+fn collect_layout(taffy: &TaffyTree<MeasureFn>, node: &TreeNode) -> LayoutNode {
+    LayoutNode {
+        layout: *taffy.layout(node.id).expect("Node not in layout tree"),
+        children: node
+            .children
+            .iter()
+            .map(|child| collect_layout(taffy, child))
+            .collect(),
+    }
+}
+
+/// Compute the layout of the given style node as the root of a window with the given size.
+pub(crate) fn compute_root_layout(
+    style: &StyleNode,
+    size: Vector2<f32>,
+    info: &AppInfo,
+) -> TaffyResult<LayoutNode> {
+    let mut tree = LayoutTree::new(size)?;
+
+    tree.update(style)?;
+    tree.compute(info)?;
+
+    Ok(tree.layout.take().unwrap())
 }
@@ -7,23 +7,38 @@
 #[cfg(feature = "vg")]
 pub use vello as vg;
 
+pub use accesskit;
+
 /// Contains useful types for interacting with winit.
 pub mod window {
     pub use winit::event::*;
+    pub use winit::event_loop::ControlFlow;
     pub use winit::keyboard::*;
 }
 
+/// Contains animation features, like tweened values, easing curves and springs.
+pub mod animation;
+
 /// Contains app functionality.
 pub mod app;
 
 /// Contains the [MayConfig](config::MayConfig) struct.
 pub mod config;
 
+/// Contains widget keys and the [InstanceStore](instance::InstanceStore) for keeping widget state across rebuilds.
+pub mod instance;
+
 /// Contains useful types and functions for layout interaction.
 pub mod layout;
 
+/// Contains golden image snapshot testing features.
+pub mod snapshot;
+
 /// Contains app state management features
 pub mod state;
 
+/// Contains text shaping and layout features.
+pub mod text;
+
 /// Contains the core widget functionalities
 pub mod widget;
@@ -0,0 +1,320 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use nalgebra::Vector2;
+use png::{BitDepth, ColorType, Decoder, DecodingError, Encoder, EncodingError, Transformations};
+
+use crate::app::offscreen::RgbaImage;
+
+/// The environment variable to bless snapshots.
+///
+/// If set to anything except `0` or an empty string, [Snapshots::compare] writes the given image as the new snapshot
+/// instead of comparing them.
+pub const BLESS_ENV: &str = "MAYCOON_BLESS";
+
+/// Golden image snapshot testing.
+///
+/// Compares rendered images (e.g. from the [OffscreenRenderer](crate::app::offscreen::OffscreenRenderer))
+/// against PNG snapshots stored in a directory, with a configurable per-pixel tolerance.
+///
+/// If an image does not match its snapshot, the actual image is written to `<name>.actual.png`
+/// and a diff image to `<name>.diff.png` next to the snapshot.
+/// The diff image shows mismatched pixels in red over a faded version of the snapshot.
+///
+/// Set the [BLESS_ENV] environment variable to write new snapshots or update existing ones.
+///
+/// ```no_run
+/// use maycoon_core::app::offscreen::RgbaImage;
+/// use maycoon_core::snapshot::Snapshots;
+///
+/// let image = RgbaImage::new(1, 1, vec![255, 255, 255, 255]);
+///
+/// Snapshots::new("tests/snapshots")
+///     .with_tolerance(8)
+///     .assert_snapshot("white_pixel", &image);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Snapshots {
+    dir: PathBuf,
+    tolerance: u8,
+    max_mismatched: usize,
+}
+
+impl Snapshots {
+    /// Create a new snapshot tester, storing snapshots in the given directory.
+    ///
+    /// By default, pixels need to match exactly.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            tolerance: 0,
+            max_mismatched: 0,
+        }
+    }
+
+    /// Set the maximum difference per color channel for two pixels to still be equal.
+    pub fn with_tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the maximum amount of pixels, that may exceed the tolerance, for the images to still match.
+    pub fn with_max_mismatched_pixels(mut self, max_mismatched: usize) -> Self {
+        self.max_mismatched = max_mismatched;
+        self
+    }
+
+    /// Returns the snapshot directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the snapshot with the given name.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", name))
+    }
+
+    /// Returns if snapshots should be blessed (written) instead of compared. See [BLESS_ENV].
+    pub fn bless() -> bool {
+        std::env::var(BLESS_ENV).is_ok_and(|val| !val.is_empty() && val != "0")
+    }
+
+    /// Compare the given image against the snapshot with the given name.
+    ///
+    /// If [Snapshots::bless] is `true`, the image is written as the new snapshot instead.
+    pub fn compare(&self, name: &str, image: &RgbaImage) -> Result<(), SnapshotError> {
+        let path = self.path(name);
+        let actual_path = self.dir.join(format!("{}.actual.png", name));
+        let diff_path = self.dir.join(format!("{}.diff.png", name));
+
+        if Self::bless() {
+            std::fs::create_dir_all(&self.dir)?;
+            write_png(image, &path)?;
+        } else {
+            if !path.exists() {
+                return Err(SnapshotError::Missing(path));
+            }
+
+            let expected = read_png(&path)?;
+
+            if expected.size() != image.size() {
+                write_png(image, &actual_path)?;
+
+                return Err(SnapshotError::SizeMismatch {
+                    expected: expected.size(),
+                    actual: image.size(),
+                    actual_path,
+                });
+            }
+
+            let mut mismatched = 0;
+            let mut max_difference = 0;
+            let mut diff = Vec::with_capacity(image.data().len());
+
+            for (expected, actual) in expected
+                .data()
+                .chunks_exact(4)
+                .zip(image.data().chunks_exact(4))
+            {
+                let difference = expected
+                    .iter()
+                    .zip(actual)
+                    .map(|(e, a)| e.abs_diff(*a))
+                    .max()
+                    .unwrap_or_default();
+
+                max_difference = max_difference.max(difference);
+
+                if difference > self.tolerance {
+                    mismatched += 1;
+                    diff.extend_from_slice(&[255, 0, 0, 255]);
+                } else {
+                    // fade matching pixels, so the mismatches stand out
+                    diff.extend(expected[..3].iter().map(|c| 191 + c / 4));
+                    diff.push(255);
+                }
+            }
+
+            if mismatched > self.max_mismatched {
+                write_png(image, &actual_path)?;
+                write_png(
+                    &RgbaImage::new(image.width(), image.height(), diff),
+                    &diff_path,
+                )?;
+
+                return Err(SnapshotError::Mismatch {
+                    mismatched,
+                    max_difference,
+                    actual_path,
+                    diff_path,
+                });
+            }
+        }
+
+        // remove outdated failure output
+        for path in [actual_path, diff_path] {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compare the given image against the snapshot with the given name and panic if they don't match.
+    ///
+    /// See [Snapshots::compare] for more.
+    #[track_caller]
+    pub fn assert_snapshot(&self, name: &str, image: &RgbaImage) {
+        if let Err(err) = self.compare(name, image) {
+            panic!("Snapshot '{}' failed: {}", name, err);
+        }
+    }
+}
+
+/// Read an RGBA image from the PNG file at the given path.
+pub fn read_png(path: impl AsRef<Path>) -> Result<RgbaImage, SnapshotError> {
+    let mut decoder = Decoder::new(BufReader::new(File::open(path)?));
+
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size().unwrap_or_default()];
+    let info = reader.next_frame(&mut buf)?;
+    let buf = &buf[..info.buffer_size()];
+
+    let data = match info.color_type {
+        ColorType::Rgba => buf.to_vec(),
+        ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|px| [px[0], px[1], px[2], 255])
+            .collect(),
+        ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|px| [px[0], px[0], px[0], px[1]])
+            .collect(),
+        ColorType::Grayscale => buf.iter().flat_map(|px| [*px, *px, *px, 255]).collect(),
+        ColorType::Indexed => return Err(SnapshotError::UnsupportedFormat),
+    };
+
+    Ok(RgbaImage::new(info.width, info.height, data))
+}
+
+/// Write the given RGBA image as a PNG file to the given path.
+pub fn write_png(image: &RgbaImage, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    let mut encoder = Encoder::new(
+        BufWriter::new(File::create(path)?),
+        image.width(),
+        image.height(),
+    );
+
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+
+    writer.write_image_data(image.data())?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// An error that occurred during snapshot testing.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot does not exist yet.
+    Missing(PathBuf),
+    /// The image size does not match the snapshot size.
+    SizeMismatch {
+        /// The size of the snapshot.
+        expected: Vector2<u32>,
+        /// The size of the actual image.
+        actual: Vector2<u32>,
+        /// The path the actual image was written to.
+        actual_path: PathBuf,
+    },
+    /// Too many pixels of the image do not match the snapshot.
+    Mismatch {
+        /// The amount of pixels exceeding the tolerance.
+        mismatched: usize,
+        /// The maximum difference of a color channel.
+        max_difference: u8,
+        /// The path the actual image was written to.
+        actual_path: PathBuf,
+        /// The path the diff image was written to.
+        diff_path: PathBuf,
+    },
+    /// The PNG file has an unsupported color format.
+    UnsupportedFormat,
+    /// An IO error occurred.
+    Io(std::io::Error),
+    /// The PNG file could not be decoded.
+    Decode(DecodingError),
+    /// The PNG file could not be encoded.
+    Encode(EncodingError),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Missing(path) => write!(
+                f,
+                "snapshot {} does not exist, run with {}=1 to create it",
+                path.display(),
+                BLESS_ENV
+            ),
+            SnapshotError::SizeMismatch {
+                expected,
+                actual,
+                actual_path,
+            } => write!(
+                f,
+                "expected size {}x{}, got {}x{} (see {})",
+                expected.x,
+                expected.y,
+                actual.x,
+                actual.y,
+                actual_path.display()
+            ),
+            SnapshotError::Mismatch {
+                mismatched,
+                max_difference,
+                actual_path,
+                diff_path,
+            } => write!(
+                f,
+                "{} pixels differ by up to {} (see {} and {})",
+                mismatched,
+                max_difference,
+                actual_path.display(),
+                diff_path.display()
+            ),
+            SnapshotError::UnsupportedFormat => write!(f, "unsupported PNG color format"),
+            SnapshotError::Io(err) => write!(f, "IO error: {}", err),
+            SnapshotError::Decode(err) => write!(f, "failed to decode PNG: {}", err),
+            SnapshotError::Encode(err) => write!(f, "failed to encode PNG: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<DecodingError> for SnapshotError {
+    fn from(err: DecodingError) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl From<EncodingError> for SnapshotError {
+    fn from(err: EncodingError) -> Self {
+        Self::Encode(err)
+    }
+}
@@ -0,0 +1,884 @@
+use std::ops::Range;
+
+use nalgebra::Vector2;
+use peniko::{Brush, Color, Fill, Font};
+use rustybuzz::{Direction, Face, UnicodeBuffer};
+use unicode_bidi::{BidiInfo, Level};
+use unicode_linebreak::BreakOpportunity;
+use vello::glyph::Glyph;
+use vello::Scene;
+
+use crate::app::font_ctx::FontContext;
+
+/// A span of text with optional styling.
+///
+/// Unset properties fall back to the defaults passed to [ShapedText::new].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSpan {
+    /// The text of this span.
+    pub text: String,
+    /// The name of the font in the [FontContext].
+    pub font: Option<String>,
+    /// The font size.
+    pub font_size: Option<f32>,
+    /// The color of the text.
+    pub color: Option<Color>,
+}
+
+impl TextSpan {
+    /// Create a new text span with the given text and default styling.
+    pub fn new(text: impl ToString) -> Self {
+        Self {
+            text: text.to_string(),
+            font: None,
+            font_size: None,
+            color: None,
+        }
+    }
+
+    /// Set the font of the span by its name in the [FontContext].
+    pub fn with_font(mut self, font: impl ToString) -> Self {
+        self.font = Some(font.to_string());
+        self
+    }
+
+    /// Set the font size of the span.
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    /// Set the color of the span.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl From<String> for TextSpan {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<&str> for TextSpan {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+/// The horizontal alignment of text lines.
+///
+/// [TextAlign::Start] and [TextAlign::End] depend on the direction of the paragraph,
+/// so right-to-left text is aligned to the right by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAlign {
+    /// Align lines to the start of the paragraph direction.
+    #[default]
+    Start,
+    /// Center lines.
+    Center,
+    /// Align lines to the end of the paragraph direction.
+    End,
+}
+
+/// Text, that is shaped into glyphs, but not yet broken into lines.
+///
+/// Shaping is the expensive part of text layout, so you should keep the shaped text around
+/// and only call [ShapedText::layout] again if the available width changes.
+///
+/// The text is split into runs of the same font, size and direction, which are shaped separately.
+/// Characters, that are not supported by the font of their span, use the first supporting font of
+/// [FontContext::fallbacks].
+///
+/// ```
+/// use maycoon_core::app::font_ctx::FontContext;
+/// use maycoon_core::text::{ShapedText, TextAlign, TextSpan};
+///
+/// let fonts = FontContext::default();
+/// let shaped = ShapedText::new(
+///     &[TextSpan::new("Hello "), TextSpan::new("World").with_font_size(24.0)],
+///     &fonts,
+///     fonts.default_font(),
+///     16.0,
+/// );
+///
+/// let single_line = shaped.layout(None);
+/// assert_eq!(single_line.lines().len(), 1);
+///
+/// // wrap after "Hello "
+/// let mut wrapped = shaped.layout(Some(single_line.size().x - 1.0));
+/// wrapped.align(TextAlign::Center, single_line.size().x);
+/// assert_eq!(wrapped.lines().len(), 2);
+/// assert_eq!(wrapped.lines()[1].range(), 6..11);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShapedText {
+    text: String,
+    runs: Vec<ShapedRun>,
+    breaks: Vec<(usize, bool)>,
+    /// The sum of all glyph advances before the byte position.
+    advances: Vec<f32>,
+    metrics: LineMetrics,
+}
+
+/// A run of glyphs with the same font, size and direction.
+#[derive(Clone, Debug)]
+struct ShapedRun {
+    range: Range<usize>,
+    span: usize,
+    font: Font,
+    font_size: f32,
+    glyphs: Vec<ShapedGlyph>,
+    metrics: LineMetrics,
+}
+
+/// A glyph with its position information in pixels.
+#[derive(Copy, Clone, Debug)]
+struct ShapedGlyph {
+    id: u32,
+    /// The byte position of the first character, that this glyph belongs to.
+    cluster: usize,
+    advance: f32,
+    offset: Vector2<f32>,
+}
+
+/// The vertical metrics of a font or line.
+#[derive(Copy, Clone, Debug, Default)]
+struct LineMetrics {
+    ascent: f32,
+    descent: f32,
+    leading: f32,
+}
+
+impl LineMetrics {
+    /// Returns the metrics of the given font face or an estimate, if the font is invalid.
+    fn new(face: Option<&Face>, font_size: f32) -> Self {
+        let Some(face) = face else {
+            return Self {
+                ascent: font_size * 0.8,
+                descent: font_size * 0.2,
+                leading: 0.0,
+            };
+        };
+
+        let scale = font_size / face.units_per_em() as f32;
+
+        Self {
+            ascent: face.ascender() as f32 * scale,
+            descent: -face.descender() as f32 * scale,
+            leading: face.line_gap() as f32 * scale,
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            ascent: self.ascent.max(other.ascent),
+            descent: self.descent.max(other.descent),
+            leading: self.leading.max(other.leading),
+        }
+    }
+}
+
+impl ShapedText {
+    /// Shape the given spans using fonts from the given font context.
+    ///
+    /// Spans without a font or font size use the given default font and size.
+    pub fn new(
+        spans: &[TextSpan],
+        font_context: &FontContext,
+        default_font: &Font,
+        default_font_size: f32,
+    ) -> Self {
+        let text = spans
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect::<String>();
+        let bidi = BidiInfo::new(&text, None);
+
+        // fonts with invalid data (e.g. a half-saved font file) fall back to the default font of the context
+        let default_font = match load_face(default_font) {
+            Some(_) => default_font,
+            None => font_context.default_font(),
+        };
+
+        let fallbacks = font_context.fallbacks();
+        let fallback_faces = fallbacks
+            .iter()
+            .map(|font| Face::from_slice(font.data.as_ref(), font.index))
+            .collect::<Vec<_>>();
+
+        let mut runs = Vec::new();
+        let mut start = 0;
+
+        for (span_idx, span) in spans.iter().enumerate() {
+            let mut font = span
+                .font
+                .as_ref()
+                .map(|name| font_context.get_or_default(name))
+                .unwrap_or_else(|| default_font.clone());
+
+            if load_face(&font).is_none() {
+                font = default_font.clone();
+            }
+
+            let font_size = span.font_size.unwrap_or(default_font_size);
+            let face = load_face(&font);
+
+            let span_range = start..start + span.text.len();
+            start = span_range.end;
+
+            // split the span into runs of the same font and bidi level
+            let mut run_start = span_range.start;
+            let mut run_font: Option<usize> = None;
+
+            for (i, c) in text[span_range.clone()].char_indices() {
+                let i = span_range.start + i;
+
+                // whitespace and control characters stick to the previous font
+                let char_font = if c.is_whitespace() || c.is_control() {
+                    run_font
+                } else if face
+                    .as_ref()
+                    .is_some_and(|face| face.glyph_index(c).is_some())
+                {
+                    None
+                } else {
+                    fallback_faces.iter().position(|face| {
+                        face.as_ref()
+                            .is_some_and(|face| face.glyph_index(c).is_some())
+                    })
+                };
+
+                let split = i != run_start
+                    && (char_font != run_font
+                        || bidi.levels[i] != bidi.levels[run_start]
+                        || text[..i].ends_with('\n'));
+
+                if split {
+                    runs.push(shape_run(
+                        &text,
+                        run_start..i,
+                        span_idx,
+                        run_font.map_or(&font, |idx| &fallbacks[idx]),
+                        font_size,
+                        bidi.levels[run_start],
+                    ));
+
+                    run_start = i;
+                }
+
+                run_font = char_font;
+            }
+
+            if run_start != span_range.end {
+                runs.push(shape_run(
+                    &text,
+                    run_start..span_range.end,
+                    span_idx,
+                    run_font.map_or(&font, |idx| &fallbacks[idx]),
+                    font_size,
+                    bidi.levels[run_start],
+                ));
+            }
+        }
+
+        let mut advances = vec![0.0; text.len() + 1];
+
+        for run in &runs {
+            for glyph in &run.glyphs {
+                advances[glyph.cluster + 1] += glyph.advance;
+            }
+        }
+
+        for i in 1..advances.len() {
+            advances[i] += advances[i - 1];
+        }
+
+        let breaks = unicode_linebreak::linebreaks(&text)
+            .map(|(i, op)| (i, op == BreakOpportunity::Mandatory))
+            .collect();
+
+        Self {
+            text,
+            runs,
+            breaks,
+            advances,
+            metrics: LineMetrics::new(load_face(default_font).as_ref(), default_font_size),
+        }
+    }
+
+    /// Returns the shaped text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Break the shaped text into lines of the given maximum width.
+    ///
+    /// If the maximum width is [None], lines are only broken at line breaks (`\n`).
+    /// Words, that are wider than the maximum width, overflow.
+    ///
+    /// Lines are aligned to the start of the maximum width. Use [TextLayout::align] to change the alignment.
+    pub fn layout(&self, max_width: Option<f32>) -> TextLayout {
+        let bidi = BidiInfo::new(&self.text, None);
+
+        let mut ranges = Vec::new();
+        let mut line_start = 0;
+        let mut line_end = 0;
+
+        for (brk, mandatory) in self.breaks.iter().copied() {
+            if let Some(max_width) = max_width {
+                let end = self.trim_end(line_start..brk);
+
+                if line_end > line_start && self.width(line_start..end) > max_width {
+                    ranges.push(line_start..line_end);
+                    line_start = line_end;
+                }
+            }
+
+            line_end = brk;
+
+            if mandatory {
+                ranges.push(line_start..brk);
+                line_start = brk;
+            }
+        }
+
+        // start a new line after a trailing line break or for empty text
+        if self.text.is_empty() || self.text.ends_with('\n') {
+            ranges.push(self.text.len()..self.text.len());
+        }
+
+        let mut lines = ranges
+            .into_iter()
+            .map(|range| {
+                let mut content = range.clone();
+
+                if self.text[content.clone()].ends_with('\n') {
+                    content.end -= 1;
+                }
+
+                if self.text[content.clone()].ends_with('\r') {
+                    content.end -= 1;
+                }
+
+                self.layout_line(&bidi, range, content)
+            })
+            .collect::<Vec<_>>();
+
+        let width = lines.iter().map(|line| line.width).fold(0.0, f32::max);
+
+        let mut y = 0.0;
+
+        for line in &mut lines {
+            line.offset.y = y + line.leading / 2.0 + line.ascent;
+            y += line.height;
+        }
+
+        let mut layout = TextLayout {
+            lines,
+            size: Vector2::new(width, y),
+        };
+
+        layout.align(TextAlign::Start, max_width.unwrap_or(width));
+
+        layout
+    }
+
+    /// Lay out a single line with the given byte range and content range (without line breaks).
+    fn layout_line(&self, bidi: &BidiInfo, range: Range<usize>, content: Range<usize>) -> TextLine {
+        let paragraph = bidi
+            .paragraphs
+            .iter()
+            .find(|para| para.range.start <= range.start && range.start < para.range.end)
+            .or(bidi.paragraphs.last());
+
+        let mut runs = Vec::new();
+        let mut clusters = Vec::new();
+        let mut metrics = None::<LineMetrics>;
+        let mut x = 0.0;
+
+        if let Some(paragraph) = paragraph.filter(|_| !content.is_empty()) {
+            let (levels, visual_runs) = bidi.visual_runs(paragraph, content.clone());
+
+            for visual_run in visual_runs {
+                let mut pieces = self
+                    .runs
+                    .iter()
+                    .filter(|run| {
+                        run.range.start < visual_run.end && visual_run.start < run.range.end
+                    })
+                    .collect::<Vec<_>>();
+
+                let rtl = levels[visual_run.start].is_rtl();
+
+                if rtl {
+                    pieces.reverse();
+                }
+
+                for run in pieces {
+                    let piece =
+                        run.range.start.max(visual_run.start)..run.range.end.min(visual_run.end);
+                    let mut glyphs = Vec::new();
+                    let mut piece_clusters = Vec::<Cluster>::new();
+
+                    for glyph in run
+                        .glyphs
+                        .iter()
+                        .filter(|glyph| piece.contains(&glyph.cluster))
+                    {
+                        glyphs.push(Glyph {
+                            id: glyph.id,
+                            x: x + glyph.offset.x,
+                            y: -glyph.offset.y,
+                        });
+
+                        // glyphs of the same characters (e.g. a base and its combining marks) share a cluster
+                        match piece_clusters.last_mut() {
+                            Some(cluster) if cluster.range.start == glyph.cluster => {
+                                cluster.advance += glyph.advance;
+                            },
+
+                            _ => piece_clusters.push(Cluster {
+                                range: glyph.cluster..piece.end,
+                                x,
+                                advance: glyph.advance,
+                                rtl,
+                            }),
+                        }
+
+                        x += glyph.advance;
+                    }
+
+                    // a cluster ends where the logically next one starts
+                    let mut starts = piece_clusters
+                        .iter()
+                        .map(|cluster| cluster.range.start)
+                        .collect::<Vec<_>>();
+
+                    starts.sort_unstable();
+
+                    for cluster in &mut piece_clusters {
+                        if let Some(next) =
+                            starts.iter().find(|start| **start > cluster.range.start)
+                        {
+                            cluster.range.end = *next;
+                        }
+                    }
+
+                    clusters.extend(piece_clusters);
+
+                    metrics = Some(metrics.map_or(run.metrics, |metrics| metrics.max(run.metrics)));
+
+                    runs.push(GlyphRun {
+                        span: run.span,
+                        font: run.font.clone(),
+                        font_size: run.font_size,
+                        glyphs,
+                    });
+                }
+            }
+        }
+
+        let metrics = metrics.unwrap_or(self.metrics);
+
+        TextLine {
+            range,
+            runs,
+            clusters,
+            rtl: paragraph.is_some_and(|para| para.level.is_rtl()),
+            width: self.width(content.start..self.trim_end(content.clone())),
+            height: metrics.ascent + metrics.descent + metrics.leading,
+            ascent: metrics.ascent,
+            leading: metrics.leading,
+            offset: Vector2::new(0.0, 0.0),
+        }
+    }
+
+    /// Returns the width of the glyphs in the given byte range.
+    fn width(&self, range: Range<usize>) -> f32 {
+        self.advances[range.end] - self.advances[range.start]
+    }
+
+    /// Returns the end of the given range without trailing whitespace.
+    fn trim_end(&self, range: Range<usize>) -> usize {
+        range.start + self.text[range].trim_end().len()
+    }
+}
+
+/// Text, that is shaped and broken into lines. Create it using [ShapedText::layout].
+#[derive(Clone, Debug)]
+pub struct TextLayout {
+    lines: Vec<TextLine>,
+    size: Vector2<f32>,
+}
+
+impl TextLayout {
+    /// Returns the lines of the text.
+    pub fn lines(&self) -> &[TextLine] {
+        &self.lines
+    }
+
+    /// Returns the size of the text. The width is the width of the widest line.
+    pub fn size(&self) -> Vector2<f32> {
+        self.size
+    }
+
+    /// Returns the index of the line, that contains the given byte position.
+    ///
+    /// Positions after the last line belong to the last line.
+    pub fn line_at(&self, pos: usize) -> usize {
+        self.lines
+            .iter()
+            .position(|line| pos < line.range.end)
+            .unwrap_or(self.lines.len() - 1)
+    }
+
+    /// Returns the byte position of the caret closest to the given point, relative to the text origin.
+    ///
+    /// Points above or below the text hit the first or last line.
+    ///
+    /// ```
+    /// use maycoon_core::app::font_ctx::FontContext;
+    /// use maycoon_core::text::{ShapedText, TextSpan};
+    /// use nalgebra::Vector2;
+    ///
+    /// let fonts = FontContext::default();
+    /// let layout = ShapedText::new(&[TextSpan::new("Hello\nWorld")], &fonts, fonts.default_font(), 16.0)
+    ///     .layout(None);
+    ///
+    /// let line = &layout.lines()[layout.line_at(8)];
+    /// assert_eq!(layout.hit(Vector2::new(line.caret_x(8), line.top() + 1.0)), 8);
+    /// ```
+    pub fn hit(&self, point: Vector2<f32>) -> usize {
+        let line = self
+            .lines
+            .iter()
+            .position(|line| point.y < line.top() + line.height)
+            .unwrap_or(self.lines.len() - 1);
+
+        self.lines[line].hit(point.x)
+    }
+
+    /// Returns the next byte position after the given one, where the caret can be placed without splitting a glyph cluster.
+    ///
+    /// Returns the given position, if there's no such position.
+    ///
+    /// ```
+    /// use maycoon_core::app::font_ctx::FontContext;
+    /// use maycoon_core::text::{ShapedText, TextSpan};
+    ///
+    /// let fonts = FontContext::default();
+    /// let layout = ShapedText::new(&[TextSpan::new("e\u{301}x")], &fonts, fonts.default_font(), 16.0)
+    ///     .layout(None);
+    ///
+    /// // the combining mark belongs to the cluster of the `e`
+    /// assert_eq!(layout.next_caret(0), 3);
+    /// assert_eq!(layout.prev_caret(3), 0);
+    /// ```
+    pub fn next_caret(&self, pos: usize) -> usize {
+        self.boundaries()
+            .filter(|boundary| *boundary > pos)
+            .min()
+            .unwrap_or(pos)
+    }
+
+    /// Returns the previous byte position before the given one, where the caret can be placed without splitting a glyph cluster.
+    ///
+    /// Returns the given position, if there's no such position.
+    pub fn prev_caret(&self, pos: usize) -> usize {
+        self.boundaries()
+            .filter(|boundary| *boundary < pos)
+            .max()
+            .unwrap_or(pos)
+    }
+
+    /// Returns the boundaries of the lines and the glyph clusters on them.
+    fn boundaries(&self) -> impl Iterator<Item = usize> + '_ {
+        self.lines.iter().flat_map(|line| {
+            [line.range.start, line.range.end].into_iter().chain(
+                line.clusters
+                    .iter()
+                    .flat_map(|cluster| [cluster.range.start, cluster.range.end]),
+            )
+        })
+    }
+
+    /// Align the lines horizontally inside the given width.
+    pub fn align(&mut self, align: TextAlign, width: f32) {
+        for line in &mut self.lines {
+            let space = width - line.width;
+
+            line.offset.x = match (align, line.rtl) {
+                (TextAlign::Start, false) | (TextAlign::End, true) => 0.0,
+                (TextAlign::Start, true) | (TextAlign::End, false) => space,
+                (TextAlign::Center, _) => space / 2.0,
+            };
+        }
+    }
+
+    /// Draw the text into the given scene at the given position.
+    ///
+    /// The brush of each glyph run is selected by the `brush` function using the index of the [TextSpan].
+    pub fn draw(
+        &self,
+        scene: &mut Scene,
+        pos: Vector2<f32>,
+        hinting: bool,
+        brush: impl Fn(usize) -> Brush,
+    ) {
+        for line in &self.lines {
+            let origin = pos + line.offset;
+
+            for run in &line.runs {
+                scene
+                    .draw_glyphs(&run.font)
+                    .font_size(run.font_size)
+                    .brush(&brush(run.span))
+                    .hint(hinting)
+                    .draw(
+                        &peniko::Style::Fill(Fill::NonZero),
+                        run.glyphs.iter().map(|glyph| Glyph {
+                            id: glyph.id,
+                            x: origin.x + glyph.x,
+                            y: origin.y + glyph.y,
+                        }),
+                    );
+            }
+        }
+    }
+}
+
+/// A line of laid out text.
+#[derive(Clone, Debug)]
+pub struct TextLine {
+    range: Range<usize>,
+    runs: Vec<GlyphRun>,
+    clusters: Vec<Cluster>,
+    rtl: bool,
+    width: f32,
+    height: f32,
+    ascent: f32,
+    leading: f32,
+    offset: Vector2<f32>,
+}
+
+impl TextLine {
+    /// Returns the byte range of the line in the text, including line breaks.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the glyph runs of the line in visual order.
+    pub fn runs(&self) -> &[GlyphRun] {
+        &self.runs
+    }
+
+    /// Returns if the paragraph of this line is right-to-left.
+    pub fn is_rtl(&self) -> bool {
+        self.rtl
+    }
+
+    /// Returns the width of the line without trailing whitespace.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Returns the height of the line.
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Returns the position of the line start on the baseline, relative to the text origin.
+    pub fn offset(&self) -> Vector2<f32> {
+        self.offset
+    }
+
+    /// Returns the top of the line, relative to the text origin.
+    pub fn top(&self) -> f32 {
+        self.offset.y - self.ascent - self.leading / 2.0
+    }
+
+    /// Returns the x offset of the caret at the given byte position, relative to the text origin.
+    ///
+    /// Positions inside a cluster of glyphs (e.g. a ligature) are interpolated.
+    /// Positions outside the line are placed at the start or end of the line.
+    pub fn caret_x(&self, pos: usize) -> f32 {
+        // a position between two clusters belongs to the logically next one
+        let cluster = self
+            .clusters
+            .iter()
+            .find(|cluster| cluster.range.contains(&pos))
+            .or_else(|| {
+                self.clusters
+                    .iter()
+                    .find(|cluster| cluster.range.end == pos)
+            });
+
+        let x = match cluster {
+            Some(cluster) => cluster.x(pos),
+            None if (pos > self.range.start) != self.rtl => self.width,
+            None => 0.0,
+        };
+
+        self.offset.x + x
+    }
+
+    /// Returns the byte position of the caret closest to the given x offset, relative to the text origin.
+    ///
+    /// Only the boundaries of glyph clusters are hit, so the caret never splits a cluster.
+    pub fn hit(&self, x: f32) -> usize {
+        let x = x - self.offset.x;
+
+        self.clusters
+            .iter()
+            .flat_map(|cluster| {
+                [cluster.range.start, cluster.range.end].map(|pos| (pos, cluster.x(pos)))
+            })
+            .min_by(|(_, a), (_, b)| (a - x).abs().total_cmp(&(b - x).abs()))
+            .map_or(self.range.start, |(pos, _)| pos)
+    }
+
+    /// Returns the horizontal extents of the given byte range on this line, relative to the text origin.
+    ///
+    /// Bidirectional text may split the range into multiple visual pieces, which are returned from left to right.
+    pub fn selection(&self, range: Range<usize>) -> Vec<(f32, f32)> {
+        let mut pieces = Vec::<(f32, f32)>::new();
+
+        for cluster in &self.clusters {
+            let start = range.start.max(cluster.range.start);
+            let end = range.end.min(cluster.range.end);
+
+            if start >= end {
+                continue;
+            }
+
+            let (a, b) = (cluster.x(start), cluster.x(end));
+            let (left, right) = (self.offset.x + a.min(b), self.offset.x + a.max(b));
+
+            match pieces.last_mut() {
+                Some(last) if (last.1 - left).abs() < 0.5 => last.1 = right,
+                _ => pieces.push((left, right)),
+            }
+        }
+
+        pieces
+    }
+}
+
+/// A cluster of glyphs on a line, which belongs to the same characters.
+#[derive(Clone, Debug)]
+struct Cluster {
+    /// The byte range of the characters.
+    range: Range<usize>,
+    /// The x offset of the left edge, relative to the line start.
+    x: f32,
+    advance: f32,
+    rtl: bool,
+}
+
+impl Cluster {
+    /// Returns the x offset of the caret at the given byte position inside this cluster.
+    fn x(&self, pos: usize) -> f32 {
+        let progress = (pos.clamp(self.range.start, self.range.end) - self.range.start) as f32
+            / (self.range.end - self.range.start) as f32;
+
+        if self.rtl {
+            self.x + self.advance * (1.0 - progress)
+        } else {
+            self.x + self.advance * progress
+        }
+    }
+}
+
+/// A run of glyphs with the same font and font size.
+#[derive(Clone, Debug)]
+pub struct GlyphRun {
+    span: usize,
+    font: Font,
+    font_size: f32,
+    glyphs: Vec<Glyph>,
+}
+
+impl GlyphRun {
+    /// Returns the index of the [TextSpan] this run belongs to.
+    pub fn span(&self) -> usize {
+        self.span
+    }
+
+    /// Returns the font of this run.
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Returns the font size of this run.
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Returns the glyphs of this run, relative to the line start on the baseline.
+    pub fn glyphs(&self) -> &[Glyph] {
+        &self.glyphs
+    }
+}
+
+/// Load the font face of the given font or [None], if the font data is invalid.
+fn load_face(font: &Font) -> Option<Face<'_>> {
+    Face::from_slice(font.data.as_ref(), font.index)
+}
+
+/// Shape the given range of the text using the given font.
+fn shape_run(
+    text: &str,
+    range: Range<usize>,
+    span: usize,
+    font: &Font,
+    font_size: f32,
+    level: Level,
+) -> ShapedRun {
+    // text in invalid fonts has no glyphs
+    let Some(face) = load_face(font) else {
+        return ShapedRun {
+            range,
+            span,
+            font: font.clone(),
+            font_size,
+            glyphs: Vec::new(),
+            metrics: LineMetrics::new(None, font_size),
+        };
+    };
+
+    let scale = font_size / face.units_per_em() as f32;
+
+    let mut buffer = UnicodeBuffer::new();
+
+    buffer.push_str(&text[range.clone()]);
+    buffer.set_direction(if level.is_rtl() {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    });
+
+    let output = rustybuzz::shape(&face, &[], buffer);
+
+    let glyphs = output
+        .glyph_infos()
+        .iter()
+        .zip(output.glyph_positions())
+        .map(|(info, pos)| ShapedGlyph {
+            id: info.glyph_id,
+            cluster: range.start + info.cluster as usize,
+            advance: pos.x_advance as f32 * scale,
+            offset: Vector2::new(pos.x_offset as f32 * scale, pos.y_offset as f32 * scale),
+        })
+        .collect();
+
+    ShapedRun {
+        range,
+        span,
+        font: font.clone(),
+        font_size,
+        glyphs,
+        metrics: LineMetrics::new(Some(&face), font_size),
+    }
+}
@@ -1,10 +1,14 @@
+use accesskit::Role;
 use vello::Scene;
 
 use maycoon_theme::id::WidgetId;
 use maycoon_theme::theme::Theme;
 
+use crate::app::accessibility::AccessNode;
 use crate::app::info::AppInfo;
+use crate::app::inspector::InspectNode;
 use crate::app::update::Update;
+use crate::instance::InstanceStore;
 use crate::layout::{LayoutNode, StyleNode};
 use crate::state::State;
 
@@ -25,4 +29,71 @@ pub trait Widget<S: State> {
     fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update;
     /// Return the widget id.
     fn widget_id(&mut self) -> WidgetId;
+
+    /// Return the node of this widget and its children for the widget inspector.
+    ///
+    /// The children must match the children of the [StyleNode]. Returns a node without children by default,
+    /// so widgets with child widgets should override this.
+    fn inspect(&mut self, _: &S) -> InspectNode {
+        InspectNode::new(self.widget_id())
+    }
+
+    /// Return the node of this widget and its children for assistive technologies (e.g. screen readers).
+    ///
+    /// The children must match the children of the [StyleNode]. Returns a generic container without children by default,
+    /// so widgets with child widgets or a meaning for the user should override this.
+    fn accessibility(&mut self, _: &S) -> AccessNode {
+        AccessNode::new(Role::GenericContainer)
+    }
+
+    /// Save the instance state of this widget and its children (e.g. hover, drag or animation state) into the given store.
+    ///
+    /// Gets called before a state dependent widget is rebuilt. Does nothing by default.
+    fn save_state(&mut self, _: &mut InstanceStore) {}
+
+    /// Restore the instance state, that was saved by the previous instance of this widget using [Widget::save_state].
+    ///
+    /// Gets called after a state dependent widget was rebuilt. Does nothing by default.
+    fn restore_state(&mut self, _: &S, _: &mut InstanceStore) {}
+}
+
+impl<S: State, W: Widget<S> + ?Sized> Widget<S> for Box<W> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        self.as_mut().render(scene, theme, info, layout_node, state)
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        self.as_mut().layout_style(state)
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.as_mut().update(layout, state, info)
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        self.as_mut().widget_id()
+    }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        self.as_mut().inspect(state)
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        self.as_mut().accessibility(state)
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        self.as_mut().save_state(store)
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        self.as_mut().restore_state(state, store)
+    }
 }
@@ -0,0 +1,211 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use maycoon_core::app::offscreen::RgbaImage;
+use maycoon_core::snapshot::{read_png, write_png, SnapshotError, Snapshots, BLESS_ENV};
+use nalgebra::Vector2;
+
+/// A 2x1 image with the given pixels.
+fn image(left: [u8; 4], right: [u8; 4]) -> RgbaImage {
+    RgbaImage::new(2, 1, [left, right].concat())
+}
+
+const GRAY: [u8; 4] = [100, 100, 100, 255];
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// Create an empty snapshot directory inside the temp directory.
+fn snapshot_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("maycoon-snapshot-{}-{}", std::process::id(), name));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Run the given function with blessing enabled or disabled.
+///
+/// The environment is shared by all tests, so they run one after another.
+fn with_bless<T>(bless: bool, f: impl FnOnce() -> T) -> T {
+    static ENV: Mutex<()> = Mutex::new(());
+
+    let _lock = ENV.lock().unwrap_or_else(|err| err.into_inner());
+
+    if bless {
+        std::env::set_var(BLESS_ENV, "1");
+    } else {
+        std::env::remove_var(BLESS_ENV);
+    }
+
+    f()
+}
+
+#[test]
+fn compare_respects_tolerance() {
+    let dir = snapshot_dir("tolerance");
+    let snapshots = Snapshots::new(&dir);
+
+    write_png(&image(GRAY, WHITE), snapshots.path("image")).unwrap();
+
+    let changed = image([105, 100, 95, 255], WHITE);
+
+    with_bless(false, || {
+        assert!(snapshots
+            .clone()
+            .with_tolerance(5)
+            .compare("image", &changed)
+            .is_ok());
+
+        match snapshots.with_tolerance(4).compare("image", &changed) {
+            Err(SnapshotError::Mismatch {
+                mismatched,
+                max_difference,
+                ..
+            }) => assert_eq!((mismatched, max_difference), (1, 5)),
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+    });
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compare_allows_max_mismatched_pixels() {
+    let dir = snapshot_dir("max-mismatched");
+    let snapshots = Snapshots::new(&dir);
+
+    write_png(&image(GRAY, GRAY), snapshots.path("image")).unwrap();
+
+    let changed = image(WHITE, WHITE);
+
+    with_bless(false, || {
+        assert!(snapshots
+            .clone()
+            .with_max_mismatched_pixels(2)
+            .compare("image", &changed)
+            .is_ok());
+
+        assert!(matches!(
+            snapshots
+                .with_max_mismatched_pixels(1)
+                .compare("image", &changed),
+            Err(SnapshotError::Mismatch { mismatched: 2, .. })
+        ));
+    });
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compare_writes_diff_on_failure() {
+    let dir = snapshot_dir("diff");
+    let snapshots = Snapshots::new(&dir);
+
+    write_png(&image(GRAY, WHITE), snapshots.path("image")).unwrap();
+
+    let (actual_path, diff_path) = with_bless(false, || {
+        match snapshots.compare("image", &image(GRAY, [0, 0, 0, 255])) {
+            Err(SnapshotError::Mismatch {
+                actual_path,
+                diff_path,
+                ..
+            }) => (actual_path, diff_path),
+            other => panic!("expected a mismatch, got {other:?}"),
+        }
+    });
+
+    assert_eq!(actual_path, dir.join("image.actual.png"));
+    assert_eq!(diff_path, dir.join("image.diff.png"));
+    assert_eq!(
+        read_png(&actual_path).unwrap().data(),
+        image(GRAY, [0, 0, 0, 255]).data()
+    );
+
+    // mismatched pixels are red, matching ones are faded
+    let diff = read_png(&diff_path).unwrap();
+
+    assert_eq!(diff.pixel(0, 0), Some([216, 216, 216, 255]));
+    assert_eq!(diff.pixel(1, 0), Some([255, 0, 0, 255]));
+
+    // the failure output is removed, once the image matches again
+    with_bless(false, || {
+        snapshots.assert_snapshot("image", &image(GRAY, WHITE));
+    });
+
+    assert!(!actual_path.exists());
+    assert!(!diff_path.exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compare_reports_size_mismatch() {
+    let dir = snapshot_dir("size");
+    let snapshots = Snapshots::new(&dir);
+
+    write_png(&image(GRAY, WHITE), snapshots.path("image")).unwrap();
+
+    let result = with_bless(false, || {
+        snapshots.compare("image", &RgbaImage::new(1, 1, GRAY.to_vec()))
+    });
+
+    match result {
+        Err(SnapshotError::SizeMismatch {
+            expected,
+            actual,
+            actual_path,
+        }) => {
+            assert_eq!(expected, Vector2::new(2, 1));
+            assert_eq!(actual, Vector2::new(1, 1));
+            assert_eq!(read_png(actual_path).unwrap().size(), Vector2::new(1, 1));
+        },
+        other => panic!("expected a size mismatch, got {other:?}"),
+    }
+
+    assert!(!dir.join("image.diff.png").exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compare_reports_missing_snapshot() {
+    let dir = snapshot_dir("missing");
+    let snapshots = Snapshots::new(&dir);
+
+    let result = with_bless(false, || snapshots.compare("image", &image(GRAY, WHITE)));
+
+    assert!(matches!(result, Err(SnapshotError::Missing(path)) if path == dir.join("image.png")));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn bless_writes_snapshot() {
+    let dir = snapshot_dir("bless");
+    let snapshots = Snapshots::new(dir.join("nested"));
+
+    with_bless(true, || {
+        assert!(Snapshots::bless());
+
+        snapshots.assert_snapshot("image", &image(GRAY, WHITE));
+    });
+
+    assert_eq!(
+        read_png(snapshots.path("image")).unwrap().data(),
+        image(GRAY, WHITE).data()
+    );
+
+    // blessing a changed image replaces the snapshot
+    with_bless(true, || {
+        snapshots.assert_snapshot("image", &image(WHITE, WHITE));
+    });
+
+    with_bless(false, || {
+        assert!(!Snapshots::bless());
+
+        snapshots.assert_snapshot("image", &image(WHITE, WHITE));
+    });
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,8 +1,189 @@
-/// Global theme values for all widgets to use.
+use peniko::Color;
+
+/// The style cascade of a theme, which passes inheritable style values from parent widgets to their children.
 ///
-/// This may be used to invert the text color of widgets that are inside a container.
-#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+/// Parents [push](Globals::push) their [StyleOverrides] before rendering their children and [pop](Globals::pop) them afterward.
+/// Children read the inherited values using [Globals::context].
+/// Values, which are not overridden, are inherited from the parent or from the root context of the theme.
+///
+/// ```
+/// use maycoon_theme::globals::{Globals, StyleOverrides};
+///
+/// let mut globals = Globals::default();
+///
+/// globals.push(&StyleOverrides::new().with_font_size(14.0).with_opacity(0.5));
+/// globals.push(&StyleOverrides::new().with_disabled(true).with_opacity(0.5));
+///
+/// assert_eq!(globals.context().font_size, Some(14.0));
+/// assert_eq!(globals.context().opacity, 0.25);
+/// assert!(globals.context().disabled);
+///
+/// globals.pop();
+/// globals.pop();
+///
+/// assert_eq!(globals.context().font_size, None);
+/// ```
+#[derive(Clone, Default, Debug, PartialEq)]
 pub struct Globals {
-    /// Invert text color.
+    root: StyleContext,
+    stack: Vec<StyleContext>,
+}
+
+impl Globals {
+    /// Create a new style cascade with the given root context.
+    pub fn new(root: StyleContext) -> Self {
+        Self {
+            root,
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns the inherited style values of the widget, that is currently rendered.
+    pub fn context(&self) -> &StyleContext {
+        self.stack.last().unwrap_or(&self.root)
+    }
+
+    /// Returns the root context, which is inherited by all widgets.
+    pub fn root(&self) -> &StyleContext {
+        &self.root
+    }
+
+    /// Returns the mutable root context, which is inherited by all widgets.
+    pub fn root_mut(&mut self) -> &mut StyleContext {
+        &mut self.root
+    }
+
+    /// Apply the given overrides to the current context, until the next call to [Globals::pop].
+    pub fn push(&mut self, overrides: &StyleOverrides) {
+        self.stack.push(self.context().apply(overrides));
+    }
+
+    /// Restore the context before the last call to [Globals::push].
+    pub fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Returns the number of pushed contexts.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+/// Style values, which are inherited by child widgets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleContext {
+    /// The color of text. Text uses the color of its theme style, if [None].
+    pub text_color: Option<Color>,
+    /// If text should use its inverted color (e.g. on top of an accent colored background).
     pub invert_text_color: bool,
+    /// The name of the font of text. Text uses the default font, if [None].
+    pub font: Option<String>,
+    /// The font size of text. Text uses its default size, if [None].
+    pub font_size: Option<f32>,
+    /// The opacity, which all colors are multiplied with. `1.0` by default.
+    pub opacity: f32,
+    /// If the widgets are disabled. Disabled widgets use the disabled color of the theme and ignore input.
+    pub disabled: bool,
+}
+
+impl StyleContext {
+    /// Returns the context of a child with the given overrides.
+    ///
+    /// Overridden values replace the inherited ones, except for the opacity, which is multiplied.
+    pub fn apply(&self, overrides: &StyleOverrides) -> Self {
+        Self {
+            text_color: overrides.text_color.or(self.text_color),
+            invert_text_color: overrides
+                .invert_text_color
+                .unwrap_or(self.invert_text_color),
+            font: overrides.font.clone().or_else(|| self.font.clone()),
+            font_size: overrides.font_size.or(self.font_size),
+            opacity: self.opacity * overrides.opacity.unwrap_or(1.0),
+            disabled: overrides.disabled.unwrap_or(self.disabled),
+        }
+    }
+
+    /// Returns the given color with the opacity of the context applied.
+    pub fn color(&self, color: Color) -> Color {
+        if self.opacity < 1.0 {
+            color.with_alpha_factor(self.opacity)
+        } else {
+            color
+        }
+    }
+}
+
+impl Default for StyleContext {
+    fn default() -> Self {
+        Self {
+            text_color: None,
+            invert_text_color: false,
+            font: None,
+            font_size: None,
+            opacity: 1.0,
+            disabled: false,
+        }
+    }
+}
+
+/// Overrides of inheritable style values. Values, which are not set, are inherited from the parent.
+///
+/// Use the `Styled` widget of `maycoon-widgets` to override the style of any widget and its children.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct StyleOverrides {
+    /// Override the color of text.
+    pub text_color: Option<Color>,
+    /// Override if text should use its inverted color.
+    pub invert_text_color: Option<bool>,
+    /// Override the font of text.
+    pub font: Option<String>,
+    /// Override the font size of text.
+    pub font_size: Option<f32>,
+    /// Multiply the opacity.
+    pub opacity: Option<f32>,
+    /// Override if the widgets are disabled.
+    pub disabled: Option<bool>,
+}
+
+impl StyleOverrides {
+    /// Create new overrides, which inherit all values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Override the color of text.
+    pub fn with_text_color(mut self, color: Color) -> Self {
+        self.text_color = Some(color);
+        self
+    }
+
+    /// Override if text should use its inverted color.
+    pub fn with_invert_text_color(mut self, invert: bool) -> Self {
+        self.invert_text_color = Some(invert);
+        self
+    }
+
+    /// Override the font of text.
+    pub fn with_font(mut self, font: impl ToString) -> Self {
+        self.font = Some(font.to_string());
+        self
+    }
+
+    /// Override the font size of text.
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Multiply the opacity of the widgets with the given opacity.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = Some(opacity.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Override if the widgets are disabled.
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = Some(disabled);
+        self
+    }
 }
@@ -3,15 +3,24 @@ use std::fmt::{Debug, Display, Formatter};
 /// An identifier for a widget. This is not for instantiated widgets, but for the widget types in general.
 /// It contains a namespace, which should be the crate name and the id of the widget.
 ///
+/// An id can also name a style variant of the widget using a class (e.g. `Button.danger`),
+/// so themes can provide different styles for instances of the same widget type.
+///
 /// ```
 /// use maycoon_theme::id::WidgetId;
 ///
 /// WidgetId::new("fancy_text_widget", "FancyText");
+///
+/// let danger = WidgetId::new("maycoon-widgets", "Button").with_class("danger");
+///
+/// assert_eq!(danger.to_string(), "maycoon-widgets:Button.danger");
+/// assert_eq!(danger.base(), WidgetId::new("maycoon-widgets", "Button"));
 /// ```
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct WidgetId {
     namespace: String,
     id: String,
+    class: Option<String>,
 }
 
 impl WidgetId {
@@ -26,9 +35,16 @@ impl WidgetId {
         Self {
             namespace: namespace.to_string(),
             id: id.to_string(),
+            class: None,
         }
     }
 
+    /// Set the class of the style variant and return itself.
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+
     /// Returns the namespace of the widget id.
     pub fn namespace(&self) -> &str {
         &self.namespace
@@ -38,10 +54,26 @@ impl WidgetId {
     pub fn id(&self) -> &str {
         &self.id
     }
+
+    /// Returns the class of the style variant or [None] if the id names the widget itself.
+    pub fn class(&self) -> Option<&str> {
+        self.class.as_deref()
+    }
+
+    /// Returns the id of the widget without the class of the style variant.
+    pub fn base(&self) -> WidgetId {
+        Self::new(&self.namespace, &self.id)
+    }
 }
 
 impl Display for WidgetId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}:{}", self.namespace, self.id)
+        write!(f, "{}:{}", self.namespace, self.id)?;
+
+        if let Some(class) = &self.class {
+            write!(f, ".{}", class)?;
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use peniko::{Brush, Color, Gradient};
+
+use crate::id::WidgetId;
+use crate::style::{DefaultStyles, StyleVal};
+use crate::theme::Theme;
+
+/// A typed key of a style value with a fallback, which is used if the style doesn't contain the value.
+///
+/// Widgets declare their keys as constants and look them up using [Style::resolve](crate::style::Style::resolve),
+/// so a theme, that misses a value, falls back to its [DefaultStyles] instead of panicking.
+///
+/// ```
+/// use peniko::Color;
+/// use maycoon_theme::key::StyleKey;
+/// use maycoon_theme::style::{DefaultStyles, Style};
+///
+/// const COLOR_IDLE: StyleKey<Color> = StyleKey::new("color_idle", |defaults| defaults.interactive().inactive());
+///
+/// let defaults = DefaultStyles::default();
+///
+/// assert_eq!(Style::new().resolve(&COLOR_IDLE, &defaults), defaults.interactive().inactive());
+/// assert_eq!(Style::new().with_key(&COLOR_IDLE, Color::RED).resolve(&COLOR_IDLE, &defaults), Color::RED);
+/// ```
+pub struct StyleKey<T: StyleType> {
+    name: &'static str,
+    fallback: fn(&DefaultStyles) -> T,
+}
+
+impl<T: StyleType> StyleKey<T> {
+    /// Create a new style key with the given name and a function returning the fallback value from the default styles.
+    pub const fn new(name: &'static str, fallback: fn(&DefaultStyles) -> T) -> Self {
+        Self { name, fallback }
+    }
+
+    /// Returns the name of the key.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the fallback value of the key.
+    pub fn fallback(&self, defaults: &DefaultStyles) -> T {
+        (self.fallback)(defaults)
+    }
+
+    /// Returns the untyped description of the key, which widgets use to declare their keys.
+    pub const fn info(&self) -> KeyInfo {
+        KeyInfo {
+            name: self.name,
+            kind: T::KIND,
+        }
+    }
+}
+
+/// A type, which can be stored in a [StyleVal].
+pub trait StyleType: Sized {
+    /// The kind of style value, that stores this type.
+    const KIND: StyleKind;
+
+    /// Returns the value, if the style value has a matching kind.
+    fn from_val(val: &StyleVal) -> Option<Self>;
+
+    /// Wraps the value into a style value.
+    fn into_val(self) -> StyleVal;
+}
+
+macro_rules! style_type {
+    ($ty:ty, $kind:ident) => {
+        impl StyleType for $ty {
+            const KIND: StyleKind = StyleKind::$kind;
+
+            fn from_val(val: &StyleVal) -> Option<Self> {
+                match val {
+                    StyleVal::$kind(value) => Some(value.clone()),
+                    _ => None,
+                }
+            }
+
+            fn into_val(self) -> StyleVal {
+                StyleVal::$kind(self)
+            }
+        }
+    };
+}
+
+style_type!(Color, Color);
+style_type!(Gradient, Gradient);
+style_type!(f32, Float);
+style_type!(i32, Int);
+style_type!(u32, UInt);
+style_type!(bool, Bool);
+
+impl StyleType for Brush {
+    const KIND: StyleKind = StyleKind::Brush;
+
+    /// Colors and gradients are accepted as brushes too.
+    fn from_val(val: &StyleVal) -> Option<Self> {
+        match val {
+            StyleVal::Brush(brush) => Some(brush.clone()),
+            StyleVal::Color(color) => Some(Brush::Solid(*color)),
+            StyleVal::Gradient(gradient) => Some(Brush::Gradient(gradient.clone())),
+            _ => None,
+        }
+    }
+
+    fn into_val(self) -> StyleVal {
+        StyleVal::Brush(self)
+    }
+}
+
+/// The kind of style value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StyleKind {
+    /// A [StyleVal::Color].
+    Color,
+    /// A [StyleVal::Gradient].
+    Gradient,
+    /// A [StyleVal::Brush].
+    Brush,
+    /// A [StyleVal::Float].
+    Float,
+    /// A [StyleVal::Int].
+    Int,
+    /// A [StyleVal::UInt].
+    UInt,
+    /// A [StyleVal::Bool].
+    Bool,
+}
+
+impl StyleKind {
+    /// Returns the kind of the given style value.
+    pub fn of(val: &StyleVal) -> Self {
+        match val {
+            StyleVal::Color(_) => Self::Color,
+            StyleVal::Gradient(_) => Self::Gradient,
+            StyleVal::Brush(_) => Self::Brush,
+            StyleVal::Float(_) => Self::Float,
+            StyleVal::Int(_) => Self::Int,
+            StyleVal::UInt(_) => Self::UInt,
+            StyleVal::Bool(_) => Self::Bool,
+        }
+    }
+
+    /// Returns if a key of this kind accepts values of the given kind.
+    ///
+    /// Brushes also accept colors and gradients.
+    pub fn accepts(self, kind: StyleKind) -> bool {
+        self == kind || (self == Self::Brush && matches!(kind, Self::Color | Self::Gradient))
+    }
+}
+
+impl Display for StyleKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Color => "color",
+            Self::Gradient => "gradient",
+            Self::Brush => "brush",
+            Self::Float => "float",
+            Self::Int => "int",
+            Self::UInt => "unsigned int",
+            Self::Bool => "bool",
+        })
+    }
+}
+
+/// The name and kind of a style key, without its type. Returned by [StyleKey::info].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyInfo {
+    /// The name of the key.
+    pub name: &'static str,
+    /// The kind of value, the key expects.
+    pub kind: StyleKind,
+}
+
+/// The style keys of widgets, which a theme should provide.
+///
+/// Use [StyleSchema::validate] to find missing or mistyped values of a theme up front (e.g. right after loading a theme file),
+/// instead of noticing the fallback colors at runtime.
+///
+/// ```
+/// use peniko::Color;
+/// use maycoon_theme::id::WidgetId;
+/// use maycoon_theme::key::{StyleKey, StyleSchema};
+/// use maycoon_theme::theme::celeste::CelesteTheme;
+///
+/// const COLOR_IDLE: StyleKey<Color> = StyleKey::new("color_idle", |defaults| defaults.interactive().inactive());
+///
+/// let schema = StyleSchema::new()
+///     .with_widget(WidgetId::new("maycoon-widgets", "Button"), &[COLOR_IDLE.info()]);
+///
+/// assert!(schema.validate(&CelesteTheme::light()).is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StyleSchema {
+    widgets: BTreeMap<WidgetId, Vec<KeyInfo>>,
+}
+
+impl StyleSchema {
+    /// Create a new schema without any widgets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the keys of the given widget. Keys of an already registered widget are added.
+    pub fn register(&mut self, id: WidgetId, keys: &[KeyInfo]) {
+        self.widgets.entry(id).or_default().extend_from_slice(keys);
+    }
+
+    /// Register the keys of the given widget and return itself.
+    pub fn with_widget(mut self, id: WidgetId, keys: &[KeyInfo]) -> Self {
+        self.register(id, keys);
+        self
+    }
+
+    /// Returns the registered keys of the given widget.
+    pub fn keys(&self, id: &WidgetId) -> Option<&[KeyInfo]> {
+        self.widgets.get(id).map(Vec::as_slice)
+    }
+
+    /// Check the styles of all registered widgets in the given theme and return the issues.
+    ///
+    /// Returns an empty list, if the theme provides all keys with the expected kinds.
+    /// Style variants are not checked, since they only contain the values, that differ from the widget style.
+    pub fn validate(&self, theme: &dyn Theme) -> Vec<StyleIssue> {
+        let mut issues = Vec::new();
+
+        for (id, keys) in &self.widgets {
+            if keys.is_empty() {
+                continue;
+            }
+
+            let Some(style) = theme.of(id.clone()) else {
+                issues.push(StyleIssue::MissingStyle { widget: id.clone() });
+                continue;
+            };
+
+            for key in keys {
+                match style.get(key.name) {
+                    None => issues.push(StyleIssue::MissingKey {
+                        widget: id.clone(),
+                        key: key.name,
+                        expected: key.kind,
+                    }),
+
+                    Some(val) if !key.kind.accepts(StyleKind::of(&val)) => {
+                        issues.push(StyleIssue::WrongKind {
+                            widget: id.clone(),
+                            key: key.name,
+                            expected: key.kind,
+                            found: StyleKind::of(&val),
+                        })
+                    },
+
+                    _ => (),
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+/// An issue of a theme, found by [StyleSchema::validate].
+#[derive(Clone, Debug, PartialEq)]
+pub enum StyleIssue {
+    /// The theme has no style for the widget, so all keys use their fallbacks.
+    MissingStyle {
+        /// The widget without style.
+        widget: WidgetId,
+    },
+    /// The style of the widget doesn't contain the key, so its fallback is used.
+    MissingKey {
+        /// The widget of the style.
+        widget: WidgetId,
+        /// The name of the missing key.
+        key: &'static str,
+        /// The kind of value, the key expects.
+        expected: StyleKind,
+    },
+    /// The style of the widget contains the key with a wrong kind of value, so its fallback is used.
+    WrongKind {
+        /// The widget of the style.
+        widget: WidgetId,
+        /// The name of the mistyped key.
+        key: &'static str,
+        /// The kind of value, the key expects.
+        expected: StyleKind,
+        /// The kind of value in the style.
+        found: StyleKind,
+    },
+}
+
+impl Display for StyleIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleIssue::MissingStyle { widget } => write!(f, "missing style for {}", widget),
+            StyleIssue::MissingKey {
+                widget,
+                key,
+                expected,
+            } => write!(
+                f,
+                "missing {} `{}` in the style of {}",
+                expected, key, widget
+            ),
+            StyleIssue::WrongKind {
+                widget,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected a {} for `{}` in the style of {}, found a {}",
+                expected, key, widget, found
+            ),
+        }
+    }
+}
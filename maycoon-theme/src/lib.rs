@@ -4,10 +4,12 @@
 //!
 //! Contains themes and widget styles.
 
-/// Contains the [globals::Globals] struct.
+/// Contains the [globals::Globals] style cascade.
 pub mod globals;
 /// Contains the [id::WidgetId] struct.
 pub mod id;
+/// Contains typed [key::StyleKey]s and the [key::StyleSchema] to validate themes.
+pub mod key;
 /// Contains styling structures.
 pub mod style;
 /// Contains the [theme::Theme] trait and built-in themes.
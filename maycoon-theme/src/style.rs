@@ -1,6 +1,8 @@
 use dashmap::DashMap;
 use peniko::{Brush, Color, Gradient};
 
+use crate::key::{StyleKey, StyleType};
+
 /// Styling map for defining widget appearance.
 #[derive(Clone, Debug)]
 pub struct Style {
@@ -27,6 +29,13 @@ impl Style {
         self.map.remove(&name.to_string());
     }
 
+    /// Insert all values of the given style, replacing values with the same name.
+    pub fn merge(&mut self, other: &Style) {
+        for entry in other.map.iter() {
+            self.map.insert(entry.key().clone(), entry.value().clone());
+        }
+    }
+
     /// Insert a style value with the given name into the style map.
     pub fn with_value(self, name: impl ToString, value: StyleVal) -> Self {
         self.map.insert(name.to_string(), value);
@@ -74,6 +83,30 @@ impl Style {
         self.map.insert(name.to_string(), StyleVal::UInt(value));
     }
 
+    /// Set the value of the given typed key.
+    pub fn set_key<T: StyleType>(&mut self, key: &StyleKey<T>, value: T) {
+        self.map.insert(key.name().to_string(), value.into_val());
+    }
+
+    /// Set the value of the given typed key and return itself.
+    pub fn with_key<T: StyleType>(mut self, key: &StyleKey<T>, value: T) -> Self {
+        self.set_key(key, value);
+        self
+    }
+
+    /// Get the value of the given typed key. Returns [None] if the value does not exist or has another type.
+    pub fn get_key<T: StyleType>(&self, key: &StyleKey<T>) -> Option<T> {
+        self.map
+            .get(key.name())
+            .and_then(|val| T::from_val(val.value()))
+    }
+
+    /// Get the value of the given typed key or its fallback from the given default styles,
+    /// if the value does not exist or has another type.
+    pub fn resolve<T: StyleType>(&self, key: &StyleKey<T>, defaults: &DefaultStyles) -> T {
+        self.get_key(key).unwrap_or_else(|| key.fallback(defaults))
+    }
+
     /// Get a style value by name. Returns [None] if the value name does not exist.
     pub fn get(&self, name: impl ToString) -> Option<StyleVal> {
         self.map.get(&name.to_string()).map(|val| val.clone())
@@ -6,13 +6,20 @@ use crate::style::{
     DefaultContainerStyles, DefaultInteractiveStyles, DefaultStyles, DefaultTextStyles, Style,
     StyleVal,
 };
-use crate::theme::Theme;
+use crate::theme::{ColorScheme, Theme};
 
 /// A smooth and minimalistic theme with a cold blue and purple touch.
+///
+/// Comes in a light, a dark and a high contrast variant.
+/// The light and dark variant switch to each other, when the preferred [ColorScheme] changes.
 #[derive(Debug, Clone)]
 pub enum CelesteTheme {
     /// Use [CelesteTheme::light] to use the light Celeste theme.
     Light(Globals),
+    /// Use [CelesteTheme::dark] to use the dark Celeste theme.
+    Dark(Globals),
+    /// Use [CelesteTheme::high_contrast] to use the high contrast Celeste theme.
+    HighContrast(Globals),
 }
 
 impl CelesteTheme {
@@ -20,86 +27,265 @@ impl CelesteTheme {
     pub fn light() -> Self {
         Self::Light(Globals::default())
     }
+
+    /// The Dark Celeste Theme.
+    pub fn dark() -> Self {
+        Self::Dark(Globals::default())
+    }
+
+    /// The High Contrast Celeste Theme with white text on black and yellow controls.
+    pub fn high_contrast() -> Self {
+        Self::HighContrast(Globals::default())
+    }
+
+    /// The light or dark Celeste Theme for the given color scheme.
+    pub fn from_scheme(scheme: ColorScheme) -> Self {
+        match scheme {
+            ColorScheme::Light => Self::light(),
+            ColorScheme::Dark => Self::dark(),
+        }
+    }
+
+    /// Returns the colors of the current variant.
+    fn palette(&self) -> &'static Palette {
+        match self {
+            CelesteTheme::Light(_) => &LIGHT,
+            CelesteTheme::Dark(_) => &DARK,
+            CelesteTheme::HighContrast(_) => &HIGH_CONTRAST,
+        }
+    }
 }
 
+/// The colors of a theme variant.
+struct Palette {
+    window: Color,
+    text: Color,
+    text_invert: Color,
+    text_background: Color,
+    container: Color,
+    container_background: Color,
+    surface: Color,
+    overlay: Color,
+    backdrop: Color,
+    idle: Color,
+    hovered: Color,
+    pressed: Color,
+    accent: Color,
+    accent_soft: Color,
+    focused: Color,
+    selection: Color,
+    track: Color,
+    disabled: Color,
+}
+
+const LIGHT: Palette = Palette {
+    window: Color::WHITE,
+    text: Color::BLACK,
+    text_invert: Color::WHITE,
+    text_background: Color::WHITE_SMOKE,
+    container: Color::ANTIQUE_WHITE,
+    container_background: Color::WHITE,
+    surface: Color::WHITE,
+    overlay: Color::rgb8(240, 242, 255),
+    backdrop: Color::rgba8(0, 0, 0, 80),
+    idle: Color::rgb8(150, 170, 250),
+    hovered: Color::rgb8(140, 160, 240),
+    pressed: Color::rgb8(130, 150, 230),
+    accent: Color::rgb8(130, 130, 230),
+    accent_soft: Color::rgb8(170, 170, 250),
+    focused: Color::rgb8(110, 110, 230),
+    selection: Color::rgb8(190, 200, 250),
+    track: Color::rgba8(0, 0, 0, 20),
+    disabled: Color::rgb8(110, 110, 110),
+};
+
+const DARK: Palette = Palette {
+    window: Color::rgb8(28, 29, 38),
+    text: Color::rgb8(230, 232, 245),
+    text_invert: Color::WHITE,
+    text_background: Color::rgb8(38, 40, 52),
+    container: Color::rgb8(48, 50, 66),
+    container_background: Color::rgb8(28, 29, 38),
+    surface: Color::rgb8(38, 40, 52),
+    overlay: Color::rgb8(44, 46, 62),
+    backdrop: Color::rgba8(0, 0, 0, 140),
+    idle: Color::rgb8(82, 94, 176),
+    hovered: Color::rgb8(94, 106, 190),
+    pressed: Color::rgb8(70, 80, 156),
+    accent: Color::rgb8(140, 140, 240),
+    accent_soft: Color::rgb8(78, 80, 130),
+    focused: Color::rgb8(160, 170, 255),
+    selection: Color::rgb8(70, 80, 140),
+    track: Color::rgba8(255, 255, 255, 24),
+    disabled: Color::rgb8(90, 90, 100),
+};
+
+const HIGH_CONTRAST: Palette = Palette {
+    window: Color::BLACK,
+    text: Color::WHITE,
+    text_invert: Color::BLACK,
+    text_background: Color::BLACK,
+    container: Color::WHITE,
+    container_background: Color::BLACK,
+    surface: Color::BLACK,
+    overlay: Color::rgb8(20, 20, 20),
+    backdrop: Color::rgba8(0, 0, 0, 160),
+    idle: Color::rgb8(255, 255, 0),
+    hovered: Color::rgb8(255, 255, 140),
+    pressed: Color::rgb8(0, 255, 255),
+    accent: Color::rgb8(255, 255, 0),
+    accent_soft: Color::WHITE,
+    focused: Color::rgb8(0, 255, 255),
+    selection: Color::rgb8(0, 0, 170),
+    track: Color::rgba8(255, 255, 255, 60),
+    disabled: Color::rgb8(128, 128, 128),
+};
+
 impl Theme for CelesteTheme {
     fn of(&self, id: WidgetId) -> Option<Style> {
+        let palette = self.palette();
+
+        // there are no style variants, so variants use the style of the widget
+        if id.class().is_some() {
+            return None;
+        }
+
         match id.namespace() {
             "maycoon-widgets" => match id.id() {
                 "Text" => Some(Style::from_values([
-                    ("color".to_string(), StyleVal::Color(Color::BLACK)),
-                    ("color_invert".to_string(), StyleVal::Color(Color::WHITE)),
+                    ("color".to_string(), StyleVal::Color(palette.text)),
+                    (
+                        "color_invert".to_string(),
+                        StyleVal::Color(palette.text_invert),
+                    ),
                 ])),
 
                 "Button" => Some(Style::from_values([
-                    (
-                        "color_idle".to_string(),
-                        StyleVal::Color(Color::rgb8(150, 170, 250)),
-                    ),
+                    ("color_idle".to_string(), StyleVal::Color(palette.idle)),
                     (
                         "color_pressed".to_string(),
-                        StyleVal::Color(Color::rgb8(130, 150, 230)),
+                        StyleVal::Color(palette.pressed),
                     ),
                     (
                         "color_hovered".to_string(),
-                        StyleVal::Color(Color::rgb8(140, 160, 240)),
+                        StyleVal::Color(palette.hovered),
                     ),
                 ])),
 
                 "Checkbox" => Some(Style::from_values([
-                    (
-                        "color_checked".to_string(),
-                        StyleVal::Color(Color::rgb8(130, 130, 230)),
-                    ),
+                    ("color_checked".to_string(), StyleVal::Color(palette.accent)),
                     (
                         "color_unchecked".to_string(),
-                        StyleVal::Color(Color::rgb8(170, 170, 250)),
+                        StyleVal::Color(palette.accent_soft),
                     ),
                 ])),
 
                 "Slider" => Some(Style::from_values([
+                    ("color".to_string(), StyleVal::Color(palette.accent)),
                     (
-                        "color".to_string(),
-                        StyleVal::Color(Color::rgb8(130, 130, 230)),
+                        "color_ball".to_string(),
+                        StyleVal::Color(palette.accent_soft),
                     ),
+                ])),
+
+                "TextInput" | "TextArea" => Some(Style::from_values([
                     (
-                        "color_ball".to_string(),
-                        StyleVal::Color(Color::rgb8(170, 170, 250)),
+                        "color_background".to_string(),
+                        StyleVal::Color(palette.surface),
+                    ),
+                    ("color_border".to_string(), StyleVal::Color(palette.idle)),
+                    (
+                        "color_border_focused".to_string(),
+                        StyleVal::Color(palette.focused),
+                    ),
+                    ("color_text".to_string(), StyleVal::Color(palette.text)),
+                    ("color_caret".to_string(), StyleVal::Color(palette.text)),
+                    (
+                        "color_selection".to_string(),
+                        StyleVal::Color(palette.selection),
+                    ),
+                ])),
+
+                "ScrollView" | "VirtualList" => Some(Style::from_values([
+                    ("color_track".to_string(), StyleVal::Color(palette.track)),
+                    ("color_thumb".to_string(), StyleVal::Color(palette.idle)),
+                    (
+                        "color_thumb_active".to_string(),
+                        StyleVal::Color(palette.focused),
+                    ),
+                ])),
+
+                _ => None,
+            },
+
+            "maycoon-core" => match id.id() {
+                "Overlay" => Some(Style::from_values([
+                    (
+                        "color_background".to_string(),
+                        StyleVal::Color(palette.overlay),
+                    ),
+                    (
+                        "color_backdrop".to_string(),
+                        StyleVal::Color(palette.backdrop),
                     ),
                 ])),
 
                 _ => None,
             },
+
             _ => None,
         }
     }
 
     fn defaults(&self) -> DefaultStyles {
+        let palette = self.palette();
+
         DefaultStyles::new(
-            DefaultTextStyles::new(Color::BLACK, Color::WHITE_SMOKE),
-            DefaultContainerStyles::new(Color::ANTIQUE_WHITE, Color::WHITE),
+            DefaultTextStyles::new(palette.text, palette.text_background),
+            DefaultContainerStyles::new(palette.container, palette.container_background),
             DefaultInteractiveStyles::new(
-                Color::rgb8(130, 150, 230),
-                Color::rgb8(150, 170, 250),
-                Color::rgb8(140, 160, 240),
-                Color::rgb8(110, 110, 110),
+                palette.pressed,
+                palette.idle,
+                palette.hovered,
+                palette.disabled,
             ),
         )
     }
 
     fn window_background(&self) -> Color {
-        Color::WHITE
+        self.palette().window
     }
 
     fn globals(&self) -> &Globals {
         match &self {
-            CelesteTheme::Light(globals) => globals,
+            CelesteTheme::Light(globals)
+            | CelesteTheme::Dark(globals)
+            | CelesteTheme::HighContrast(globals) => globals,
         }
     }
 
     fn globals_mut(&mut self) -> &mut Globals {
         match self {
-            CelesteTheme::Light(globals) => globals,
+            CelesteTheme::Light(globals)
+            | CelesteTheme::Dark(globals)
+            | CelesteTheme::HighContrast(globals) => globals,
         }
     }
+
+    fn set_color_scheme(&mut self, scheme: ColorScheme) -> bool {
+        let new = match (&self, scheme) {
+            (CelesteTheme::Light(globals), ColorScheme::Dark) => {
+                CelesteTheme::Dark(globals.clone())
+            },
+            (CelesteTheme::Dark(globals), ColorScheme::Light) => {
+                CelesteTheme::Light(globals.clone())
+            },
+
+            // the high contrast variant is chosen explicitly, so keep it
+            _ => return false,
+        };
+
+        *self = new;
+        true
+    }
 }
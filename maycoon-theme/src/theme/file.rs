@@ -0,0 +1,742 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use peniko::{Brush, Color, ColorStop, Gradient};
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+use crate::globals::{Globals, StyleContext};
+use crate::id::WidgetId;
+use crate::key::{StyleKind, StyleSchema};
+use crate::style::{
+    DefaultContainerStyles, DefaultInteractiveStyles, DefaultStyles, DefaultTextStyles, Style,
+    StyleVal,
+};
+use crate::theme::Theme;
+
+/// A theme loaded from a declarative theme file, so themes can be created without writing Rust.
+///
+/// The file contains the window background, the [DefaultStyles], the root [StyleContext] of the [Globals]
+/// (`text_color`, `font` and `font_size`) and a [Style] for every [WidgetId], keyed by `"namespace:id"`.
+/// Style variants are keyed by `"namespace:id.class"` and only need to contain the values, that differ from the widget style.
+///
+/// Style values are written as:
+/// - colors as strings (`"#96aafa"`, `"#00000050"` or SVG color names like `"white"`),
+/// - floats, ints and bools as plain values (use [FileTheme::with_schema] to read plain ints as floats, where a key expects a float),
+/// - unsigned ints as `{ uint = 4 }` (`{ int = 4 }` and `{ float = 4 }` are also accepted),
+/// - gradients as tables with a `kind` (`"linear"`, `"radial"` or `"sweep"`), its points and `stops`,
+/// - brushes as `{ brush = <color or gradient> }`.
+///
+/// ```
+/// use peniko::Color;
+/// use maycoon_theme::id::WidgetId;
+/// use maycoon_theme::theme::file::FileTheme;
+/// use maycoon_theme::theme::Theme;
+///
+/// let theme = FileTheme::from_toml(r##"
+///     window_background = "#ffffff"
+///
+///     [defaults.text]
+///     foreground = "black"
+///     background = "whitesmoke"
+///
+///     [defaults.container]
+///     foreground = "antiquewhite"
+///     background = "white"
+///
+///     [defaults.interactive]
+///     active = "#8296e6"
+///     inactive = "#96aafa"
+///     hover = "#8ca0f0"
+///     disabled = "#6e6e6e"
+///
+///     [styles."maycoon-widgets:Button"]
+///     color_idle = "#96aafa"
+///     color_pressed = "#8296e6"
+///     color_hovered = { kind = "linear", start = [0, 0], end = [100, 0], stops = ["#8ca0f0", "#96aafa"] }
+///
+///     [styles."maycoon-widgets:Button.danger"]
+///     color_idle = "#e05050"
+/// "##).unwrap();
+///
+/// let style = theme.of(WidgetId::new("maycoon-widgets", "Button")).unwrap();
+///
+/// assert!(style.get_color("color_idle").is_some());
+/// assert!(style.get_gradient("color_hovered").is_some());
+///
+/// let danger = theme.style_of(WidgetId::new("maycoon-widgets", "Button").with_class("danger"), None);
+///
+/// assert_eq!(danger.get_color("color_idle"), Some(Color::rgb8(224, 80, 80)));
+/// assert!(danger.get_color("color_pressed").is_some());
+/// ```
+#[derive(Clone, Debug)]
+pub struct FileTheme {
+    styles: BTreeMap<WidgetId, Style>,
+    defaults: DefaultStyles,
+    window_background: Color,
+    globals: Globals,
+}
+
+impl FileTheme {
+    /// Load a theme from the given file. The format is chosen by the file extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThemeFileError> {
+        let path = path.as_ref();
+
+        let format = ThemeFormat::from_path(path)
+            .ok_or_else(|| ThemeFileError::UnsupportedFormat(path.to_path_buf()))?;
+
+        Self::parse(&std::fs::read_to_string(path)?, format)
+    }
+
+    /// Parse a theme from the given source in the given format.
+    #[cfg_attr(
+        not(any(feature = "toml", feature = "json", feature = "ron")),
+        allow(unused_variables)
+    )]
+    pub fn parse(source: &str, format: ThemeFormat) -> Result<Self, ThemeFileError> {
+        match format {
+            #[cfg(feature = "toml")]
+            ThemeFormat::Toml => Self::from_toml(source),
+            #[cfg(feature = "json")]
+            ThemeFormat::Json => Self::from_json(source),
+            #[cfg(feature = "ron")]
+            ThemeFormat::Ron => Self::from_ron(source),
+        }
+    }
+
+    /// Parse a theme from a TOML source.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(source: &str) -> Result<Self, ThemeFileError> {
+        Self::from_value(
+            toml::from_str(source).map_err(|err| ThemeFileError::Parse(err.to_string()))?,
+        )
+    }
+
+    /// Parse a theme from a JSON source.
+    #[cfg(feature = "json")]
+    pub fn from_json(source: &str) -> Result<Self, ThemeFileError> {
+        Self::from_value(
+            serde_json::from_str(source).map_err(|err| ThemeFileError::Parse(err.to_string()))?,
+        )
+    }
+
+    /// Parse a theme from a RON source. Tables are written as maps (e.g. `{ "color": "#ffffff" }`).
+    #[cfg(feature = "ron")]
+    pub fn from_ron(source: &str) -> Result<Self, ThemeFileError> {
+        Self::from_value(
+            ron::from_str(source).map_err(|err| ThemeFileError::Parse(err.to_string()))?,
+        )
+    }
+
+    /// Parse a theme using any [serde] deserializer, to support other formats.
+    pub fn from_deserializer<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, ThemeFileError> {
+        Self::from_value(
+            Value::deserialize(deserializer)
+                .map_err(|err| ThemeFileError::Parse(err.to_string()))?,
+        )
+    }
+
+    /// Convert integer values to floats, where the given schema expects a float, and return itself.
+    ///
+    /// Theme files can't tell `8` and `8.0` apart in every format, so a plain `radius = 8` is parsed as an int.
+    /// Style variants use the keys of their widget.
+    pub fn with_schema(mut self, schema: &StyleSchema) -> Self {
+        for (id, style) in &mut self.styles {
+            let Some(keys) = schema.keys(&id.base()) else {
+                continue;
+            };
+
+            for key in keys {
+                if key.kind != StyleKind::Float {
+                    continue;
+                }
+
+                if let Some(value) = style.get_int(key.name) {
+                    style.set_float(key.name, value as f32);
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Validate the parsed theme file.
+    fn from_value(value: Value) -> Result<Self, ThemeFileError> {
+        let mut root = Table::new(value, String::new())?;
+
+        let window_background = color(
+            root.required("window_background")?,
+            &root.key("window_background"),
+        )?;
+
+        let defaults = {
+            let mut table = Table::new(root.required("defaults")?, root.key("defaults"))?;
+            let defaults = defaults(&mut table)?;
+
+            table.finish()?;
+            defaults
+        };
+
+        let globals = match root.take("globals") {
+            Some(value) => {
+                let mut table = Table::new(value, root.key("globals"))?;
+
+                let mut context = StyleContext::default();
+
+                if let Some(value) = table.take("text_color") {
+                    context.text_color = Some(color(value, &table.key("text_color"))?);
+                }
+
+                if let Some(value) = table.take("font") {
+                    context.font = Some(string(value, &table.key("font"))?);
+                }
+
+                if let Some(value) = table.take("font_size") {
+                    context.font_size = Some(float(value, &table.key("font_size"))?);
+                }
+
+                table.finish()?;
+                Globals::new(context)
+            },
+
+            None => Globals::default(),
+        };
+
+        let mut styles = BTreeMap::new();
+
+        if let Some(value) = root.take("styles") {
+            let table = Table::new(value, root.key("styles"))?;
+
+            for (name, value) in table.map {
+                let key = join(&table.path, &name);
+
+                let Some((namespace, id)) = name.split_once(':') else {
+                    return Err(ThemeFileError::invalid(
+                        key,
+                        "expected a widget id like \"namespace:id\"",
+                    ));
+                };
+
+                let widget = Table::new(value, key)?;
+                let mut style = Style::new();
+
+                for (name, value) in widget.map {
+                    let key = join(&widget.path, &name);
+
+                    style.set(name, style_val(value, &key)?);
+                }
+
+                let id = match id.split_once('.') {
+                    Some((id, class)) => WidgetId::new(namespace, id).with_class(class),
+                    None => WidgetId::new(namespace, id),
+                };
+
+                styles.insert(id, style);
+            }
+        }
+
+        root.finish()?;
+
+        Ok(Self {
+            styles,
+            defaults,
+            window_background,
+            globals,
+        })
+    }
+}
+
+impl Theme for FileTheme {
+    fn of(&self, id: WidgetId) -> Option<Style> {
+        self.styles.get(&id).cloned()
+    }
+
+    fn defaults(&self) -> DefaultStyles {
+        self.defaults.clone()
+    }
+
+    fn window_background(&self) -> Color {
+        self.window_background
+    }
+
+    fn globals(&self) -> &Globals {
+        &self.globals
+    }
+
+    fn globals_mut(&mut self) -> &mut Globals {
+        &mut self.globals
+    }
+}
+
+/// The file format of a theme file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ThemeFormat {
+    /// The TOML format with the `.toml` extension.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// The JSON format with the `.json` extension.
+    #[cfg(feature = "json")]
+    Json,
+    /// The RON format with the `.ron` extension.
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl ThemeFormat {
+    /// Returns the format of the given file, based on its extension.
+    /// Returns [None] if the extension is unknown or the format is not enabled.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::Toml),
+            #[cfg(feature = "json")]
+            "json" => Some(Self::Json),
+            #[cfg(feature = "ron")]
+            "ron" => Some(Self::Ron),
+            _ => None,
+        }
+    }
+}
+
+/// An error that occurred while loading a theme file.
+#[derive(Debug)]
+pub enum ThemeFileError {
+    /// The file extension does not belong to a supported format.
+    UnsupportedFormat(PathBuf),
+    /// An IO error occurred.
+    Io(std::io::Error),
+    /// The file is not valid in its format.
+    Parse(String),
+    /// A value of the file is missing, unknown or invalid.
+    Invalid {
+        /// The path of the offending key (e.g. `styles.maycoon-widgets:Button.color_idle`).
+        key: String,
+        /// What is wrong with the value.
+        message: String,
+    },
+}
+
+impl ThemeFileError {
+    /// Create an [ThemeFileError::Invalid] error.
+    fn invalid(key: impl ToString, message: impl ToString) -> Self {
+        Self::Invalid {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for ThemeFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeFileError::UnsupportedFormat(path) => {
+                write!(f, "unsupported theme file format: {}", path.display())
+            },
+            ThemeFileError::Io(err) => write!(f, "IO error: {}", err),
+            ThemeFileError::Parse(err) => write!(f, "failed to parse theme file: {}", err),
+            ThemeFileError::Invalid { key, message } => {
+                write!(f, "invalid theme value at `{}`: {}", key, message)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ThemeFileError {}
+
+impl From<std::io::Error> for ThemeFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// A format independent value of a theme file.
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Value>),
+    Table(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// Returns the kind of value for error messages.
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Bool(_) => "a bool",
+            Value::Int(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Table(_) => "a table",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Visits any value of a self-describing format.
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "a theme value")
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Int(v))
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Value, E> {
+        i64::try_from(v)
+            .map(Value::Int)
+            .map_err(|_| E::custom(format!("integer {} is too large", v)))
+    }
+
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Float(v))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::new();
+
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut values = BTreeMap::new();
+
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            values.insert(key, value);
+        }
+
+        Ok(Value::Table(values))
+    }
+}
+
+/// A table of a theme file, which keeps track of its path for error messages.
+struct Table {
+    path: String,
+    map: BTreeMap<String, Value>,
+}
+
+impl Table {
+    /// Expect the given value at the given path to be a table.
+    fn new(value: Value, path: String) -> Result<Self, ThemeFileError> {
+        match value {
+            Value::Table(map) => Ok(Self { path, map }),
+            value => Err(ThemeFileError::invalid(
+                path,
+                format!("expected a table, got {}", value.kind()),
+            )),
+        }
+    }
+
+    /// Returns the path of the given key inside this table.
+    fn key(&self, name: &str) -> String {
+        join(&self.path, name)
+    }
+
+    /// Remove the value with the given key, if it exists.
+    fn take(&mut self, name: &str) -> Option<Value> {
+        self.map.remove(name)
+    }
+
+    /// Remove the value with the given key or fail, if it's missing.
+    fn required(&mut self, name: &str) -> Result<Value, ThemeFileError> {
+        self.take(name)
+            .ok_or_else(|| ThemeFileError::invalid(self.key(name), "missing value"))
+    }
+
+    /// Fail, if there are keys left, which were not taken.
+    fn finish(self) -> Result<(), ThemeFileError> {
+        match self.map.into_keys().next() {
+            Some(name) => Err(ThemeFileError::invalid(
+                join(&self.path, &name),
+                "unknown key",
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Join the given path of a table and key.
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+/// Parse the `defaults` table.
+fn defaults(table: &mut Table) -> Result<DefaultStyles, ThemeFileError> {
+    let mut text = Table::new(table.required("text")?, table.key("text"))?;
+    let mut container = Table::new(table.required("container")?, table.key("container"))?;
+    let mut interactive = Table::new(table.required("interactive")?, table.key("interactive"))?;
+
+    let defaults = DefaultStyles::new(
+        DefaultTextStyles::new(
+            required_color(&mut text, "foreground")?,
+            required_color(&mut text, "background")?,
+        ),
+        DefaultContainerStyles::new(
+            required_color(&mut container, "foreground")?,
+            required_color(&mut container, "background")?,
+        ),
+        DefaultInteractiveStyles::new(
+            required_color(&mut interactive, "active")?,
+            required_color(&mut interactive, "inactive")?,
+            required_color(&mut interactive, "hover")?,
+            required_color(&mut interactive, "disabled")?,
+        ),
+    );
+
+    text.finish()?;
+    container.finish()?;
+    interactive.finish()?;
+
+    Ok(defaults)
+}
+
+/// Remove the color with the given key from the table or fail, if it's missing or invalid.
+fn required_color(table: &mut Table, name: &str) -> Result<Color, ThemeFileError> {
+    color(table.required(name)?, &table.key(name))
+}
+
+/// Parse a style value.
+fn style_val(value: Value, key: &str) -> Result<StyleVal, ThemeFileError> {
+    match value {
+        Value::Bool(value) => Ok(StyleVal::Bool(value)),
+        Value::Int(value) => Ok(StyleVal::Int(int(value, key)?)),
+        Value::Float(value) => Ok(StyleVal::Float(value as f32)),
+        Value::String(_) => Ok(StyleVal::Color(color(value, key)?)),
+
+        Value::Table(map) if map.contains_key("kind") || map.contains_key("stops") => {
+            Ok(StyleVal::Gradient(gradient(Value::Table(map), key)?))
+        },
+
+        Value::Table(map) if map.len() == 1 => {
+            let (name, value) = map.into_iter().next().unwrap();
+            let key = join(key, &name);
+
+            match name.as_str() {
+                "uint" => Ok(StyleVal::UInt(uint(value, &key)?)),
+
+                "int" => match value {
+                    Value::Int(value) => Ok(StyleVal::Int(int(value, &key)?)),
+                    value => Err(expected("an integer", &value, &key)),
+                },
+
+                "float" => Ok(StyleVal::Float(float(value, &key)?)),
+                "brush" => Ok(StyleVal::Brush(brush(value, &key)?)),
+
+                _ => Err(ThemeFileError::invalid(
+                    key,
+                    "unknown key, expected \"uint\", \"int\", \"float\" or \"brush\"",
+                )),
+            }
+        },
+
+        value => Err(expected("a style value", &value, key)),
+    }
+}
+
+/// Create an error for a value of the wrong kind.
+fn expected(what: &str, value: &Value, key: &str) -> ThemeFileError {
+    ThemeFileError::invalid(key, format!("expected {}, got {}", what, value.kind()))
+}
+
+/// Parse a color from a hex string or SVG color name.
+fn color(value: Value, key: &str) -> Result<Color, ThemeFileError> {
+    match value {
+        Value::String(string) => Color::parse(&string).ok_or_else(|| {
+            ThemeFileError::invalid(
+                key,
+                format!(
+                    "invalid color \"{}\", expected a hex color like \"#rrggbb\" or a color name",
+                    string
+                ),
+            )
+        }),
+
+        value => Err(expected("a color", &value, key)),
+    }
+}
+
+/// Parse a string.
+fn string(value: Value, key: &str) -> Result<String, ThemeFileError> {
+    match value {
+        Value::String(value) => Ok(value),
+        value => Err(expected("a string", &value, key)),
+    }
+}
+
+/// Check if the integer fits into an `i32`.
+fn int(value: i64, key: &str) -> Result<i32, ThemeFileError> {
+    i32::try_from(value)
+        .map_err(|_| ThemeFileError::invalid(key, format!("integer {} is out of range", value)))
+}
+
+/// Parse an unsigned integer.
+fn uint(value: Value, key: &str) -> Result<u32, ThemeFileError> {
+    match value {
+        Value::Int(value) => u32::try_from(value).map_err(|_| {
+            ThemeFileError::invalid(key, format!("unsigned integer {} is out of range", value))
+        }),
+
+        value => Err(expected("an unsigned integer", &value, key)),
+    }
+}
+
+/// Parse a float, allowing integers.
+fn float(value: Value, key: &str) -> Result<f32, ThemeFileError> {
+    match value {
+        Value::Float(value) => Ok(value as f32),
+        Value::Int(value) => Ok(value as f32),
+        value => Err(expected("a number", &value, key)),
+    }
+}
+
+/// Parse a point as an array of two numbers.
+fn point(value: Value, key: &str) -> Result<(f64, f64), ThemeFileError> {
+    match value {
+        Value::Array(values) if values.len() == 2 => {
+            let mut values = values.into_iter();
+
+            Ok((
+                float(values.next().unwrap(), key)? as f64,
+                float(values.next().unwrap(), key)? as f64,
+            ))
+        },
+
+        value => Err(expected("a point like [x, y]", &value, key)),
+    }
+}
+
+/// Parse a brush from a color or gradient.
+fn brush(value: Value, key: &str) -> Result<Brush, ThemeFileError> {
+    match value {
+        Value::String(_) => Ok(Brush::Solid(color(value, key)?)),
+        Value::Table(_) => Ok(Brush::Gradient(gradient(value, key)?)),
+        value => Err(expected("a color or gradient", &value, key)),
+    }
+}
+
+/// Parse a gradient table.
+fn gradient(value: Value, key: &str) -> Result<Gradient, ThemeFileError> {
+    let mut table = Table::new(value, key.to_string())?;
+
+    let kind = match table.take("kind") {
+        Some(Value::String(kind)) => kind,
+        Some(value) => return Err(expected("a gradient kind", &value, &table.key("kind"))),
+        None => String::from("linear"),
+    };
+
+    let gradient = match kind.as_str() {
+        "linear" => Gradient::new_linear(
+            point(table.required("start")?, &table.key("start"))?,
+            point(table.required("end")?, &table.key("end"))?,
+        ),
+
+        "radial" => Gradient::new_radial(
+            point(table.required("center")?, &table.key("center"))?,
+            float(table.required("radius")?, &table.key("radius"))?,
+        ),
+
+        "sweep" => Gradient::new_sweep(
+            point(table.required("center")?, &table.key("center"))?,
+            float(table.required("start_angle")?, &table.key("start_angle"))?,
+            float(table.required("end_angle")?, &table.key("end_angle"))?,
+        ),
+
+        kind => {
+            return Err(ThemeFileError::invalid(
+                table.key("kind"),
+                format!(
+                    "unknown gradient kind \"{}\", expected \"linear\", \"radial\" or \"sweep\"",
+                    kind
+                ),
+            ))
+        },
+    };
+
+    let stops = stops(table.required("stops")?, &table.key("stops"))?;
+
+    table.finish()?;
+
+    Ok(gradient.with_stops(stops.as_slice()))
+}
+
+/// Parse the color stops of a gradient.
+///
+/// Stops are either colors, which are spread evenly, or tables with an `offset` and `color`.
+fn stops(value: Value, key: &str) -> Result<Vec<ColorStop>, ThemeFileError> {
+    let Value::Array(values) = value else {
+        return Err(expected("an array of color stops", &value, key));
+    };
+
+    if values.len() < 2 {
+        return Err(ThemeFileError::invalid(
+            key,
+            "expected at least two color stops",
+        ));
+    }
+
+    let denom = (values.len() - 1) as f32;
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let key = format!("{}[{}]", key, i);
+
+            match value {
+                Value::String(_) => Ok(ColorStop {
+                    offset: i as f32 / denom,
+                    color: color(value, &key)?,
+                }),
+
+                Value::Table(_) => {
+                    let mut table = Table::new(value, key)?;
+
+                    let stop = ColorStop {
+                        offset: float(table.required("offset")?, &table.key("offset"))?,
+                        color: required_color(&mut table, "color")?,
+                    };
+
+                    table.finish()?;
+
+                    Ok(stop)
+                },
+
+                value => Err(expected("a color stop", &value, &key)),
+            }
+        })
+        .collect()
+}
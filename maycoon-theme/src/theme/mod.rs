@@ -6,12 +6,17 @@ use crate::style::{DefaultStyles, Style};
 
 /// The Celeste Theme.
 pub mod celeste;
+/// Contains the [file::FileTheme], which is loaded from theme files.
+pub mod file;
 
 /// Base trait for all themes.
 pub trait Theme {
     /// Return the Style of the given widget using its ID.
     /// Returns [None] if the theme does not have styles for the given widget.
     /// In that case, you should use [Theme::defaults] to get widget style defaults.
+    ///
+    /// If the ID has a [class](WidgetId::class), the style of that variant should be returned.
+    /// It only needs to contain the values, which differ from the style of the widget itself (see [Theme::style_of]).
     fn of(&self, id: WidgetId) -> Option<Style>;
     /// Get the default widget styles.
     fn defaults(&self) -> DefaultStyles;
@@ -21,4 +26,46 @@ pub trait Theme {
     fn globals(&self) -> &Globals;
     /// Get mutable global style values.
     fn globals_mut(&mut self) -> &mut Globals;
+
+    /// Return the style of a widget instance.
+    ///
+    /// Merges the style of the variant over the style of the widget (if the ID has a class)
+    /// and the given instance overrides over both.
+    /// Returns an empty style, if neither the theme nor the overrides have values, so widgets should fall back to [Theme::defaults] for missing values.
+    fn style_of(&self, id: WidgetId, overrides: Option<&Style>) -> Style {
+        let mut style = if id.class().is_some() {
+            let mut style = self.of(id.base()).unwrap_or_default();
+
+            if let Some(variant) = self.of(id) {
+                style.merge(&variant);
+            }
+
+            style
+        } else {
+            self.of(id).unwrap_or_default()
+        };
+
+        if let Some(overrides) = overrides {
+            style.merge(overrides);
+        }
+
+        style
+    }
+
+    /// Adapt the theme to the given color scheme, which the user prefers (e.g. when the operating system switches to dark mode).
+    ///
+    /// Returns if the theme changed and the windows need to be redrawn. Does nothing by default.
+    fn set_color_scheme(&mut self, _: ColorScheme) -> bool {
+        false
+    }
+}
+
+/// The color scheme, which the user prefers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    #[default]
+    Light,
+    /// Light text on a dark background.
+    Dark,
 }
@@ -0,0 +1,434 @@
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{StyleIssue, StyleKey, StyleKind, StyleSchema};
+use maycoon_theme::style::{Style, StyleVal};
+use maycoon_theme::theme::file::{FileTheme, ThemeFileError, ThemeFormat};
+use maycoon_theme::theme::Theme;
+use peniko::{Brush, Color};
+
+const DEFAULTS: &str = r##"
+window_background = "#102030"
+
+[defaults.text]
+foreground = "black"
+background = "white"
+
+[defaults.container]
+foreground = "black"
+background = "white"
+
+[defaults.interactive]
+active = "#010203"
+inactive = "#040506"
+hover = "#070809"
+disabled = "#0a0b0c"
+"##;
+
+fn toml(styles: &str) -> Result<FileTheme, ThemeFileError> {
+    FileTheme::from_toml(&format!("{}\n{}", DEFAULTS, styles))
+}
+
+fn invalid_key(result: Result<FileTheme, ThemeFileError>) -> String {
+    match result {
+        Err(ThemeFileError::Invalid { key, .. }) => key,
+        Err(err) => panic!("expected an invalid value, got {}", err),
+        Ok(_) => panic!("expected an invalid value"),
+    }
+}
+
+#[test]
+fn toml_theme_has_styles_and_defaults() {
+    let theme = toml(
+        r##"
+        [globals]
+        font = "mono"
+        font_size = 14
+
+        [styles."maycoon-widgets:Button"]
+        color_idle = "#96aafa80"
+        radius = 4.5
+        border = 2
+        segments = { uint = 12 }
+        rounded = true
+        fill = { brush = { kind = "radial", center = [10, 10], radius = 5, stops = [
+            { offset = 0.0, color = "white" },
+            { offset = 1.0, color = "black" },
+        ] } }
+        "##,
+    )
+    .unwrap();
+
+    assert_eq!(theme.window_background(), Color::rgb8(0x10, 0x20, 0x30));
+    assert_eq!(theme.defaults().interactive().hover(), Color::rgb8(7, 8, 9));
+    assert_eq!(theme.globals().context().font.as_deref(), Some("mono"));
+    assert_eq!(theme.globals().context().font_size, Some(14.0));
+
+    let style = theme
+        .of(WidgetId::new("maycoon-widgets", "Button"))
+        .unwrap();
+
+    assert_eq!(
+        style.get_color("color_idle"),
+        Some(Color::rgba8(0x96, 0xaa, 0xfa, 0x80))
+    );
+    assert_eq!(style.get_float("radius"), Some(4.5));
+    assert_eq!(style.get_int("border"), Some(2));
+    assert_eq!(style.get_uint("segments"), Some(12));
+    assert_eq!(style.get_bool("rounded"), Some(true));
+
+    let Some(Brush::Gradient(gradient)) = style.get_brush("fill") else {
+        panic!("expected a gradient brush");
+    };
+
+    assert_eq!(gradient.stops.len(), 2);
+    assert_eq!(gradient.stops[1].color, Color::BLACK);
+
+    assert!(theme
+        .of(WidgetId::new("maycoon-widgets", "Slider"))
+        .is_none());
+}
+
+#[test]
+fn style_variants_and_overrides_are_merged() {
+    let theme = toml(
+        r##"
+        [styles."maycoon-widgets:Button"]
+        color_idle = "#000001"
+        color_pressed = "#000002"
+        color_hovered = "#000003"
+
+        [styles."maycoon-widgets:Button.danger"]
+        color_idle = "#ff0001"
+        color_pressed = "#ff0002"
+        "##,
+    )
+    .unwrap();
+
+    let button = WidgetId::new("maycoon-widgets", "Button");
+    let danger = button.clone().with_class("danger");
+
+    // variants only contain their own values
+    assert!(theme
+        .of(danger.clone())
+        .unwrap()
+        .get_color("color_hovered")
+        .is_none());
+
+    let overrides =
+        Style::from_values([("color_pressed".to_string(), StyleVal::Color(Color::WHITE))]);
+    let style = theme.style_of(danger, Some(&overrides));
+
+    assert_eq!(style.get_color("color_idle"), Some(Color::rgb8(255, 0, 1)));
+    assert_eq!(style.get_color("color_pressed"), Some(Color::WHITE));
+    assert_eq!(style.get_color("color_hovered"), Some(Color::rgb8(0, 0, 3)));
+
+    // unknown variants fall back to the style of the widget
+    let style = theme.style_of(button.with_class("unknown"), None);
+
+    assert_eq!(style.get_color("color_idle"), Some(Color::rgb8(0, 0, 1)));
+    assert!(theme
+        .style_of(WidgetId::new("maycoon-widgets", "Slider"), None)
+        .get_color("color")
+        .is_none());
+}
+
+#[test]
+fn missing_and_mistyped_keys_are_reported() {
+    const COLOR_IDLE: StyleKey<Color> =
+        StyleKey::new("color_idle", |defaults| defaults.interactive().inactive());
+    const COLOR_PRESSED: StyleKey<Color> =
+        StyleKey::new("color_pressed", |defaults| defaults.interactive().active());
+    const COLOR_HOVERED: StyleKey<Color> =
+        StyleKey::new("color_hovered", |defaults| defaults.interactive().hover());
+    const COLOR: StyleKey<Color> =
+        StyleKey::new("color", |defaults| defaults.interactive().inactive());
+
+    let theme = toml(
+        r##"
+        [styles."maycoon-widgets:Button"]
+        color_idle = "#000001"
+        color_pressed = 2.0
+        "##,
+    )
+    .unwrap();
+
+    let button = WidgetId::new("maycoon-widgets", "Button");
+    let slider = WidgetId::new("maycoon-widgets", "Slider");
+
+    let schema = StyleSchema::new()
+        .with_widget(
+            button.clone(),
+            &[
+                COLOR_IDLE.info(),
+                COLOR_PRESSED.info(),
+                COLOR_HOVERED.info(),
+            ],
+        )
+        .with_widget(slider.clone(), &[COLOR.info()]);
+
+    assert_eq!(
+        schema.validate(&theme),
+        vec![
+            StyleIssue::WrongKind {
+                widget: button.clone(),
+                key: "color_pressed",
+                expected: StyleKind::Color,
+                found: StyleKind::Float,
+            },
+            StyleIssue::MissingKey {
+                widget: button.clone(),
+                key: "color_hovered",
+                expected: StyleKind::Color,
+            },
+            StyleIssue::MissingStyle { widget: slider },
+        ]
+    );
+
+    // missing and mistyped keys resolve to their fallbacks
+    let style = theme.style_of(button, None);
+    let defaults = theme.defaults();
+
+    assert_eq!(style.resolve(&COLOR_IDLE, &defaults), Color::rgb8(0, 0, 1));
+    assert_eq!(
+        style.resolve(&COLOR_PRESSED, &defaults),
+        Color::rgb8(1, 2, 3)
+    );
+    assert_eq!(
+        style.resolve(&COLOR_HOVERED, &defaults),
+        Color::rgb8(7, 8, 9)
+    );
+}
+
+#[test]
+fn formats_describe_the_same_theme() {
+    let json = FileTheme::from_json(
+        r##"{
+            "window_background": "#102030",
+            "defaults": {
+                "text": { "foreground": "black", "background": "white" },
+                "container": { "foreground": "black", "background": "white" },
+                "interactive": {
+                    "active": "#010203",
+                    "inactive": "#040506",
+                    "hover": "#070809",
+                    "disabled": "#0a0b0c"
+                }
+            },
+            "styles": {
+                "maycoon-widgets:Slider": {
+                    "color": { "kind": "linear", "start": [0, 0], "end": [100, 0], "stops": ["red", "blue"] },
+                    "step": 0.25
+                }
+            }
+        }"##,
+    )
+    .unwrap();
+
+    let ron = FileTheme::from_ron(
+        r##"{
+            "window_background": "#102030",
+            "defaults": {
+                "text": { "foreground": "black", "background": "white" },
+                "container": { "foreground": "black", "background": "white" },
+                "interactive": {
+                    "active": "#010203",
+                    "inactive": "#040506",
+                    "hover": "#070809",
+                    "disabled": "#0a0b0c",
+                },
+            },
+            "styles": {
+                "maycoon-widgets:Slider": {
+                    "color": { "kind": "linear", "start": [0, 0], "end": [100, 0], "stops": ["red", "blue"] },
+                    "step": 0.25,
+                },
+            },
+        }"##,
+    )
+    .unwrap();
+
+    let toml = toml(
+        r##"
+        [styles."maycoon-widgets:Slider"]
+        color = { kind = "linear", start = [0, 0], end = [100, 0], stops = ["red", "blue"] }
+        step = 0.25
+        "##,
+    )
+    .unwrap();
+
+    let id = WidgetId::new("maycoon-widgets", "Slider");
+
+    for theme in [json, ron] {
+        assert_eq!(theme.window_background(), toml.window_background());
+        assert_eq!(theme.defaults(), toml.defaults());
+
+        let (style, expected) = (theme.of(id.clone()).unwrap(), toml.of(id.clone()).unwrap());
+
+        assert_eq!(style.get_gradient("color"), expected.get_gradient("color"));
+        assert_eq!(style.get_float("step"), expected.get_float("step"));
+    }
+}
+
+#[test]
+fn invalid_values_point_at_their_key() {
+    assert_eq!(
+        invalid_key(toml(
+            r##"
+            [styles."maycoon-widgets:Button"]
+            color_idle = "#96aafz"
+            "##
+        )),
+        "styles.maycoon-widgets:Button.color_idle"
+    );
+
+    assert_eq!(
+        invalid_key(toml(
+            r##"
+            [styles."maycoon-widgets:Button"]
+            color_idle = { kind = "linear", start = [0, 0], end = [1], stops = ["red", "blue"] }
+            "##
+        )),
+        "styles.maycoon-widgets:Button.color_idle.end"
+    );
+
+    assert_eq!(
+        invalid_key(toml(
+            r##"
+            [styles."maycoon-widgets:Button"]
+            color_idle = { kind = "linear", start = [0, 0], end = [1, 0], stops = ["red", 4] }
+            "##
+        )),
+        "styles.maycoon-widgets:Button.color_idle.stops[1]"
+    );
+
+    assert_eq!(
+        invalid_key(toml(
+            r##"
+            [styles."maycoon-widgets:Button"]
+            segments = { uint = -1 }
+            "##
+        )),
+        "styles.maycoon-widgets:Button.segments.uint"
+    );
+
+    assert_eq!(
+        invalid_key(toml(
+            r##"
+            [styles.Button]
+            color_idle = "red"
+            "##
+        )),
+        "styles.Button"
+    );
+
+    assert_eq!(
+        invalid_key(FileTheme::from_toml(&format!(
+            "window_title = \"Brand\"\n{}",
+            DEFAULTS
+        ))),
+        "window_title"
+    );
+
+    assert_eq!(
+        invalid_key(FileTheme::from_toml(
+            &DEFAULTS.replace("hover = \"#070809\"", "")
+        )),
+        "defaults.interactive.hover"
+    );
+
+    assert!(matches!(
+        FileTheme::from_toml("window_background = "),
+        Err(ThemeFileError::Parse(_))
+    ));
+}
+
+#[test]
+fn load_chooses_format_by_extension() {
+    let path = std::env::temp_dir().join(format!("maycoon-theme-{}.toml", std::process::id()));
+
+    std::fs::write(&path, DEFAULTS).unwrap();
+
+    let theme = FileTheme::load(&path);
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        theme.unwrap().window_background(),
+        Color::rgb8(0x10, 0x20, 0x30)
+    );
+
+    assert_eq!(ThemeFormat::from_path("brand.ron"), Some(ThemeFormat::Ron));
+    assert_eq!(
+        ThemeFormat::from_path("brand.json"),
+        Some(ThemeFormat::Json)
+    );
+    assert!(matches!(
+        FileTheme::load("brand.yaml"),
+        Err(ThemeFileError::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn integers_are_floats_where_the_schema_expects_floats() {
+    const RADIUS: StyleKey<f32> = StyleKey::new("radius", |_| 4.0);
+    const COUNT: StyleKey<i32> = StyleKey::new("count", |_| 0);
+
+    let slider = WidgetId::new("maycoon-widgets", "Slider");
+
+    let schema = StyleSchema::new().with_widget(slider.clone(), &[RADIUS.info(), COUNT.info()]);
+
+    let theme = toml(
+        r##"
+        [styles."maycoon-widgets:Slider"]
+        radius = 8
+        count = 3
+
+        [styles."maycoon-widgets:Slider.large"]
+        radius = 12
+        "##,
+    )
+    .unwrap();
+
+    // without a schema, plain integers stay integers
+    assert_eq!(
+        schema.validate(&theme),
+        vec![StyleIssue::WrongKind {
+            widget: slider.clone(),
+            key: "radius",
+            expected: StyleKind::Float,
+            found: StyleKind::Int,
+        }]
+    );
+
+    let theme = theme.with_schema(&schema);
+    let defaults = theme.defaults();
+
+    assert!(schema.validate(&theme).is_empty());
+
+    let style = theme.style_of(slider.clone(), None);
+
+    assert_eq!(style.resolve(&RADIUS, &defaults), 8.0);
+    assert_eq!(style.resolve(&COUNT, &defaults), 3);
+
+    let large = theme.style_of(slider.with_class("large"), None);
+
+    assert_eq!(large.resolve(&RADIUS, &defaults), 12.0);
+}
+
+#[test]
+fn unsigned_integers_are_described_as_unsigned() {
+    assert_eq!(StyleKind::UInt.to_string(), "unsigned int");
+
+    let err = toml(
+        r##"
+        [styles."maycoon-widgets:Slider"]
+        steps = { uint = -1 }
+        "##,
+    )
+    .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("unsigned integer -1 is out of range"),
+        "{err}"
+    );
+}
@@ -1,18 +1,54 @@
+use std::time::Duration;
+
+use maycoon_core::accesskit::{Action, Role};
+use maycoon_core::animation::{Animation, Lerp, Tween};
+use maycoon_core::app::accessibility::{AccessAction, AccessNode};
+use maycoon_core::app::focus::FocusId;
 use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::inspector::InspectNode;
 use maycoon_core::app::update::Update;
+use maycoon_core::instance::{InstanceStore, WidgetKey};
 use maycoon_core::layout;
 use maycoon_core::layout::{LayoutNode, LayoutStyle, LengthPercentage, StyleNode};
 use maycoon_core::state::{State, Val};
-use maycoon_core::vg::kurbo::{Affine, Rect, RoundedRect, RoundedRectRadii, Vec2};
-use maycoon_core::vg::peniko::{Brush, Fill};
+use maycoon_core::vg::kurbo::{Affine, Rect, RoundedRect, RoundedRectRadii, Stroke, Vec2};
+use maycoon_core::vg::peniko::{Brush, Color, Fill};
 use maycoon_core::vg::Scene;
 use maycoon_core::widget::Widget;
-use maycoon_core::window::{ElementState, MouseButton};
+use maycoon_core::window::{ElementState, Key, MouseButton, NamedKey};
+use maycoon_theme::globals::StyleOverrides;
 use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
 use maycoon_theme::theme::Theme;
 
+/// The color of the button when pressed.
+pub const COLOR_PRESSED: StyleKey<Color> =
+    StyleKey::new("color_pressed", |defaults| defaults.interactive().active());
+
+/// The color of the button when not pressed and not hovered (idling).
+pub const COLOR_IDLE: StyleKey<Color> =
+    StyleKey::new("color_idle", |defaults| defaults.interactive().inactive());
+
+/// The color of the button when hovered on.
+pub const COLOR_HOVERED: StyleKey<Color> =
+    StyleKey::new("color_hovered", |defaults| defaults.interactive().hover());
+
+/// The style keys of the [Button].
+pub const STYLE_KEYS: &[KeyInfo] = &[
+    COLOR_PRESSED.info(),
+    COLOR_IDLE.info(),
+    COLOR_HOVERED.info(),
+];
+
 /// An interactive area with a child widget that runs a closure when pressed.
 ///
+/// The button can be focused and activated using the keyboard by pressing `Enter` or `Space`
+/// or by assistive technologies (e.g. screen readers), which read the text of the child widget as its name.
+/// It smoothly transitions between its colors, when it's hovered or pressed.
+/// Inside a disabled style context (see [Globals](maycoon_theme::globals::Globals)), it uses the disabled color and ignores input.
+/// The child inherits the inverted text color.
+///
 /// See the [counter](https://github.com/maycoon-ui/maycoon/blob/master/examples/counter/src/main.rs) example for how to use it in practice.
 ///
 /// ### Theming
@@ -20,11 +56,21 @@ use maycoon_theme::theme::Theme;
 /// - `color_pressed` -  The color of the button when pressed.
 /// - `color_idle` - The color of the button when not pressed and not hovered (idling).
 /// - `color_hovered` - The color of the button when hovered on.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single button using [Button::with_style]
+/// and a style variant of the theme can be chosen using [Button::with_class].
 pub struct Button<S: State, W: Widget<S> + 'static> {
     child: Val<S, W>,
     state: ButtonState,
     on_pressed: Box<dyn FnMut(&mut S) -> Update>,
     layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
+    focus: Option<FocusId>,
+    hover: Tween<f32>,
+    press: Tween<f32>,
 }
 
 impl<S: State, W: Widget<S> + 'static> Button<S, W> {
@@ -44,6 +90,11 @@ impl<S: State, W: Widget<S> + 'static> Button<S, W> {
                 ..Default::default()
             }
             .into(),
+            id: WidgetId::new("maycoon-widgets", "Button"),
+            theme_style: None.into(),
+            focus: None,
+            hover: Tween::new(0.0, Duration::from_millis(150)),
+            press: Tween::new(0.0, Duration::from_millis(100)),
         }
     }
 
@@ -58,6 +109,20 @@ impl<S: State, W: Widget<S> + 'static> Button<S, W> {
         self.layout_style = layout_style.into();
         self
     }
+
+    /// Sets the style of this button, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this button (e.g. `danger` for `Button.danger`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
 }
 
 impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
@@ -69,22 +134,31 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
         layout_node: &LayoutNode,
         state: &S,
     ) {
-        let brush = if let Some(style) = theme.of(self.widget_id()) {
-            match self.state {
-                ButtonState::Idle => Brush::Solid(style.get_color("color_idle").unwrap()),
-                ButtonState::Hovered => Brush::Solid(style.get_color("color_hovered").unwrap()),
-                ButtonState::Pressed => Brush::Solid(style.get_color("color_pressed").unwrap()),
-                ButtonState::Released => Brush::Solid(style.get_color("color_hovered").unwrap()),
-            }
+        let context = theme.globals().context().clone();
+
+        let (idle, hovered, pressed) = if context.disabled {
+            let disabled = theme.defaults().interactive().disabled();
+
+            (disabled, disabled, disabled)
         } else {
-            Brush::Solid(match self.state {
-                ButtonState::Idle => theme.defaults().interactive().inactive(),
-                ButtonState::Hovered => theme.defaults().interactive().hover(),
-                ButtonState::Pressed => theme.defaults().interactive().active(),
-                ButtonState::Released => theme.defaults().interactive().hover(),
-            })
+            let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+            let defaults = theme.defaults();
+
+            (
+                style.resolve(&COLOR_IDLE, &defaults),
+                style.resolve(&COLOR_HOVERED, &defaults),
+                style.resolve(&COLOR_PRESSED, &defaults),
+            )
         };
 
+        // blend the colors of the current transition
+        let brush = Brush::Solid(
+            context.color(
+                idle.lerp(&hovered, self.hover.value())
+                    .lerp(&pressed, self.press.value()),
+            ),
+        );
+
         scene.fill(
             Fill::NonZero,
             Affine::default(),
@@ -101,18 +175,40 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
             ),
         );
 
-        {
-            theme.globals_mut().invert_text_color = true;
+        if self.focus.is_some_and(|focus| info.focus.is_focused(focus)) {
+            scene.stroke(
+                &Stroke::new(2.0),
+                Affine::default(),
+                &Brush::Solid(context.color(theme.defaults().interactive().active())),
+                None,
+                &RoundedRect::from_rect(
+                    Rect::new(
+                        layout_node.layout.location.x as f64 - 3.0,
+                        layout_node.layout.location.y as f64 - 3.0,
+                        (layout_node.layout.location.x + layout_node.layout.size.width) as f64
+                            + 3.0,
+                        (layout_node.layout.location.y + layout_node.layout.size.height) as f64
+                            + 3.0,
+                    ),
+                    RoundedRectRadii::from_single_radius(13.0),
+                ),
+            );
+        }
 
+        {
             let mut child_scene = Scene::new();
+            let child = self.child.get_mut(state);
 
-            self.child.get_mut(state).render(
-                &mut child_scene,
-                theme,
-                info,
-                &layout_node.children[0],
-                state,
-            );
+            info.style
+                .render_with_overrides(theme, &child_overrides(), |theme| {
+                    child.render(
+                        &mut child_scene,
+                        theme,
+                        info,
+                        &layout_node.children[0],
+                        state,
+                    )
+                });
 
             scene.append(
                 &child_scene,
@@ -121,8 +217,6 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
                     layout_node.layout.location.y as f64,
                 ))),
             );
-
-            theme.globals_mut().invert_text_color = false;
         }
     }
 
@@ -130,16 +224,42 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
         StyleNode {
             style: self.layout_style.get_ref(state).clone(),
             children: vec![self.child.get_mut(state).layout_style(state)],
+            key: None,
+            measure: None,
         }
     }
 
     fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
         self.layout_style.invalidate();
-        self.child.invalidate();
+        self.theme_style.invalidate();
+        self.child.rebuild(state);
+
+        let mut update = info.style.update_with_overrides(&child_overrides(), || {
+            self.child
+                .get_mut(state)
+                .update(&layout.children[0], state, info)
+        });
 
-        let mut update = Update::empty();
         let old_state = self.state;
 
+        // disabled buttons can't be focused or pressed
+        if info.style.context().disabled {
+            self.focus = None;
+            self.state = ButtonState::Idle;
+            self.hover.set(0.0);
+            self.press.set(0.0);
+
+            if old_state != self.state {
+                update |= Update::DRAW;
+            }
+
+            return update | self.hover.animate(info) | self.press.animate(info);
+        }
+
+        let focus = info.focus.register();
+
+        self.focus = Some(focus);
+
         // check for hovering
         if let Some(cursor) = info.cursor_pos {
             if cursor.x as f32 >= layout.layout.location.x
@@ -158,6 +278,7 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
                         match el {
                             ElementState::Pressed => {
                                 self.state = ButtonState::Pressed;
+                                info.focus.request_focus(focus);
                             },
 
                             // actually fire the event if the button is released
@@ -177,16 +298,90 @@ impl<S: State, W: Widget<S>> Widget<S> for Button<S, W> {
             self.state = ButtonState::Idle;
         }
 
+        // activate using the keyboard, if focused
+        if info.focus.is_focused(focus) {
+            for (_, key) in &info.keys {
+                if key.state == ElementState::Pressed
+                    && !key.repeat
+                    && matches!(
+                        key.logical_key,
+                        Key::Named(NamedKey::Enter) | Key::Named(NamedKey::Space)
+                    )
+                {
+                    update |= (self.on_pressed)(state);
+                }
+            }
+        }
+
+        // activate using assistive technologies
+        for action in info.access_actions_for(focus) {
+            if action == AccessAction::Click {
+                update |= (self.on_pressed)(state);
+            }
+        }
+
         // update on state change, due to re-coloring
         if old_state != self.state {
             update |= Update::DRAW;
         }
 
+        // transition to the colors of the new state
+        self.hover.set(if self.state == ButtonState::Idle {
+            0.0
+        } else {
+            1.0
+        });
+
+        self.press.set(if self.state == ButtonState::Pressed {
+            1.0
+        } else {
+            0.0
+        });
+
+        update |= self.hover.animate(info) | self.press.animate(info);
+
         update
     }
 
     fn widget_id(&mut self) -> WidgetId {
-        WidgetId::new("maycoon-widgets", "Button")
+        self.id.clone()
+    }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        InspectNode::new(self.widget_id())
+            .with_children(vec![self.child.get_mut(state).inspect(state)])
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let mut node = AccessNode::new(Role::Button).with_action(Action::Click);
+
+        if let Some(focus) = self.focus {
+            node = node.with_focus(focus);
+        }
+
+        node.with_children(vec![self.child.get_mut(state).accessibility(state)])
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert((self.state, self.hover.clone(), self.press.clone()));
+
+        if let Some(child) = self.child.value_mut() {
+            child.save_state(store.child(WidgetKey::new(0)));
+        }
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        if let Some((button_state, hover, press)) = store.take() {
+            self.state = button_state;
+            self.hover = hover;
+            self.press = press;
+        }
+
+        if let Some(mut child_store) = store.take_child(WidgetKey::new(0)) {
+            self.child
+                .get_mut(state)
+                .restore_state(state, &mut child_store);
+        }
     }
 }
 
@@ -203,3 +398,8 @@ pub enum ButtonState {
     /// This is when the `on_pressed` function is called.
     Released,
 }
+
+/// The style overrides of the button content, which inverts the text color to contrast with the button background.
+fn child_overrides() -> StyleOverrides {
+    StyleOverrides::new().with_invert_text_color(true)
+}
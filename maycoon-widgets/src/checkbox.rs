@@ -1,29 +1,63 @@
+use std::time::Duration;
+
+use maycoon_core::accesskit::{Action, Role};
+use maycoon_core::animation::{Animation, Lerp, Tween};
+use maycoon_core::app::accessibility::{AccessAction, AccessNode};
+use maycoon_core::app::focus::FocusId;
 use maycoon_core::app::info::AppInfo;
 use maycoon_core::app::update::Update;
+use maycoon_core::instance::InstanceStore;
 use maycoon_core::layout;
 use maycoon_core::layout::{Dimension, LayoutNode, LayoutStyle, LengthPercentageAuto, StyleNode};
 use maycoon_core::state::{State, Val};
 use maycoon_core::vg::kurbo::{Affine, Rect, RoundedRect, RoundedRectRadii, Stroke};
-use maycoon_core::vg::peniko::{Brush, Fill};
+use maycoon_core::vg::peniko::{Brush, Color, Fill};
 use maycoon_core::vg::Scene;
 use maycoon_core::widget::Widget;
-use maycoon_core::window::{ElementState, MouseButton};
+use maycoon_core::window::{ElementState, Key, MouseButton, NamedKey};
 use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
 use maycoon_theme::theme::Theme;
 use nalgebra::Vector2;
 
+/// The color of the checkbox, when it's not checked (inner value is false).
+pub const COLOR_UNCHECKED: StyleKey<Color> = StyleKey::new("color_unchecked", |defaults| {
+    defaults.interactive().inactive()
+});
+
+/// The color of the checkbox, when it's checked (inner value is true).
+pub const COLOR_CHECKED: StyleKey<Color> =
+    StyleKey::new("color_checked", |defaults| defaults.interactive().active());
+
+/// The style keys of the [Checkbox].
+pub const STYLE_KEYS: &[KeyInfo] = &[COLOR_UNCHECKED.info(), COLOR_CHECKED.info()];
+
 /// A checkbox widget. Changes state when it's clicked.
 ///
+/// The checkbox can be focused and toggled using the keyboard by pressing `Space` or by assistive technologies (e.g. screen readers).
+/// It smoothly transitions between its checked and unchecked look, when it's toggled.
+/// Inside a disabled style context (see [Globals](maycoon_theme::globals::Globals)), it uses the disabled color and can't be toggled.
+///
 /// See the [checkbox](https://github.com/maycoon-ui/maycoon/blob/master/examples/checkbox/src/main.rs) example for how to use it in practice.
 ///
 /// ### Theming
 /// Styling the checkbox require following properties:
 /// - `color_unchecked` -  The color of the checkbox, when it's not checked (inner value is false).
 /// - `color_checked` - The color of the checkbox, when it's checked (inner value is true).
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single checkbox using [Checkbox::with_style]
+/// and a style variant of the theme can be chosen using [Checkbox::with_class].
 pub struct Checkbox<S: State> {
     layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
     on_change: Box<dyn FnMut(&mut S) -> Update>,
     value: Val<S, bool>,
+    focus: Option<FocusId>,
+    transition: Option<Tween<f32>>,
 }
 
 impl<S: State> Checkbox<S> {
@@ -42,8 +76,12 @@ impl<S: State> Checkbox<S> {
                 },
                 ..Default::default()
             }),
+            id: WidgetId::new("maycoon-widgets", "Checkbox"),
+            theme_style: None.into(),
             on_change: Box::new(|_| Update::empty()),
             value,
+            focus: None,
+            transition: None,
         }
     }
 
@@ -68,6 +106,66 @@ impl<S: State> Checkbox<S> {
         self.value = value.into();
         self
     }
+
+    /// Sets the style of this checkbox, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this checkbox (e.g. `danger` for `Checkbox.danger`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
+
+    /// Toggle the checkbox using the mouse, the keyboard or assistive technologies.
+    fn handle_input(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        let mut update = Update::empty();
+        let focus = info.focus.register();
+
+        self.focus = Some(focus);
+
+        if let Some(cursor) = &info.cursor_pos {
+            if cursor.x as f32 >= layout.layout.location.x
+                && cursor.x as f32 <= layout.layout.location.x + layout.layout.size.width
+                && cursor.y as f32 >= layout.layout.location.y
+                && cursor.y as f32 <= layout.layout.location.y + layout.layout.size.height
+            {
+                for (_, btn, el) in &info.buttons {
+                    if *btn == MouseButton::Left {
+                        match el {
+                            ElementState::Pressed => info.focus.request_focus(focus),
+                            ElementState::Released => update |= (self.on_change)(state),
+                        }
+                    }
+                }
+            }
+        }
+
+        // toggle using the keyboard, if focused
+        if info.focus.is_focused(focus) {
+            for (_, key) in &info.keys {
+                if key.state == ElementState::Pressed
+                    && !key.repeat
+                    && key.logical_key == Key::Named(NamedKey::Space)
+                {
+                    update |= (self.on_change)(state);
+                }
+            }
+        }
+
+        // toggle using assistive technologies
+        for action in info.access_actions_for(focus) {
+            if action == AccessAction::Click {
+                update |= (self.on_change)(state);
+            }
+        }
+
+        update
+    }
 }
 
 impl<S: State> Widget<S> for Checkbox<S> {
@@ -75,26 +173,36 @@ impl<S: State> Widget<S> for Checkbox<S> {
         &mut self,
         scene: &mut Scene,
         theme: &mut dyn Theme,
-        _: &AppInfo,
+        info: &AppInfo,
         layout_node: &LayoutNode,
         state: &S,
     ) {
         let checked = *self.value.get_ref(state);
 
-        let color = if let Some(style) = theme.of(self.widget_id()) {
-            if checked {
-                style.get_color("color_checked").unwrap()
-            } else {
-                style.get_color("color_unchecked").unwrap()
-            }
+        // the progress of the transition from unchecked to checked
+        let progress = self
+            .transition
+            .as_ref()
+            .map_or(if checked { 1.0 } else { 0.0 }, Tween::value);
+
+        let context = theme.globals().context().clone();
+
+        let (color_unchecked, color_checked) = if context.disabled {
+            let disabled = theme.defaults().interactive().disabled();
+
+            (disabled, disabled)
         } else {
-            if checked {
-                theme.defaults().interactive().active()
-            } else {
-                theme.defaults().interactive().inactive()
-            }
+            let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+            let defaults = theme.defaults();
+
+            (
+                style.resolve(&COLOR_UNCHECKED, &defaults),
+                style.resolve(&COLOR_CHECKED, &defaults),
+            )
         };
 
+        let color = context.color(color_unchecked.lerp(&color_checked, progress));
+
         scene.stroke(
             &Stroke::new(3.0),
             Affine::default(),
@@ -111,7 +219,30 @@ impl<S: State> Widget<S> for Checkbox<S> {
             ),
         );
 
-        if checked {
+        if self.focus.is_some_and(|focus| info.focus.is_focused(focus)) {
+            scene.stroke(
+                &Stroke::new(2.0),
+                Affine::default(),
+                &Brush::Solid(context.color(theme.defaults().interactive().active())),
+                None,
+                &RoundedRect::from_rect(
+                    Rect::new(
+                        layout_node.layout.location.x as f64 - 4.0,
+                        layout_node.layout.location.y as f64 - 4.0,
+                        (layout_node.layout.location.x + layout_node.layout.size.width) as f64
+                            + 4.0,
+                        (layout_node.layout.location.y + layout_node.layout.size.height) as f64
+                            + 4.0,
+                    ),
+                    RoundedRectRadii::from_single_radius(8.0),
+                ),
+            );
+        }
+
+        if progress > 0.0 {
+            // the check mark grows from the center
+            let inset = 5.0 + (1.0 - progress as f64) * 5.0;
+
             scene.fill(
                 Fill::NonZero,
                 Affine::default(),
@@ -119,12 +250,12 @@ impl<S: State> Widget<S> for Checkbox<S> {
                 None,
                 &RoundedRect::from_rect(
                     Rect::new(
-                        layout_node.layout.location.x as f64 + 5.0,
-                        layout_node.layout.location.y as f64 + 5.0,
+                        layout_node.layout.location.x as f64 + inset,
+                        layout_node.layout.location.y as f64 + inset,
                         (layout_node.layout.location.x + layout_node.layout.size.width) as f64
-                            - 5.0,
+                            - inset,
                         (layout_node.layout.location.y + layout_node.layout.size.height) as f64
-                            - 5.0,
+                            - inset,
                     ),
                     RoundedRectRadii::from_single_radius(2.5),
                 ),
@@ -136,39 +267,64 @@ impl<S: State> Widget<S> for Checkbox<S> {
         StyleNode {
             style: self.layout_style.get_ref(state).clone(),
             children: Vec::new(),
+            key: None,
+            measure: None,
         }
     }
 
     fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
         self.value.invalidate();
         self.layout_style.invalidate();
+        self.theme_style.invalidate();
 
         let mut update = Update::empty();
 
-        if let Some(cursor) = &info.cursor_pos {
-            if cursor.x as f32 >= layout.layout.location.x
-                && cursor.x as f32 <= layout.layout.location.x + layout.layout.size.width
-                && cursor.y as f32 >= layout.layout.location.y
-                && cursor.y as f32 <= layout.layout.location.y + layout.layout.size.height
-            {
-                for (_, btn, el) in &info.buttons {
-                    match btn {
-                        MouseButton::Left => {
-                            if *el == ElementState::Released {
-                                update |= (self.on_change)(state);
-                            }
-                        },
-
-                        _ => (),
-                    }
-                }
-            }
+        // disabled checkboxes can't be focused or toggled
+        if info.style.context().disabled {
+            self.focus = None;
+        } else {
+            update |= self.handle_input(layout, state, info);
         }
 
+        // transition to the new value, which may have changed
+        self.value.invalidate();
+
+        let checked = if *self.value.get_ref(state) { 1.0 } else { 0.0 };
+
+        let transition = self
+            .transition
+            .get_or_insert_with(|| Tween::new(checked, Duration::from_millis(150)));
+
+        transition.set(checked);
+
+        update |= transition.animate(info);
+
         update
     }
 
     fn widget_id(&mut self) -> WidgetId {
-        WidgetId::new("maycoon-widgets", "Checkbox")
+        self.id.clone()
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let mut node = AccessNode::new(Role::CheckBox)
+            .with_toggled(*self.value.get_ref(state))
+            .with_action(Action::Click);
+
+        if let Some(focus) = self.focus {
+            node = node.with_focus(focus);
+        }
+
+        node
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert(self.transition.clone());
+    }
+
+    fn restore_state(&mut self, _: &S, store: &mut InstanceStore) {
+        if let Some(transition) = store.take() {
+            self.transition = transition;
+        }
     }
 }
@@ -1,5 +1,10 @@
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
 use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::inspector::InspectNode;
 use maycoon_core::app::update::Update;
+use maycoon_core::instance;
+use maycoon_core::instance::{InstanceStore, KeyedChildren, WidgetKey};
 use maycoon_core::layout::{LayoutNode, LayoutStyle, StyleNode};
 use maycoon_core::state::{State, Val};
 use maycoon_core::vg::Scene;
@@ -11,6 +16,10 @@ use maycoon_theme::theme::Theme;
 ///
 /// The layout of the children (row, column, etc.) depends on the [LayoutStyle] of the container.
 ///
+/// State dependent children are rebuilt on every update, but keep their instance state (e.g. hover or drag state).
+/// For dynamic lists, use [Container::with_keyed_children], so the children keep their instance state
+/// when items get inserted, removed or reordered.
+///
 /// See the [counter](https://github.com/maycoon-ui/maycoon/blob/master/examples/counter/src/main.rs) example for how to use it in practice.
 ///
 /// ### Theming
@@ -18,6 +27,7 @@ use maycoon_theme::theme::Theme;
 pub struct Container<S: State> {
     style: Val<S, LayoutStyle>,
     children: Vec<Val<S, Box<dyn Widget<S>>>>,
+    keyed: Val<S, KeyedChildren<S>>,
 }
 
 impl<S: State> Container<S> {
@@ -26,6 +36,7 @@ impl<S: State> Container<S> {
         Self {
             style: LayoutStyle::default().into(),
             children,
+            keyed: Val::new_val(Vec::new()),
         }
     }
 
@@ -56,6 +67,26 @@ impl<S: State> Container<S> {
         self.add_child(child);
         self
     }
+
+    /// Sets the keyed children of the container, which are placed after the other children.
+    ///
+    /// Use a state dependent value to build the children from a dynamic list.
+    /// Rebuilt children keep the instance state of the previous child with the same [WidgetKey].
+    pub fn with_keyed_children(mut self, children: impl Into<Val<S, KeyedChildren<S>>>) -> Self {
+        self.keyed = children.into();
+        self
+    }
+
+    /// Returns all children, including the keyed ones, computing them if necessary.
+    fn all_children<'a>(
+        &'a mut self,
+        state: &'a S,
+    ) -> impl Iterator<Item = &'a mut Box<dyn Widget<S>>> + 'a {
+        self.children
+            .iter_mut()
+            .map(|child| child.get_mut(state))
+            .chain(self.keyed.get_mut(state).iter_mut().map(|(_, child)| child))
+    }
 }
 
 impl<S: State> Default for Container<S> {
@@ -73,38 +104,69 @@ impl<S: State> Widget<S> for Container<S> {
         layout_node: &LayoutNode,
         state: &S,
     ) {
-        for (i, child) in self.children.iter_mut().enumerate() {
-            let child = child.get_mut(state);
-            child.render(scene, theme, info, &layout_node.children[i], state);
+        for (child, layout) in self.all_children(state).zip(&layout_node.children) {
+            child.render(scene, theme, info, layout, state);
         }
     }
 
     fn layout_style(&mut self, state: &S) -> StyleNode {
         let style = self.style.get_ref(state).clone();
 
-        let mut children = Vec::with_capacity(self.children.len());
-
-        for child in &mut self.children {
-            let child = child.get_mut(state);
-
-            children.push(child.layout_style(state));
+        let mut children = self
+            .children
+            .iter_mut()
+            .map(|child| child.get_mut(state).layout_style(state))
+            .collect::<Vec<_>>();
+
+        // keyed children keep their layout, when other children are added or removed
+        children.extend(
+            self.keyed
+                .get_mut(state)
+                .iter_mut()
+                .map(|(key, child)| StyleNode {
+                    key: Some(*key),
+                    ..child.layout_style(state)
+                }),
+        );
+
+        StyleNode {
+            style,
+            children,
+            key: None,
+            measure: None,
         }
-
-        StyleNode { style, children }
     }
 
     fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
         self.style.invalidate();
 
+        // rebuild state dependent children, but keep their instance state
         for child in &mut self.children {
-            child.invalidate();
+            child.rebuild(state);
         }
 
+        self.keyed.rebuild_keyed(state);
+
         let mut update = Update::empty();
+        let count = self.children.len();
 
-        for (i, child) in self.children.iter_mut().enumerate() {
+        for (child, layout) in self.children.iter_mut().zip(&layout.children) {
             let child = child.get_mut(state);
-            update.insert(child.update(&layout.children[i], state, info));
+            update.insert(child.update(layout, state, info));
+        }
+
+        let keyed = self.keyed.get_mut(state);
+
+        // the layout of added or removed children is not computed yet
+        if layout.children.len() != count + keyed.len() {
+            update.insert(Update::LAYOUT);
+        }
+
+        // keyed children keep their focus ids, when other children are added or removed
+        for ((key, child), layout) in keyed.iter_mut().zip(layout.children.iter().skip(count)) {
+            info.focus.push_scope(*key);
+            update.insert(child.update(layout, state, info));
+            info.focus.pop_scope();
         }
 
         update
@@ -113,4 +175,46 @@ impl<S: State> Widget<S> for Container<S> {
     fn widget_id(&mut self) -> WidgetId {
         WidgetId::new("maycoon-widgets", "Container")
     }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        let children = self
+            .all_children(state)
+            .map(|child| child.inspect(state))
+            .collect();
+
+        InspectNode::new(self.widget_id()).with_children(children)
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let children = self
+            .all_children(state)
+            .map(|child| child.accessibility(state))
+            .collect();
+
+        AccessNode::new(Role::GenericContainer).with_children(children)
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Some(child) = child.value_mut() {
+                child.save_state(store.child(WidgetKey::new(i)));
+            }
+        }
+
+        if let Some(keyed) = self.keyed.value_mut() {
+            instance::save_keyed(keyed, store.child(WidgetKey::new("keyed")));
+        }
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        for (i, child) in self.children.iter_mut().enumerate() {
+            if let Some(mut child_store) = store.take_child(WidgetKey::new(i)) {
+                child.get_mut(state).restore_state(state, &mut child_store);
+            }
+        }
+
+        if let Some(mut keyed_store) = store.take_child(WidgetKey::new("keyed")) {
+            instance::restore_keyed(self.keyed.get_mut(state), state, &mut keyed_store);
+        }
+    }
 }
@@ -20,6 +20,8 @@ impl<S: State> Widget<S> for DummyWidget {
         StyleNode {
             style: LayoutStyle::default(),
             children: Vec::new(),
+            key: None,
+            measure: None,
         }
     }
 
@@ -1,9 +1,13 @@
+use std::rc::Rc;
+
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
 use maycoon_core::app::info::AppInfo;
 use maycoon_core::app::update::Update;
-use maycoon_core::layout::{LayoutNode, LayoutStyle, StyleNode};
+use maycoon_core::layout::{LayoutNode, LayoutStyle, MeasureCache, StyleNode};
 use maycoon_core::state::{State, Val};
-use maycoon_core::vg::kurbo::{Affine, Vec2};
-use maycoon_core::vg::peniko::{Blob, Format};
+use maycoon_core::vg::kurbo::{Affine, Rect, Vec2};
+use maycoon_core::vg::peniko::{BlendMode, Blob, Format};
 use maycoon_core::vg::{peniko, Scene};
 use maycoon_core::widget::Widget;
 use maycoon_theme::id::WidgetId;
@@ -12,13 +16,20 @@ use nalgebra::Vector2;
 
 /// An image widget. Pretty self-explanatory.
 ///
+/// By default, the image is sized to its pixel dimensions.
+/// If only the width or height is given by the layout style, the other dimension follows the aspect ratio of the image.
+/// The image is scaled to fit into its layout, while keeping its aspect ratio.
+///
 /// See the [image](https://github.com/maycoon-ui/maycoon/blob/master/examples/image/src/main.rs) example for how to use it in practice.
 ///
 /// ### Theming
 /// The widget itself only draws the underlying image, so theming is useless.
+/// The image is faded by the inherited opacity (see [Globals](maycoon_theme::globals::Globals)).
 pub struct Image<S: State> {
     image: Val<S, peniko::Image>,
     style: Val<S, LayoutStyle>,
+    alt: Val<S, Option<String>>,
+    measure: MeasureCache<(u32, u32)>,
 }
 
 impl<S: State> Image<S> {
@@ -34,6 +45,8 @@ impl<S: State> Image<S> {
                 )
             }),
             style: LayoutStyle::default().into(),
+            alt: None.into(),
+            measure: MeasureCache::default(),
         }
     }
 
@@ -42,44 +55,117 @@ impl<S: State> Image<S> {
         self.style = style.into();
         self
     }
+
+    /// Set the alternative text, which describes the image for assistive technologies (e.g. screen readers).
+    pub fn with_alt(mut self, alt: impl Into<Val<S, String>>) -> Self {
+        self.alt = alt.into().map(Some);
+        self
+    }
 }
 
 impl<S: State> Widget<S> for Image<S> {
     fn render(
         &mut self,
         scene: &mut Scene,
-        _: &mut dyn Theme,
+        theme: &mut dyn Theme,
         _: &AppInfo,
         layout_node: &LayoutNode,
         state: &S,
     ) {
         let image = self.image.get_ref(state);
+        let opacity = theme.globals().context().opacity;
+
+        let scale = if image.width == 0 || image.height == 0 {
+            1.0
+        } else {
+            (layout_node.layout.size.width as f64 / image.width as f64)
+                .min(layout_node.layout.size.height as f64 / image.height as f64)
+        };
+
+        // images have no alpha, so fade them using a layer
+        if opacity < 1.0 {
+            scene.push_layer(
+                BlendMode::default(),
+                opacity,
+                Affine::default(),
+                &Rect::new(
+                    layout_node.layout.location.x as f64,
+                    layout_node.layout.location.y as f64,
+                    (layout_node.layout.location.x + layout_node.layout.size.width) as f64,
+                    (layout_node.layout.location.y + layout_node.layout.size.height) as f64,
+                ),
+            );
+        }
 
         scene.draw_image(
             image,
             Affine::translate(Vec2::new(
                 layout_node.layout.location.x as f64,
                 layout_node.layout.location.y as f64,
-            )),
+            )) * Affine::scale(scale),
         );
+
+        if opacity < 1.0 {
+            scene.pop_layer();
+        }
     }
 
     fn layout_style(&mut self, state: &S) -> StyleNode {
+        let image = self.image.get_ref(state);
+
         StyleNode {
             style: self.style.get_ref(state).clone(),
             children: Vec::new(),
+            key: None,
+            measure: Some(
+                self.measure
+                    .get((image.width, image.height), |&(width, height)| {
+                        let size = Vector2::new(width as f32, height as f32);
+
+                        Rc::new(move |known, _, _| {
+                            let aspect_ratio = if size.y == 0.0 { 1.0 } else { size.x / size.y };
+
+                            match (known.x, known.y) {
+                                (Some(width), Some(height)) => Vector2::new(width, height),
+                                (Some(width), None) => Vector2::new(width, width / aspect_ratio),
+                                (None, Some(height)) => Vector2::new(height * aspect_ratio, height),
+                                (None, None) => size,
+                            }
+                        })
+                    }),
+            ),
         }
     }
 
-    fn update(&mut self, _: &LayoutNode, _: &mut S, _: &AppInfo) -> Update {
+    fn update(&mut self, _: &LayoutNode, state: &mut S, _: &AppInfo) -> Update {
+        let image = self.image.get_ref(state);
+        let size = (image.width, image.height);
+
         self.image.invalidate();
         self.style.invalidate();
-        Update::empty()
+
+        // the layout depends on the image size
+        let image = self.image.get_ref(state);
+
+        if (image.width, image.height) != size {
+            Update::LAYOUT
+        } else {
+            Update::empty()
+        }
     }
 
     fn widget_id(&mut self) -> WidgetId {
         WidgetId::new("maycoon-widgets", "image")
     }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let node = AccessNode::new(Role::Image);
+
+        match self.alt.get_ref(state) {
+            Some(alt) => node.with_label(alt),
+            None => node,
+        }
+    }
 }
 
 /// Contains data about the image itself, size and the image format.
@@ -24,3 +24,21 @@ pub mod checkbox;
 
 /// Contains the [slider::Slider] widget.
 pub mod slider;
+
+/// Contains the [text_input::TextInput] widget.
+pub mod text_input;
+
+/// Contains the [text_area::TextArea] widget.
+pub mod text_area;
+
+/// Contains the [scroll_view::ScrollView] widget.
+pub mod scroll_view;
+
+/// Contains the [virtual_list::VirtualList] widget.
+pub mod virtual_list;
+
+/// Contains the [styled::Styled] widget.
+pub mod styled;
+
+/// Contains the [schema::schema] of the style keys of all widgets.
+pub mod schema;
@@ -0,0 +1,35 @@
+use maycoon_core::app::overlay;
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::StyleSchema;
+
+use crate::{button, checkbox, scroll_view, slider, text, text_input};
+
+/// Returns the style keys of all widgets of this crate and of the overlays of `maycoon-core`.
+///
+/// Use it to check a theme for missing or mistyped values, before using it:
+///
+/// ```
+/// use maycoon_theme::theme::celeste::CelesteTheme;
+/// use maycoon_widgets::schema::schema;
+///
+/// for issue in schema().validate(&CelesteTheme::dark()) {
+///     eprintln!("{}", issue);
+/// }
+/// ```
+pub fn schema() -> StyleSchema {
+    let id = |id: &str| WidgetId::new("maycoon-widgets", id);
+
+    StyleSchema::new()
+        .with_widget(id("Text"), text::STYLE_KEYS)
+        .with_widget(id("Button"), button::STYLE_KEYS)
+        .with_widget(id("Checkbox"), checkbox::STYLE_KEYS)
+        .with_widget(id("Slider"), slider::STYLE_KEYS)
+        .with_widget(id("TextInput"), text_input::STYLE_KEYS)
+        .with_widget(id("TextArea"), text_input::STYLE_KEYS)
+        .with_widget(id("ScrollView"), scroll_view::STYLE_KEYS)
+        .with_widget(id("VirtualList"), scroll_view::STYLE_KEYS)
+        .with_widget(
+            WidgetId::new("maycoon-core", "Overlay"),
+            overlay::STYLE_KEYS,
+        )
+}
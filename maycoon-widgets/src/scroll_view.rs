@@ -0,0 +1,797 @@
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
+use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::inspector::InspectNode;
+use maycoon_core::app::update::Update;
+use maycoon_core::instance::{InstanceStore, WidgetKey};
+use maycoon_core::layout::{FlexDirection, Layout, LayoutNode, LayoutStyle, Overflow, StyleNode};
+use maycoon_core::state::{State, Val};
+use maycoon_core::vg::kurbo::{Affine, Point, Rect, RoundedRect, RoundedRectRadii, Vec2};
+use maycoon_core::vg::peniko::{Brush, Color, Fill, Mix};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_core::window::{DeviceId, ElementState, MouseButton, MouseScrollDelta, TouchPhase};
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
+use maycoon_theme::theme::Theme;
+use nalgebra::Vector2;
+
+/// The color of the scrollbar track.
+pub const COLOR_TRACK: StyleKey<Color> =
+    StyleKey::new("color_track", |defaults| defaults.container().background());
+
+/// The color of the scrollbar thumb.
+pub const COLOR_THUMB: StyleKey<Color> =
+    StyleKey::new("color_thumb", |defaults| defaults.interactive().inactive());
+
+/// The color of the scrollbar thumb, when hovered or dragged.
+pub const COLOR_THUMB_ACTIVE: StyleKey<Color> = StyleKey::new("color_thumb_active", |defaults| {
+    defaults.interactive().hover()
+});
+
+/// The style keys of the [ScrollView] and [VirtualList](crate::virtual_list::VirtualList).
+pub const STYLE_KEYS: &[KeyInfo] = &[
+    COLOR_TRACK.info(),
+    COLOR_THUMB.info(),
+    COLOR_THUMB_ACTIVE.info(),
+];
+
+/// The width of the scrollbars.
+const SCROLLBAR_WIDTH: f32 = 8.0;
+
+/// The space between the scrollbars and the edge of the viewport.
+const SCROLLBAR_MARGIN: f32 = 2.0;
+
+/// The minimum length of a scrollbar thumb.
+const MIN_THUMB_LENGTH: f32 = 20.0;
+
+/// The distance to scroll per line of a mouse wheel.
+const LINE_HEIGHT: f32 = 40.0;
+
+/// How fast the momentum of a drag slows down. Higher values stop faster.
+const FRICTION: f32 = 4.0;
+
+/// The speed in pixels per second at which the momentum stops.
+const MIN_VELOCITY: f32 = 10.0;
+
+/// A scrollable viewport for a child widget, that is larger than the available space.
+///
+/// The child is clipped to the viewport and can be scrolled using the mouse wheel, the touchpad
+/// or by dragging the scrollbars. If enabled with [ScrollView::with_drag_scroll],
+/// the content can also be dragged and keeps scrolling with momentum when released.
+///
+/// The child keeps its natural size in the scroll direction and stretches across the viewport otherwise.
+/// Use [ScrollView::with_scroll_to] to scroll programmatically.
+///
+/// ### Theming
+/// You can style the scroll view using the following properties:
+/// - `color_track` - The color of the scrollbar track.
+/// - `color_thumb` - The color of the scrollbar thumb.
+/// - `color_thumb_active` - The color of the scrollbar thumb, when hovered or dragged.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single scroll view using [ScrollView::with_style]
+/// and a style variant of the theme can be chosen using [ScrollView::with_class].
+pub struct ScrollView<S: State, W: Widget<S> + 'static> {
+    child: Val<S, W>,
+    layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
+    scroller: Scroller,
+    scroll_to: Val<S, Option<Vector2<f32>>>,
+    last_scroll_to: Option<Vector2<f32>>,
+    on_scroll: OnScroll<S>,
+}
+
+/// The callback of the scroll view, that gets called when the scroll offset changes.
+type OnScroll<S> = Box<dyn FnMut(&mut S, Vector2<f32>) -> Update>;
+
+impl<S: State, W: Widget<S> + 'static> ScrollView<S, W> {
+    /// Create a new scroll view with the given child widget. Scrolls vertically by default.
+    pub fn new(child: impl Into<Val<S, W>>) -> Self {
+        Self {
+            child: child.into(),
+            layout_style: LayoutStyle {
+                flex_direction: FlexDirection::Column,
+                overflow: (Overflow::Scroll, Overflow::Scroll),
+                ..Default::default()
+            }
+            .into(),
+            id: WidgetId::new("maycoon-widgets", "ScrollView"),
+            theme_style: None.into(),
+            scroller: Scroller::new(false, true),
+            scroll_to: None.into(),
+            last_scroll_to: None,
+            on_scroll: Box::new(|_, _| Update::empty()),
+        }
+    }
+
+    /// Set if the content can be scrolled horizontally. Disabled by default.
+    pub fn with_horizontal(mut self, horizontal: bool) -> Self {
+        self.scroller.horizontal = horizontal;
+        self
+    }
+
+    /// Set if the content can be scrolled vertically. Enabled by default.
+    pub fn with_vertical(mut self, vertical: bool) -> Self {
+        self.scroller.vertical = vertical;
+        self
+    }
+
+    /// Set if the content can be dragged with the mouse, keeping its momentum when released.
+    /// Disabled by default.
+    pub fn with_drag_scroll(mut self, drag_scroll: bool) -> Self {
+        self.scroller.drag_scroll = drag_scroll;
+        self
+    }
+
+    /// Scroll to the given offset, whenever the value changes to [Some] offset.
+    ///
+    /// The value should be state dependent, so you can scroll from outside the widget.
+    pub fn with_scroll_to(mut self, offset: impl Into<Val<S, Option<Vector2<f32>>>>) -> Self {
+        self.scroll_to = offset.into();
+        self
+    }
+
+    /// Set the function, that gets called when the scroll offset changes.
+    pub fn with_on_scroll(
+        mut self,
+        on_scroll: impl FnMut(&mut S, Vector2<f32>) -> Update + 'static,
+    ) -> Self {
+        self.on_scroll = Box::new(on_scroll);
+        self
+    }
+
+    /// Set the layout style of the scroll view.
+    ///
+    /// The overflow should be set to [Overflow::Scroll], so the scroll view doesn't grow with its child.
+    pub fn with_layout_style(mut self, layout_style: impl Into<Val<S, LayoutStyle>>) -> Self {
+        self.layout_style = layout_style.into();
+        self
+    }
+
+    /// Sets the style of this scroll view, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this scroll view (e.g. `subtle` for `ScrollView.subtle`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
+
+    /// Returns the current scroll offset.
+    pub fn offset(&self) -> Vector2<f32> {
+        self.scroller.offset
+    }
+
+    /// Scroll to the given offset. Gets clamped to the content size on the next update.
+    pub fn scroll_to(&mut self, offset: Vector2<f32>) {
+        self.scroller.scroll_to(offset);
+    }
+}
+
+impl<S: State, W: Widget<S> + 'static> Widget<S> for ScrollView<S, W> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        let (viewport, content) = content_bounds(layout_node);
+
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &viewport);
+
+        {
+            let mut child_scene = Scene::new();
+
+            self.child.get_mut(state).render(
+                &mut child_scene,
+                theme,
+                info,
+                &layout_node.children[0],
+                state,
+            );
+
+            scene.append(
+                &child_scene,
+                Some(self.scroller.content_transform(layout_node)),
+            );
+        }
+
+        scene.pop_layer();
+
+        let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+
+        self.scroller
+            .render(scene, theme, &style, viewport, content);
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        let mut child = self.child.get_mut(state).layout_style(state);
+
+        // the child should keep its size and overflow the viewport instead
+        child.style.flex_shrink = 0.0;
+
+        StyleNode {
+            style: self.layout_style.get_ref(state).clone(),
+            children: vec![child],
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.layout_style.invalidate();
+        self.theme_style.invalidate();
+        self.child.rebuild(state);
+        self.scroll_to.invalidate();
+
+        let old_offset = self.scroller.offset;
+
+        // scroll programmatically, if the requested offset changed
+        let scroll_to = *self.scroll_to.get_ref(state);
+
+        if scroll_to != self.last_scroll_to {
+            self.last_scroll_to = scroll_to;
+
+            if let Some(offset) = scroll_to {
+                self.scroller.scroll_to(offset);
+            }
+        }
+
+        let (viewport, content) = content_bounds(layout);
+        let mut update = self.scroller.update(viewport, content, info);
+
+        if self.scroller.offset != old_offset {
+            update.insert((self.on_scroll)(state, self.scroller.offset));
+            update.insert(Update::DRAW);
+        }
+
+        let child_info = self.scroller.child_info(layout, info);
+
+        update.insert(
+            self.child
+                .get_mut(state)
+                .update(&layout.children[0], state, &child_info),
+        );
+
+        update
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        self.id.clone()
+    }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        InspectNode::new(self.widget_id())
+            .with_offset(-self.scroller.offset)
+            .with_children(vec![self.child.get_mut(state).inspect(state)])
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        AccessNode::new(Role::ScrollView)
+            .with_offset(-self.scroller.offset)
+            .with_children(vec![self.child.get_mut(state).accessibility(state)])
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        self.scroller.save_state(store);
+
+        if let Some(child) = self.child.value_mut() {
+            child.save_state(store.child(WidgetKey::new(0)));
+        }
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        self.scroller.restore_state(store);
+
+        if let Some(mut child_store) = store.take_child(WidgetKey::new(0)) {
+            self.child
+                .get_mut(state)
+                .restore_state(state, &mut child_store);
+        }
+    }
+}
+
+/// Returns the viewport and the content size of the scroll view.
+///
+/// The content extends from the viewport origin to the end of the child including padding.
+fn content_bounds(layout: &LayoutNode) -> (Rect, Vector2<f32>) {
+    let own = &layout.layout;
+    let child = &layout.children[0].layout;
+
+    (
+        viewport(own),
+        Vector2::new(
+            child.location.x + child.size.width + own.padding.right - own.border.left,
+            child.location.y + child.size.height + own.padding.bottom - own.border.top,
+        ),
+    )
+}
+
+/// Returns the visible area of a scrollable widget with the given layout, which is the area inside its border.
+pub(crate) fn viewport(layout: &Layout) -> Rect {
+    Rect::new(
+        (layout.location.x + layout.border.left) as f64,
+        (layout.location.y + layout.border.top) as f64,
+        (layout.location.x + layout.size.width - layout.border.right) as f64,
+        (layout.location.y + layout.size.height - layout.border.bottom) as f64,
+    )
+}
+
+/// Scrolls the content of a viewport using the mouse wheel, the touchpad, the scrollbars or by dragging the content.
+///
+/// Used by scrollable widgets like [ScrollView] and [VirtualList](crate::virtual_list::VirtualList).
+pub(crate) struct Scroller {
+    /// If the content can be scrolled horizontally.
+    pub(crate) horizontal: bool,
+    /// If the content can be scrolled vertically.
+    pub(crate) vertical: bool,
+    /// If the content can be dragged, keeping its momentum when released.
+    pub(crate) drag_scroll: bool,
+    /// The current scroll offset.
+    pub(crate) offset: Vector2<f32>,
+    velocity: Vector2<f32>,
+    drag: Option<Drag>,
+    hovered: bool,
+    thumb_hovered: bool,
+    /// The wheel events of the last update, that didn't scroll the content and are passed to the children.
+    unused_wheel: Vec<(DeviceId, MouseScrollDelta, TouchPhase)>,
+}
+
+impl Scroller {
+    /// Create a new scroller, that can scroll in the given directions.
+    pub(crate) fn new(horizontal: bool, vertical: bool) -> Self {
+        Self {
+            horizontal,
+            vertical,
+            drag_scroll: false,
+            offset: Vector2::new(0.0, 0.0),
+            velocity: Vector2::new(0.0, 0.0),
+            drag: None,
+            hovered: false,
+            thumb_hovered: false,
+            unused_wheel: Vec::new(),
+        }
+    }
+
+    /// Save the scroll offset, the momentum and the active drag into the given store.
+    pub(crate) fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert(ScrollState {
+            offset: self.offset,
+            velocity: self.velocity,
+            drag: self.drag.take(),
+            hovered: self.hovered,
+            thumb_hovered: self.thumb_hovered,
+        });
+    }
+
+    /// Restore the state saved using [Scroller::save_state].
+    pub(crate) fn restore_state(&mut self, store: &mut InstanceStore) {
+        if let Some(scroll) = store.take::<ScrollState>() {
+            self.offset = scroll.offset;
+            self.velocity = scroll.velocity;
+            self.drag = scroll.drag;
+            self.hovered = scroll.hovered;
+            self.thumb_hovered = scroll.thumb_hovered;
+        }
+    }
+
+    /// Scroll to the given offset and stop the momentum. Gets clamped to the content size on the next update.
+    pub(crate) fn scroll_to(&mut self, offset: Vector2<f32>) {
+        self.offset = offset;
+        self.velocity = Vector2::new(0.0, 0.0);
+    }
+
+    /// Process the scroll events inside the given viewport with the given content size.
+    ///
+    /// Wheel events are consumed, unless the content is already scrolled to its edge in their direction,
+    /// so nested scrollable widgets don't scroll at the same time.
+    ///
+    /// Returns [Update::EVAL] and [Update::DRAW], while the content keeps scrolling with momentum.
+    pub(crate) fn update(
+        &mut self,
+        viewport: Rect,
+        content: Vector2<f32>,
+        info: &AppInfo,
+    ) -> Update {
+        let mut update = Update::empty();
+
+        let dt = info.delta_time.as_secs_f32().min(0.1);
+
+        let bars = Scrollbars::new(
+            viewport,
+            content,
+            self.offset,
+            self.horizontal,
+            self.vertical,
+        );
+        let cursor = info
+            .cursor_pos
+            .map(|pos| Vector2::new(pos.x as f32, pos.y as f32));
+
+        self.hovered = cursor.is_some_and(|pos| contains(bars.viewport, pos));
+        self.thumb_hovered = cursor.is_some_and(|pos| bars.thumb_at(pos).is_some());
+
+        self.unused_wheel.clear();
+
+        for event in &info.wheel {
+            if !self.hovered {
+                self.unused_wheel.push(*event);
+                continue;
+            }
+
+            let mut delta = match event.1 {
+                MouseScrollDelta::LineDelta(x, y) => Vector2::new(x, y) * LINE_HEIGHT,
+                MouseScrollDelta::PixelDelta(pos) => Vector2::new(pos.x as f32, pos.y as f32),
+            };
+
+            // scroll horizontally with a vertical mouse wheel
+            if !self.vertical || info.modifiers.shift_key() {
+                delta = Vector2::new(delta.x + delta.y, 0.0);
+            }
+
+            let offset = bars.clamp(self.offset - delta);
+
+            // pass the event on, if the content is already scrolled to its edge
+            if offset == bars.clamp(self.offset) {
+                self.unused_wheel.push(*event);
+            } else {
+                self.offset = offset;
+                self.velocity = Vector2::new(0.0, 0.0);
+            }
+        }
+
+        for (_, button, el_state) in &info.buttons {
+            if *button != MouseButton::Left {
+                continue;
+            }
+
+            match (el_state, cursor) {
+                (ElementState::Pressed, Some(pos)) if self.hovered => {
+                    self.velocity = Vector2::new(0.0, 0.0);
+
+                    if let Some(vertical) = bars.thumb_at(pos) {
+                        self.drag = Some(Drag::Thumb {
+                            vertical,
+                            start: pos,
+                            offset: self.offset,
+                        });
+                    } else if self.drag_scroll {
+                        self.drag = Some(Drag::Content { last: pos });
+                    }
+                },
+
+                (ElementState::Released, _) => self.drag = None,
+
+                _ => (),
+            }
+        }
+
+        match (&mut self.drag, cursor) {
+            (
+                Some(Drag::Thumb {
+                    vertical,
+                    start,
+                    offset,
+                }),
+                Some(pos),
+            ) => {
+                let (moved, bar, max) = if *vertical {
+                    (pos.y - start.y, bars.vertical, bars.max.y)
+                } else {
+                    (pos.x - start.x, bars.horizontal, bars.max.x)
+                };
+
+                if let Some((track, thumb)) = bar {
+                    // the thumb moves across the free space of the track, while the content moves across the max offset
+                    let free = if *vertical {
+                        track.height() - thumb.height()
+                    } else {
+                        track.width() - thumb.width()
+                    };
+                    let moved = moved * max / (free as f32).max(1.0);
+
+                    if *vertical {
+                        self.offset.y = offset.y + moved;
+                    } else {
+                        self.offset.x = offset.x + moved;
+                    }
+                }
+            },
+
+            (Some(Drag::Content { last }), Some(pos)) => {
+                let moved = pos - *last;
+
+                *last = pos;
+                self.offset -= moved;
+
+                if dt > 0.0 {
+                    // smooth the velocity, so the last small movement doesn't dominate
+                    self.velocity = self.velocity * 0.5 - moved / dt * 0.5;
+                }
+            },
+
+            (Some(_), None) => self.drag = None,
+
+            (None, _) if self.velocity != Vector2::new(0.0, 0.0) => {
+                self.offset += self.velocity * dt;
+                self.velocity *= (-FRICTION * dt).exp();
+
+                if self.velocity.norm() < MIN_VELOCITY {
+                    self.velocity = Vector2::new(0.0, 0.0);
+                }
+
+                // keep updating until the momentum stops
+                update.insert(Update::EVAL | Update::DRAW);
+            },
+
+            _ => (),
+        }
+
+        self.offset = bars.clamp(self.offset);
+
+        // stop the momentum at the edges of the content
+        if self.offset.x == 0.0 || self.offset.x == bars.max.x {
+            self.velocity.x = 0.0;
+        }
+
+        if self.offset.y == 0.0 || self.offset.y == bars.max.y {
+            self.velocity.y = 0.0;
+        }
+
+        update
+    }
+
+    /// Returns the transformation from the content into the window.
+    pub(crate) fn content_transform(&self, layout: &LayoutNode) -> Affine {
+        Affine::translate(Vec2::new(
+            (layout.layout.location.x - self.offset.x) as f64,
+            (layout.layout.location.y - self.offset.y) as f64,
+        ))
+    }
+
+    /// Returns the app info for the content with the cursor and origin translated into the content,
+    /// so children can hit-test themselves.
+    ///
+    /// Hides the cursor, if it is outside the viewport or on a scrollbar,
+    /// and removes the wheel events, that scrolled the content.
+    pub(crate) fn child_info(&self, layout: &LayoutNode, info: &AppInfo) -> AppInfo {
+        let mut child_info = info.clone();
+
+        child_info.wheel = self.unused_wheel.clone();
+
+        child_info.origin = info.origin
+            + Vector2::new(layout.layout.location.x, layout.layout.location.y)
+            - self.offset;
+
+        child_info.cursor_pos = info
+            .cursor_pos
+            .filter(|_| self.hovered && self.drag.is_none() && !self.thumb_hovered)
+            .map(|pos| {
+                pos - Vector2::new(
+                    layout.layout.location.x as f64,
+                    layout.layout.location.y as f64,
+                ) + Vector2::new(self.offset.x as f64, self.offset.y as f64)
+            });
+
+        child_info
+    }
+
+    /// Render the scrollbars of the given viewport with the given content size.
+    ///
+    /// Uses the `color_track`, `color_thumb` and `color_thumb_active` properties of the given style
+    /// and the inherited opacity.
+    pub(crate) fn render(
+        &self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        style: &Style,
+        viewport: Rect,
+        content: Vector2<f32>,
+    ) {
+        let defaults = theme.defaults();
+        let (color_track, color_thumb, color_thumb_active) = (
+            style.resolve(&COLOR_TRACK, &defaults),
+            style.resolve(&COLOR_THUMB, &defaults),
+            style.resolve(&COLOR_THUMB_ACTIVE, &defaults),
+        );
+
+        let context = theme.globals().context();
+        let (color_track, color_thumb, color_thumb_active) = (
+            context.color(color_track),
+            context.color(color_thumb),
+            context.color(color_thumb_active),
+        );
+
+        let bars = Scrollbars::new(
+            viewport,
+            content,
+            self.offset,
+            self.horizontal,
+            self.vertical,
+        );
+        let radii = RoundedRectRadii::from_single_radius(SCROLLBAR_WIDTH as f64 / 2.0);
+        let thumb_color = if self.thumb_hovered || matches!(self.drag, Some(Drag::Thumb { .. })) {
+            color_thumb_active
+        } else {
+            color_thumb
+        };
+
+        for (track, thumb) in [bars.horizontal, bars.vertical].into_iter().flatten() {
+            scene.fill(
+                Fill::NonZero,
+                Affine::default(),
+                &Brush::Solid(color_track),
+                None,
+                &RoundedRect::from_rect(track, radii),
+            );
+
+            scene.fill(
+                Fill::NonZero,
+                Affine::default(),
+                &Brush::Solid(thumb_color),
+                None,
+                &RoundedRect::from_rect(thumb, radii),
+            );
+        }
+    }
+}
+
+/// The state of a [Scroller], that is kept when the scrollable widget gets rebuilt.
+struct ScrollState {
+    offset: Vector2<f32>,
+    velocity: Vector2<f32>,
+    drag: Option<Drag>,
+    hovered: bool,
+    thumb_hovered: bool,
+}
+
+/// An active drag inside a scrollable viewport.
+enum Drag {
+    /// Dragging the thumb of a scrollbar.
+    Thumb {
+        vertical: bool,
+        start: Vector2<f32>,
+        offset: Vector2<f32>,
+    },
+    /// Dragging the content itself.
+    Content { last: Vector2<f32> },
+}
+
+/// The geometry of the viewport and scrollbars.
+struct Scrollbars {
+    /// The visible area of the content.
+    viewport: Rect,
+    /// The maximum scroll offset.
+    max: Vector2<f32>,
+    /// The track and thumb of the horizontal scrollbar, if the content overflows horizontally.
+    horizontal: Option<(Rect, Rect)>,
+    /// The track and thumb of the vertical scrollbar, if the content overflows vertically.
+    vertical: Option<(Rect, Rect)>,
+}
+
+impl Scrollbars {
+    /// Compute the scrollbars of the given viewport with the given content size and the current offset.
+    fn new(
+        viewport: Rect,
+        content: Vector2<f32>,
+        offset: Vector2<f32>,
+        horizontal: bool,
+        vertical: bool,
+    ) -> Self {
+        let pos = Vector2::new(viewport.x0 as f32, viewport.y0 as f32);
+        let size = Vector2::new(viewport.width() as f32, viewport.height() as f32);
+
+        let max = Vector2::new(
+            if horizontal {
+                (content.x - size.x).max(0.0)
+            } else {
+                0.0
+            },
+            if vertical {
+                (content.y - size.y).max(0.0)
+            } else {
+                0.0
+            },
+        );
+
+        let offset = Vector2::new(offset.x.clamp(0.0, max.x), offset.y.clamp(0.0, max.y));
+
+        let horizontal = (max.x > 0.0).then(|| {
+            let start = pos.x + SCROLLBAR_MARGIN;
+            let length = size.x - SCROLLBAR_MARGIN * 2.0;
+            let y = pos.y + size.y - SCROLLBAR_WIDTH - SCROLLBAR_MARGIN;
+            let (thumb_start, thumb_length) = thumb(start, length, size.x, offset.x, max.x);
+
+            (
+                Rect::new(
+                    start as f64,
+                    y as f64,
+                    (start + length) as f64,
+                    (y + SCROLLBAR_WIDTH) as f64,
+                ),
+                Rect::new(
+                    thumb_start as f64,
+                    y as f64,
+                    (thumb_start + thumb_length) as f64,
+                    (y + SCROLLBAR_WIDTH) as f64,
+                ),
+            )
+        });
+
+        let vertical = (max.y > 0.0).then(|| {
+            let start = pos.y + SCROLLBAR_MARGIN;
+            let length = size.y - SCROLLBAR_MARGIN * 2.0;
+            let x = pos.x + size.x - SCROLLBAR_WIDTH - SCROLLBAR_MARGIN;
+            let (thumb_start, thumb_length) = thumb(start, length, size.y, offset.y, max.y);
+
+            (
+                Rect::new(
+                    x as f64,
+                    start as f64,
+                    (x + SCROLLBAR_WIDTH) as f64,
+                    (start + length) as f64,
+                ),
+                Rect::new(
+                    x as f64,
+                    thumb_start as f64,
+                    (x + SCROLLBAR_WIDTH) as f64,
+                    (thumb_start + thumb_length) as f64,
+                ),
+            )
+        });
+
+        Self {
+            viewport,
+            max,
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// Returns if the thumb at the given position is vertical or [None] if there is no thumb.
+    fn thumb_at(&self, pos: Vector2<f32>) -> Option<bool> {
+        if self.vertical.is_some_and(|(_, thumb)| contains(thumb, pos)) {
+            Some(true)
+        } else if self
+            .horizontal
+            .is_some_and(|(_, thumb)| contains(thumb, pos))
+        {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Clamp the given offset to the scrollable range.
+    fn clamp(&self, offset: Vector2<f32>) -> Vector2<f32> {
+        Vector2::new(
+            offset.x.max(0.0).min(self.max.x),
+            offset.y.max(0.0).min(self.max.y),
+        )
+    }
+}
+
+/// Returns the start and length of a scrollbar thumb inside the given track.
+fn thumb(start: f32, length: f32, viewport: f32, offset: f32, max: f32) -> (f32, f32) {
+    let thumb_length = (length * viewport / (viewport + max))
+        .max(MIN_THUMB_LENGTH)
+        .min(length);
+
+    (start + (length - thumb_length) * offset / max, thumb_length)
+}
+
+/// Returns if the given rectangle contains the given position.
+fn contains(rect: Rect, pos: Vector2<f32>) -> bool {
+    rect.contains(Point::new(pos.x as f64, pos.y as f64))
+}
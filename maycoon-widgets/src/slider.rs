@@ -1,30 +1,65 @@
+use maycoon_core::accesskit::{Action, Role};
+use maycoon_core::app::accessibility::{AccessAction, AccessNode, NumericValue};
+use maycoon_core::app::focus::FocusId;
 use maycoon_core::app::info::AppInfo;
 use maycoon_core::app::update::Update;
+use maycoon_core::instance::InstanceStore;
 use maycoon_core::layout;
 use maycoon_core::layout::{Dimension, LayoutNode, LayoutStyle, LengthPercentageAuto, StyleNode};
 use maycoon_core::state::{State, Val};
-use maycoon_core::vg::kurbo::{Affine, Circle, Point, Rect, RoundedRect, RoundedRectRadii};
-use maycoon_core::vg::peniko::{Brush, Fill};
+use maycoon_core::vg::kurbo::{Affine, Circle, Point, Rect, RoundedRect, RoundedRectRadii, Stroke};
+use maycoon_core::vg::peniko::{Brush, Color, Fill};
 use maycoon_core::vg::Scene;
 use maycoon_core::widget::Widget;
-use maycoon_core::window::MouseButton;
+use maycoon_core::window::{ElementState, Key, MouseButton, NamedKey};
 use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
 use maycoon_theme::theme::Theme;
 use nalgebra::Vector2;
 
+/// The color of the slider bar.
+pub const COLOR: StyleKey<Color> =
+    StyleKey::new("color", |defaults| defaults.interactive().inactive());
+
+/// The color of the slider ball.
+pub const COLOR_BALL: StyleKey<Color> =
+    StyleKey::new("color_ball", |defaults| defaults.interactive().active());
+
+/// The style keys of the [Slider].
+pub const STYLE_KEYS: &[KeyInfo] = &[COLOR.info(), COLOR_BALL.info()];
+
 /// A slider widget to control a floating point value between `0.0` and `1.0`.
 ///
+/// The slider can be focused and controlled using the keyboard:
+/// the arrow keys change the value by the step size (see [Slider::with_step])
+/// and `Home`/`End` set the value to the minimum/maximum.
+/// Assistive technologies (e.g. screen readers) can read, set, increment and decrement the value.
+/// Inside a disabled style context (see [Globals](maycoon_theme::globals::Globals)), it uses the disabled color and ignores input.
+///
 /// ### Theming
 /// You can style the slider using following properties:
 /// - `color` - The color of the slider bar.
 /// - `color_ball` - The color of the slider ball.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single slider using [Slider::with_style]
+/// and a style variant of the theme can be chosen using [Slider::with_class].
 pub struct Slider<S: State> {
     layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
     value: Val<S, f32>,
-    on_change: Box<dyn FnMut(&mut S, f32) -> Update>,
+    on_change: OnChange<S>,
     dragging: bool,
+    step: f32,
+    focus: Option<FocusId>,
 }
 
+/// The callback of the slider, that gets called when the value changes.
+type OnChange<S> = Box<dyn FnMut(&mut S, f32) -> Update>;
+
 impl<S: State> Slider<S> {
     /// Create a new Slider widget from a value (should be state bound) and an `on_change` callback.
     pub fn new(
@@ -42,11 +77,35 @@ impl<S: State> Slider<S> {
                 },
                 ..Default::default()
             }),
+            id: WidgetId::new("maycoon-widgets", "Slider"),
+            theme_style: None.into(),
             value: value.into(),
             on_change: Box::new(on_change),
             dragging: false,
+            step: 0.05,
+            focus: None,
         }
     }
+
+    /// Sets the amount the value changes, when using the arrow keys. Defaults to `0.05`.
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets the style of this slider, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this slider (e.g. `accent` for `Slider.accent`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
 }
 
 impl<S: State> Widget<S> for Slider<S> {
@@ -54,24 +113,31 @@ impl<S: State> Widget<S> for Slider<S> {
         &mut self,
         scene: &mut Scene,
         theme: &mut dyn Theme,
-        _: &AppInfo,
+        info: &AppInfo,
         layout_node: &LayoutNode,
         state: &S,
     ) {
         let value = *self.value.get_ref(state);
 
-        let brush = if let Some(style) = theme.of(self.widget_id()) {
-            Brush::Solid(style.get_color("color").unwrap())
-        } else {
-            Brush::Solid(theme.defaults().interactive().inactive())
-        };
+        let context = theme.globals().context().clone();
 
-        let ball_brush = if let Some(style) = theme.of(self.widget_id()) {
-            Brush::Solid(style.get_color("color_ball").unwrap())
+        let (color, color_ball) = if context.disabled {
+            let disabled = theme.defaults().interactive().disabled();
+
+            (disabled, disabled)
         } else {
-            Brush::Solid(theme.defaults().interactive().active())
+            let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+            let defaults = theme.defaults();
+
+            (
+                style.resolve(&COLOR, &defaults),
+                style.resolve(&COLOR_BALL, &defaults),
+            )
         };
 
+        let brush = Brush::Solid(context.color(color));
+        let ball_brush = Brush::Solid(context.color(color_ball));
+
         let circle_radius = layout_node.layout.size.height as f64 / 1.15;
 
         scene.fill(
@@ -90,6 +156,24 @@ impl<S: State> Widget<S> for Slider<S> {
             ),
         );
 
+        if self.focus.is_some_and(|focus| info.focus.is_focused(focus)) {
+            scene.stroke(
+                &Stroke::new(2.0),
+                Affine::default(),
+                &ball_brush,
+                None,
+                &Circle::new(
+                    Point::new(
+                        (layout_node.layout.location.x + layout_node.layout.size.width * value)
+                            as f64,
+                        (layout_node.layout.location.y + layout_node.layout.size.height / 2.0)
+                            as f64,
+                    ),
+                    circle_radius + 3.0,
+                ),
+            );
+        }
+
         scene.fill(
             Fill::NonZero,
             Affine::default(),
@@ -109,15 +193,30 @@ impl<S: State> Widget<S> for Slider<S> {
         StyleNode {
             style: self.layout_style.get_ref(state).clone(),
             children: Vec::new(),
+            key: None,
+            measure: None,
         }
     }
 
     fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
         self.value.invalidate();
         self.layout_style.invalidate();
+        self.theme_style.invalidate();
 
         let mut update = Update::empty();
 
+        // disabled sliders can't be focused or dragged
+        if info.style.context().disabled {
+            self.focus = None;
+            self.dragging = false;
+
+            return update;
+        }
+
+        let focus = info.focus.register();
+
+        self.focus = Some(focus);
+
         if let Some(cursor) = info.cursor_pos {
             if cursor.x as f32 >= layout.layout.location.x
                 && cursor.x as f32 <= layout.layout.location.x + layout.layout.size.width
@@ -125,16 +224,12 @@ impl<S: State> Widget<S> for Slider<S> {
                 && cursor.y as f32 <= layout.layout.location.y + layout.layout.size.height
             {
                 for (_, btn, el_state) in &info.buttons {
-                    match btn {
-                        MouseButton::Left => {
-                            if el_state.is_pressed() {
-                                self.dragging = true;
-                            } else {
-                                self.dragging = false;
-                            }
-                        },
-
-                        _ => (),
+                    if *btn == MouseButton::Left {
+                        self.dragging = el_state.is_pressed();
+
+                        if self.dragging {
+                            info.focus.request_focus(focus);
+                        }
                     }
                 }
 
@@ -150,10 +245,88 @@ impl<S: State> Widget<S> for Slider<S> {
             self.dragging = false;
         }
 
+        // control using the keyboard, if focused
+        if info.focus.is_focused(focus) {
+            let mut value = *self.value.get_ref(state);
+
+            for (_, key) in &info.keys {
+                if key.state != ElementState::Pressed {
+                    continue;
+                }
+
+                let new_value = match key.logical_key {
+                    Key::Named(NamedKey::ArrowLeft) | Key::Named(NamedKey::ArrowDown) => {
+                        value - self.step
+                    },
+                    Key::Named(NamedKey::ArrowRight) | Key::Named(NamedKey::ArrowUp) => {
+                        value + self.step
+                    },
+                    Key::Named(NamedKey::Home) => 0.0,
+                    Key::Named(NamedKey::End) => 1.0,
+                    _ => continue,
+                };
+
+                // the next key press must see the new value
+                value = new_value.clamp(0.0, 1.0);
+
+                update.insert((self.on_change)(state, value));
+                update.insert(Update::DRAW);
+            }
+
+            self.value.invalidate();
+        }
+
+        // control using assistive technologies
+        for action in info.access_actions_for(focus) {
+            let value = *self.value.get_ref(state);
+
+            let new_value = match action {
+                AccessAction::Increment => value + self.step,
+                AccessAction::Decrement => value - self.step,
+                AccessAction::SetValue(value) => value as f32,
+                _ => continue,
+            };
+
+            update.insert((self.on_change)(state, new_value.clamp(0.0, 1.0)));
+            update.insert(Update::DRAW);
+
+            // the next action must see the new value
+            self.value.invalidate();
+        }
+
         update
     }
 
     fn widget_id(&mut self) -> WidgetId {
-        WidgetId::new("maycoon-widgets", "Slider")
+        self.id.clone()
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let mut node = AccessNode::new(Role::Slider)
+            .with_numeric(NumericValue {
+                value: *self.value.get_ref(state) as f64,
+                min: 0.0,
+                max: 1.0,
+                step: self.step as f64,
+            })
+            .with_action(Action::Increment)
+            .with_action(Action::Decrement)
+            .with_action(Action::SetValue);
+
+        if let Some(focus) = self.focus {
+            node = node.with_focus(focus);
+        }
+
+        node
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert(self.dragging);
+    }
+
+    fn restore_state(&mut self, _: &S, store: &mut InstanceStore) {
+        if let Some(dragging) = store.take() {
+            self.dragging = dragging;
+        }
     }
 }
@@ -0,0 +1,116 @@
+use maycoon_core::app::accessibility::AccessNode;
+use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::inspector::InspectNode;
+use maycoon_core::app::update::Update;
+use maycoon_core::instance::InstanceStore;
+use maycoon_core::layout::{LayoutNode, StyleNode};
+use maycoon_core::state::{State, Val};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_theme::globals::StyleOverrides;
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::theme::Theme;
+
+/// Overrides the inherited style of its child widget and all widgets inside it.
+///
+/// The overrides are applied to the style cascade of the theme [Globals] and the [StyleCascade] of the [AppInfo]
+/// (see [StyleCascade::render_with_overrides]), so it can change the text color, font, font size, opacity and disabled state of a whole subtree.
+/// Values, that are not overridden, are inherited from the parent.
+///
+/// The widget is transparent for layout, inspection and assistive technologies, so it doesn't add a node of its own.
+///
+/// ```
+/// use maycoon_core::state::EmptyState;
+/// use maycoon_theme::globals::StyleOverrides;
+/// use maycoon_widgets::button::Button;
+/// use maycoon_widgets::styled::Styled;
+/// use maycoon_widgets::text::Text;
+///
+/// // a faded button, that can't be pressed
+/// let button: Styled<EmptyState, _> = Styled::new(
+///     Button::new(Text::new("Submit".to_string())),
+///     StyleOverrides::new().with_disabled(true).with_opacity(0.5),
+/// );
+/// ```
+///
+/// ### Theming
+/// The widget itself doesn't draw anything but the child widget, so theming is useless.
+///
+/// [Globals]: maycoon_theme::globals::Globals
+/// [StyleCascade]: maycoon_core::app::style::StyleCascade
+pub struct Styled<S: State, W: Widget<S> + 'static> {
+    child: Val<S, W>,
+    overrides: Val<S, StyleOverrides>,
+}
+
+impl<S: State, W: Widget<S> + 'static> Styled<S, W> {
+    /// Create a new styled widget with the given child and style overrides.
+    pub fn new(child: impl Into<Val<S, W>>, overrides: impl Into<Val<S, StyleOverrides>>) -> Self {
+        Self {
+            child: child.into(),
+            overrides: overrides.into(),
+        }
+    }
+
+    /// Set the style overrides of the child.
+    pub fn with_overrides(mut self, overrides: impl Into<Val<S, StyleOverrides>>) -> Self {
+        self.overrides = overrides.into();
+        self
+    }
+}
+
+impl<S: State, W: Widget<S>> Widget<S> for Styled<S, W> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        let overrides = self.overrides.get_ref(state).clone();
+        let child = self.child.get_mut(state);
+
+        info.style
+            .render_with_overrides(theme, &overrides, |theme| {
+                child.render(scene, theme, info, layout_node, state)
+            });
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        self.child.get_mut(state).layout_style(state)
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.overrides.invalidate();
+        self.child.rebuild(state);
+
+        let overrides = self.overrides.get_ref(state).clone();
+
+        info.style.update_with_overrides(&overrides, || {
+            self.child.get_mut(state).update(layout, state, info)
+        })
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Styled")
+    }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        self.child.get_mut(state).inspect(state)
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        self.child.get_mut(state).accessibility(state)
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        if let Some(child) = self.child.value_mut() {
+            child.save_state(store);
+        }
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        self.child.get_mut(state).restore_state(state, store);
+    }
+}
@@ -1,46 +1,93 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
 use maycoon_core::app::info::AppInfo;
 use maycoon_core::app::update::Update;
-use maycoon_core::layout::{Dimension, LayoutNode, LayoutStyle, StyleNode};
+use maycoon_core::layout::{AvailableSpace, LayoutNode, LayoutStyle, MeasureCache, StyleNode};
 use maycoon_core::state::{State, Val};
-use maycoon_core::vg::glyph::Glyph;
-use maycoon_core::vg::peniko::{Brush, Fill};
-use maycoon_core::vg::skrifa::instance::Size;
-use maycoon_core::vg::skrifa::raw::FileRef;
-use maycoon_core::vg::skrifa::setting::VariationSetting;
-use maycoon_core::vg::skrifa::MetadataProvider;
-use maycoon_core::vg::{peniko, Scene};
+use maycoon_core::text::{ShapedText, TextAlign, TextSpan};
+use maycoon_core::vg::peniko::{Brush, Color};
+use maycoon_core::vg::Scene;
 use maycoon_core::widget::Widget;
 use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
 use maycoon_theme::theme::Theme;
 use nalgebra::Vector2;
 
-/// Displays the given text with optional font, size and hinting.
+/// The color of the text.
+pub const COLOR: StyleKey<Color> = StyleKey::new("color", |defaults| defaults.text().foreground());
+
+/// The color to use when the inherited `invert_text_color` of the theme [Globals](maycoon_theme::globals::Globals) is `true`.
+pub const COLOR_INVERT: StyleKey<Color> =
+    StyleKey::new("color_invert", |defaults| defaults.text().background());
+
+/// The style keys of the [Text].
+pub const STYLE_KEYS: &[KeyInfo] = &[COLOR.info(), COLOR_INVERT.info()];
+
+/// Displays the given text with optional font, size, alignment and hinting.
+///
+/// The text is shaped (supporting kerning, ligatures, complex scripts and bidirectional text)
+/// and wrapped to the available width. Use [Text::rich] to style parts of the text differently.
 ///
 /// See the [hello-world](https://github.com/maycoon-ui/maycoon/blob/master/examples/hello-world/src/main.rs) example for how to use it in practice.
 ///
+/// The color, font and font size are inherited from the style context of the theme [Globals], if they are not set explicitly.
+///
 /// ### Theming
 /// You can style the text with the following properties:
 /// - `color` - The color of the text.
-/// - `color_invert` - The color to use when the `invert_color` property is set to `true` in the theme [Globals].
+/// - `color_invert` - The color to use when the inherited `invert_text_color` of the theme [Globals] is `true`.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single text using [Text::with_style]
+/// and a style variant of the theme can be chosen using [Text::with_class].
 ///
 /// [Globals]: maycoon_theme::globals::Globals
 pub struct Text<S: State> {
     style: Val<S, LayoutStyle>,
-    text: Val<S, String>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
+    spans: Val<S, Vec<TextSpan>>,
     font: Val<S, Option<String>>,
-    font_size: Val<S, f32>,
+    font_size: Val<S, Option<f32>>,
+    inherited: (Option<String>, Option<f32>),
     hinting: Val<S, bool>,
+    align: Val<S, TextAlign>,
+    wrap: Val<S, bool>,
+    cache: Rc<RefCell<TextCache>>,
+    measure: MeasureCache<LayoutKey>,
 }
 
+/// The values, which affect the size of the text: the spans, font, font size and wrapping.
+type LayoutKey = (Vec<TextSpan>, Option<String>, f32, bool);
+
 impl<S: State> Text<S> {
     /// Create a new text widget with the given text.
     pub fn new(text: impl Into<Val<S, String>>) -> Self {
+        Self::rich(text.into().map(|text| vec![TextSpan::new(text)]))
+    }
+
+    /// Create a new text widget with the given text spans.
+    ///
+    /// Each span can have its own font, font size and color.
+    pub fn rich(spans: impl Into<Val<S, Vec<TextSpan>>>) -> Self {
         Self {
             style: LayoutStyle::default().into(),
-            text: text.into(),
+            id: WidgetId::new("maycoon-widgets", "Text"),
+            theme_style: None.into(),
+            spans: spans.into(),
             font: None.into(),
-            font_size: 30.0.into(),
+            font_size: None.into(),
+            inherited: (None, None),
             hinting: true.into(),
+            align: TextAlign::Start.into(),
+            wrap: true.into(),
+            cache: Rc::new(RefCell::new(TextCache::default())),
+            measure: MeasureCache::default(),
         }
     }
 
@@ -59,9 +106,50 @@ impl<S: State> Text<S> {
         self
     }
 
-    /// Set the font size of the text.
+    /// Set the font size of the text. Defaults to the inherited font size or `30.0`.
     pub fn with_font_size(mut self, size: impl Into<Val<S, f32>>) -> Self {
-        self.font_size = size.into();
+        self.font_size = size.into().map(Some);
+        self
+    }
+
+    /// Returns the font and font size, falling back to the inherited ones.
+    fn font(&mut self, state: &S) -> (Option<String>, f32) {
+        let font = self
+            .font
+            .get_ref(state)
+            .clone()
+            .or_else(|| self.inherited.0.clone());
+
+        let font_size = self
+            .font_size
+            .get_ref(state)
+            .or(self.inherited.1)
+            .unwrap_or(30.0);
+
+        (font, font_size)
+    }
+
+    /// Returns the values, which affect the size of the text.
+    fn layout_key(&mut self, state: &S) -> LayoutKey {
+        let (font, font_size) = self.font(state);
+
+        (
+            self.spans.get_ref(state).clone(),
+            font,
+            font_size,
+            *self.wrap.get_ref(state),
+        )
+    }
+
+    /// Set the horizontal alignment of the text lines.
+    pub fn with_align(mut self, align: impl Into<Val<S, TextAlign>>) -> Self {
+        self.align = align.into();
+        self
+    }
+
+    /// Set if the text should wrap to the available width. Enabled by default.
+    pub fn with_wrap(mut self, wrap: impl Into<Val<S, bool>>) -> Self {
+        self.wrap = wrap.into();
         self
     }
 
@@ -70,6 +158,20 @@ impl<S: State> Text<S> {
         self.style = style.into();
         self
     }
+
+    /// Sets the style of this text, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this text (e.g. `heading` for `Text.heading`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
 }
 
 impl<S: State> Widget<S> for Text<S> {
@@ -81,109 +183,180 @@ impl<S: State> Widget<S> for Text<S> {
         layout_node: &LayoutNode,
         state: &S,
     ) {
-        let font_size = *self.font_size.get_ref(state);
         let hinting = *self.hinting.get_ref(state);
-        let font_name = self.font.get_ref(state);
+        let align = *self.align.get_ref(state);
+        let wrap = *self.wrap.get_ref(state);
 
-        let font = if font_name.is_some() {
-            info.font_context
-                .get(font_name.clone().unwrap())
-                .expect("Font not found")
+        let context = theme.globals().context().clone();
+
+        let key = if context.invert_text_color {
+            &COLOR_INVERT
         } else {
-            info.font_context.default_font().clone()
+            &COLOR
         };
 
-        let font_ref = {
-            let file_ref = FileRef::new(font.data.as_ref()).expect("Failed to load font data");
-            match file_ref {
-                FileRef::Font(font) => Some(font),
-                FileRef::Collection(collection) => collection.get(font.index).ok(),
-            }
-        }
-        .expect("Failed to load font reference");
-
-        let color = if let Some(style) = theme.of(<Text<S> as Widget<S>>::widget_id(self)) {
-            if theme.globals().invert_text_color {
-                style.get_color("color_invert").unwrap()
-            } else {
-                style.get_color("color").unwrap()
-            }
+        // the style of this instance takes precedence over the inherited text color
+        let color = if context.disabled {
+            theme.defaults().interactive().disabled()
+        } else if let Some(color) = self
+            .theme_style
+            .get_ref(state)
+            .as_ref()
+            .and_then(|style| style.get_key(key))
+        {
+            color
+        } else if let Some(color) = context.text_color {
+            color
         } else {
-            theme.defaults().text().foreground()
+            theme
+                .style_of(self.id.clone(), None)
+                .resolve(key, &theme.defaults())
         };
 
-        let location = font_ref.axes().location::<&[VariationSetting; 0]>(&[]);
+        let (font, font_size) = self.font(state);
+        let spans = self.spans.get_ref(state).clone();
+        let shaped = self
+            .cache
+            .borrow_mut()
+            .shape(&spans, &font, font_size, info);
 
-        let metrics = font_ref.metrics(Size::new(font_size), &location);
+        let layout = &layout_node.layout;
+        let pos = Vector2::new(
+            layout.location.x + layout.padding.left + layout.border.left,
+            layout.location.y + layout.padding.top + layout.border.top,
+        );
+        let width = layout.size.width
+            - layout.padding.left
+            - layout.padding.right
+            - layout.border.left
+            - layout.border.right;
 
-        let glyph_metrics = font_ref.glyph_metrics(Size::new(font_size), &location);
+        // the layout size is rounded, so allow a bit of tolerance to not wrap the measured text again
+        let mut text_layout = shaped.layout(wrap.then_some(width + 1.0));
 
-        let line_height = metrics.ascent + metrics.descent + metrics.leading;
+        text_layout.align(align, width);
 
-        let charmap = font_ref.charmap();
-
-        let mut pen_x = layout_node.layout.location.x;
-
-        let mut pen_y = layout_node.layout.location.y + font_size;
-
-        let text = self.text.get_ref(state);
-
-        scene
-            .draw_glyphs(&font)
-            .font_size(font_size)
-            .brush(&Brush::Solid(color))
-            .normalized_coords(location.coords())
-            .hint(hinting)
-            .draw(
-                &peniko::Style::Fill(Fill::NonZero),
-                text.chars().filter_map(|c| {
-                    if c == '\n' {
-                        pen_y += line_height;
-                        pen_x = layout_node.layout.location.x;
-                        return None;
-                    }
-                    let gid = charmap.map(c).unwrap_or_default();
-                    let advance = glyph_metrics.advance_width(gid).unwrap_or_default();
-                    let x = pen_x;
-                    pen_x += advance;
-                    Some(Glyph {
-                        id: gid.to_u16() as u32,
-                        x,
-                        y: pen_y,
-                    })
-                }),
-            );
+        text_layout.draw(scene, pos, hinting, |span| {
+            Brush::Solid(context.color(spans[span].color.unwrap_or(color)))
+        });
     }
 
     fn layout_style(&mut self, state: &S) -> StyleNode {
-        let text = self.text.get_ref(state);
-
-        let font_size = *self.font_size.get_ref(state);
-
-        let style = self.style.get_ref(state).clone();
+        let key = self.layout_key(state);
+        let cache = self.cache.clone();
 
         StyleNode {
-            style: LayoutStyle {
-                size: Vector2::new(
-                    Dimension::Length(font_size * text.len() as f32),
-                    Dimension::Length(font_size),
-                ),
-                ..style
-            },
+            style: self.style.get_ref(state).clone(),
             children: Vec::new(),
+            key: None,
+            measure: Some(self.measure.get(key, |(spans, font, font_size, wrap)| {
+                let (spans, font, font_size, wrap) =
+                    (spans.clone(), font.clone(), *font_size, *wrap);
+
+                Rc::new(move |known, available, info| {
+                    let shaped = cache.borrow_mut().shape(&spans, &font, font_size, info);
+
+                    let max_width = known.x.or(match available.x {
+                        AvailableSpace::Definite(width) if wrap => Some(width),
+                        AvailableSpace::MinContent if wrap => Some(0.0),
+                        _ => None,
+                    });
+
+                    let size = shaped.layout(max_width).size();
+
+                    Vector2::new(known.x.unwrap_or(size.x), known.y.unwrap_or(size.y))
+                })
+            })),
         }
     }
 
-    fn update(&mut self, _: &LayoutNode, _: &mut S, _: &AppInfo) -> Update {
-        self.text.invalidate();
+    fn update(&mut self, _: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.spans.invalidate();
         self.font.invalidate();
         self.hinting.invalidate();
         self.font_size.invalidate();
+        self.align.invalidate();
+        self.wrap.invalidate();
         self.style.invalidate();
-        Update::empty()
+        self.theme_style.invalidate();
+
+        let context = info.style.context();
+
+        self.inherited = (context.font, context.font_size);
+
+        // the size of the text changes with its content, font and wrapping
+        let key = self.layout_key(state);
+
+        if self.measure.key() != Some(&key) {
+            Update::LAYOUT
+        } else {
+            Update::empty()
+        }
     }
 
     fn widget_id(&mut self) -> WidgetId {
-        WidgetId::new("maycoon-widgets", "Text")
+        self.id.clone()
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let text = self
+            .spans
+            .get_ref(state)
+            .iter()
+            .map(|span| span.text.as_str())
+            .collect::<String>();
+
+        AccessNode::new(Role::Label).with_value(text)
+    }
+}
+
+/// Caches the shaped text, so it only gets shaped again if the text or fonts change.
+#[derive(Default)]
+struct TextCache {
+    key: Option<(Vec<TextSpan>, Option<String>, f32, u64)>,
+    shaped: Option<Rc<ShapedText>>,
+}
+
+impl TextCache {
+    /// Returns the shaped text of the given spans and font, shaping it if necessary.
+    fn shape(
+        &mut self,
+        spans: &[TextSpan],
+        font: &Option<String>,
+        font_size: f32,
+        info: &AppInfo,
+    ) -> Rc<ShapedText> {
+        let generation = info.font_context.generation();
+
+        let valid =
+            self.key
+                .as_ref()
+                .is_some_and(|(key_spans, key_font, key_size, key_generation)| {
+                    key_spans == spans
+                        && key_font == font
+                        && *key_size == font_size
+                        && *key_generation == generation
+                });
+
+        if let (true, Some(shaped)) = (valid, &self.shaped) {
+            return shaped.clone();
+        }
+
+        let default_font = match font {
+            Some(name) => info.font_context.get_or_default(name),
+            None => info.font_context.default_font().clone(),
+        };
+
+        let shaped = Rc::new(ShapedText::new(
+            spans,
+            &info.font_context,
+            &default_font,
+            font_size,
+        ));
+
+        self.key = Some((spans.to_vec(), font.clone(), font_size, generation));
+        self.shaped = Some(shaped.clone());
+
+        shaped
     }
 }
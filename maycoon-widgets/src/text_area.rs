@@ -0,0 +1,136 @@
+use maycoon_core::app::accessibility::AccessNode;
+use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::update::Update;
+use maycoon_core::instance::InstanceStore;
+use maycoon_core::layout::{LayoutNode, LayoutStyle, StyleNode};
+use maycoon_core::state::{State, Val};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::style::Style;
+use maycoon_theme::theme::Theme;
+
+use crate::text_input::TextInput;
+
+/// A multi-line text input field. Use [TextInput] for single-line text.
+///
+/// Works like a [TextInput], but `Enter` inserts a new line
+/// and the `ArrowUp`/`ArrowDown` keys move the cursor between lines.
+///
+/// ### Theming
+/// You can style the text area with the following properties:
+/// - `color_background` - The background color of the text area.
+/// - `color_border` - The border color of the text area, when it's not focused.
+/// - `color_border_focused` - The border color of the text area, when it's focused.
+/// - `color_text` - The color of the text.
+/// - `color_caret` - The color of the text cursor (caret).
+/// - `color_selection` - The background color of selected text.
+///
+/// The properties are the same as of the [TextInput] and declared in [text_input::STYLE_KEYS](crate::text_input::STYLE_KEYS).
+///
+/// The style can be overridden for a single text area using [TextArea::with_style]
+/// and a style variant of the theme can be chosen using [TextArea::with_class].
+pub struct TextArea<S: State> {
+    input: TextInput<S>,
+}
+
+impl<S: State> TextArea<S> {
+    /// Create a new text area with the given text.
+    ///
+    /// The text should be state dependent, so you can mutate it on change.
+    pub fn new(text: impl Into<Val<S, String>>) -> Self {
+        Self {
+            input: TextInput::new_multiline(text),
+        }
+    }
+
+    /// Sets the function to be called when the text is changed by the user.
+    ///
+    /// You should update the bound text with the new text using the provided state.
+    pub fn with_on_change(
+        mut self,
+        on_change: impl FnMut(&mut S, String) -> Update + 'static,
+    ) -> Self {
+        self.input = self.input.with_on_change(on_change);
+        self
+    }
+
+    /// Sets the layout style of the text area.
+    pub fn with_layout_style(mut self, layout_style: impl Into<Val<S, LayoutStyle>>) -> Self {
+        self.input = self.input.with_layout_style(layout_style);
+        self
+    }
+
+    /// Sets the font of the text.
+    pub fn with_font(mut self, font: impl Into<Val<S, String>>) -> Self {
+        self.input = self.input.with_font(font);
+        self
+    }
+
+    /// Sets the font size of the text.
+    pub fn with_font_size(mut self, size: impl Into<Val<S, f32>>) -> Self {
+        self.input = self.input.with_font_size(size);
+        self
+    }
+
+    /// Sets the style of this text area, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.input = self.input.with_style(style);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this text area (e.g. `code` for `TextArea.code`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.input = self.input.with_class(class);
+        self
+    }
+
+    /// Returns the selected byte range of the text. The range is empty, if nothing is selected.
+    pub fn selection(&self) -> std::ops::Range<usize> {
+        self.input.selection()
+    }
+
+    /// Returns the byte position of the text cursor.
+    pub fn cursor(&self) -> usize {
+        self.input.cursor()
+    }
+}
+
+impl<S: State> Widget<S> for TextArea<S> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        self.input.render(scene, theme, info, layout_node, state)
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        self.input.layout_style(state)
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.input.update(layout, state, info)
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        self.input.widget_id()
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        self.input.accessibility(state)
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        self.input.save_state(store)
+    }
+
+    fn restore_state(&mut self, state: &S, store: &mut InstanceStore) {
+        self.input.restore_state(state, store)
+    }
+}
@@ -0,0 +1,883 @@
+use std::ops::Range;
+use std::rc::Rc;
+
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
+use maycoon_core::app::focus::FocusId;
+use maycoon_core::app::info::{AppInfo, KeyInput};
+use maycoon_core::app::update::Update;
+use maycoon_core::instance::InstanceStore;
+use maycoon_core::layout;
+use maycoon_core::layout::{
+    Dimension, LayoutNode, LayoutStyle, LengthPercentageAuto, MeasureCache, StyleNode,
+};
+use maycoon_core::state::{State, Val};
+use maycoon_core::text::{ShapedText, TextLayout, TextSpan};
+use maycoon_core::vg::kurbo::{
+    Affine, Line, Point, Rect, RoundedRect, RoundedRectRadii, Stroke, Vec2,
+};
+use maycoon_core::vg::peniko::{Brush, Color, Fill, Font, Mix};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_core::window::{ElementState, Ime, Key, MouseButton, NamedKey};
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::key::{KeyInfo, StyleKey};
+use maycoon_theme::style::Style;
+use maycoon_theme::theme::Theme;
+use nalgebra::Vector2;
+
+/// The background color of the input field.
+pub const COLOR_BACKGROUND: StyleKey<Color> =
+    StyleKey::new("color_background", |defaults| defaults.text().background());
+
+/// The border color of the input field, when it's not focused.
+pub const COLOR_BORDER: StyleKey<Color> =
+    StyleKey::new("color_border", |defaults| defaults.interactive().inactive());
+
+/// The border color of the input field, when it's focused.
+pub const COLOR_BORDER_FOCUSED: StyleKey<Color> =
+    StyleKey::new("color_border_focused", |defaults| {
+        defaults.interactive().active()
+    });
+
+/// The color of the text.
+pub const COLOR_TEXT: StyleKey<Color> =
+    StyleKey::new("color_text", |defaults| defaults.text().foreground());
+
+/// The color of the text cursor (caret).
+pub const COLOR_CARET: StyleKey<Color> =
+    StyleKey::new("color_caret", |defaults| defaults.text().foreground());
+
+/// The background color of selected text.
+pub const COLOR_SELECTION: StyleKey<Color> =
+    StyleKey::new("color_selection", |defaults| defaults.interactive().hover());
+
+/// The style keys of the [TextInput] and [TextArea](crate::text_area::TextArea).
+pub const STYLE_KEYS: &[KeyInfo] = &[
+    COLOR_BACKGROUND.info(),
+    COLOR_BORDER.info(),
+    COLOR_BORDER_FOCUSED.info(),
+    COLOR_TEXT.info(),
+    COLOR_CARET.info(),
+    COLOR_SELECTION.info(),
+];
+
+/// The space between the border and the text.
+const PADDING: f32 = 6.0;
+
+/// A single-line text input field. Use [TextArea](crate::text_area::TextArea) for multi-line text.
+///
+/// The text should be state dependent and is changed using the [TextInput::with_on_change] callback.
+///
+/// The text is shaped like the text of [Text](crate::text::Text), so ligatures, combining marks and right-to-left text are supported.
+/// The cursor moves between the glyph clusters of the shaped text.
+///
+/// Supports selecting text with the mouse and `Shift` + navigation keys,
+/// navigating by characters (arrow keys), words (`Ctrl` + arrow keys) and lines (`Home`/`End`),
+/// deleting text using `Backspace`/`Delete` and input methods (IME).
+/// Inside a disabled style context (see [Globals](maycoon_theme::globals::Globals)), it uses the disabled color and can't be edited.
+///
+/// ### Theming
+/// You can style the text input with the following properties:
+/// - `color_background` - The background color of the input field.
+/// - `color_border` - The border color of the input field, when it's not focused.
+/// - `color_border_focused` - The border color of the input field, when it's focused.
+/// - `color_text` - The color of the text.
+/// - `color_caret` - The color of the text cursor (caret).
+/// - `color_selection` - The background color of selected text.
+///
+/// The properties are declared as typed keys in [STYLE_KEYS] and fall back to the [DefaultStyles](maycoon_theme::style::DefaultStyles) of the theme, if missing.
+///
+/// The style can be overridden for a single text input using [TextInput::with_style]
+/// and a style variant of the theme can be chosen using [TextInput::with_class].
+pub struct TextInput<S: State> {
+    layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
+    text: Val<S, String>,
+    font: Val<S, Option<String>>,
+    font_size: Val<S, f32>,
+    on_change: OnChange<S>,
+    on_submit: Box<dyn FnMut(&mut S) -> Update>,
+    multiline: bool,
+    focus: Option<FocusId>,
+    cursor: usize,
+    anchor: usize,
+    selecting: bool,
+    preedit: Option<(String, Option<(usize, usize)>)>,
+    scroll: Vector2<f32>,
+    measure: MeasureCache<(String, Option<String>, f32)>,
+}
+
+/// The callback of the text input, that gets called when the text changes.
+type OnChange<S> = Box<dyn FnMut(&mut S, String) -> Update>;
+
+impl<S: State> TextInput<S> {
+    /// Create a new text input with the given text.
+    ///
+    /// The text should be state dependent, so you can mutate it on change.
+    pub fn new(text: impl Into<Val<S, String>>) -> Self {
+        Self {
+            layout_style: LayoutStyle {
+                size: Vector2::new(Dimension::Length(200.0), Dimension::Auto),
+                margin: layout::Rect::<LengthPercentageAuto> {
+                    left: LengthPercentageAuto::Length(4.0),
+                    right: LengthPercentageAuto::Length(4.0),
+                    top: LengthPercentageAuto::Length(4.0),
+                    bottom: LengthPercentageAuto::Length(4.0),
+                },
+                ..Default::default()
+            }
+            .into(),
+            text: text.into(),
+            id: WidgetId::new("maycoon-widgets", "TextInput"),
+            theme_style: None.into(),
+            font: None.into(),
+            font_size: 20.0.into(),
+            on_change: Box::new(|_, _| Update::empty()),
+            on_submit: Box::new(|_| Update::empty()),
+            multiline: false,
+            focus: None,
+            cursor: 0,
+            anchor: 0,
+            selecting: false,
+            preedit: None,
+            scroll: Vector2::new(0.0, 0.0),
+            measure: MeasureCache::default(),
+        }
+    }
+
+    /// Create a new multi-line text input. Used by [TextArea](crate::text_area::TextArea).
+    pub(crate) fn new_multiline(text: impl Into<Val<S, String>>) -> Self {
+        let mut input = Self::new(text);
+
+        input.multiline = true;
+        input.id = WidgetId::new("maycoon-widgets", "TextArea");
+        input.layout_style = LayoutStyle {
+            size: Vector2::new(Dimension::Length(300.0), Dimension::Length(150.0)),
+            margin: layout::Rect::<LengthPercentageAuto> {
+                left: LengthPercentageAuto::Length(4.0),
+                right: LengthPercentageAuto::Length(4.0),
+                top: LengthPercentageAuto::Length(4.0),
+                bottom: LengthPercentageAuto::Length(4.0),
+            },
+            ..Default::default()
+        }
+        .into();
+
+        input
+    }
+
+    /// Sets the function to be called when the text is changed by the user.
+    ///
+    /// You should update the bound text with the new text using the provided state.
+    pub fn with_on_change(
+        mut self,
+        on_change: impl FnMut(&mut S, String) -> Update + 'static,
+    ) -> Self {
+        self.on_change = Box::new(on_change);
+        self
+    }
+
+    /// Sets the function to be called when `Enter` is pressed.
+    ///
+    /// Only called for single-line inputs, since multi-line inputs insert a new line instead.
+    pub fn with_on_submit(mut self, on_submit: impl FnMut(&mut S) -> Update + 'static) -> Self {
+        self.on_submit = Box::new(on_submit);
+        self
+    }
+
+    /// Sets the layout style of the text input.
+    pub fn with_layout_style(mut self, layout_style: impl Into<Val<S, LayoutStyle>>) -> Self {
+        self.layout_style = layout_style.into();
+        self
+    }
+
+    /// Sets the font of the text.
+    pub fn with_font(mut self, font: impl Into<Val<S, String>>) -> Self {
+        self.font = font.into().map(Some);
+        self
+    }
+
+    /// Sets the font size of the text.
+    pub fn with_font_size(mut self, size: impl Into<Val<S, f32>>) -> Self {
+        self.font_size = size.into();
+        self
+    }
+
+    /// Sets the style of this text input, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this text input (e.g. `danger` for `TextInput.danger`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
+
+    /// Returns the selected byte range of the text. The range is empty, if nothing is selected.
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.anchor)..self.cursor.max(self.anchor)
+    }
+
+    /// Returns the byte position of the text cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the font and font size of the text input.
+    fn font(&mut self, info: &AppInfo, state: &S) -> (Font, f32) {
+        let font = match self.font.get_ref(state) {
+            Some(name) => info.font_context.get_or_default(name),
+            None => info.font_context.default_font().clone(),
+        };
+
+        (font, *self.font_size.get_ref(state))
+    }
+
+    /// Moves the cursor to the given position. Extends the selection, if `select` is true.
+    fn move_to(&mut self, pos: usize, select: bool) {
+        self.cursor = pos;
+
+        if !select {
+            self.anchor = pos;
+        }
+    }
+
+    /// Replace the selected text with the given text and move the cursor behind it.
+    fn insert(&mut self, text: &mut String, insert: &str) {
+        let selection = self.selection();
+
+        text.replace_range(selection.clone(), insert);
+
+        self.move_to(selection.start + insert.len(), false);
+    }
+
+    /// Delete the selected text or the text between the cursor and the given position.
+    fn delete_to(&mut self, text: &mut String, pos: usize) {
+        let range = if self.cursor != self.anchor {
+            self.selection()
+        } else {
+            self.cursor.min(pos)..self.cursor.max(pos)
+        };
+
+        text.replace_range(range.clone(), "");
+
+        self.move_to(range.start, false);
+    }
+
+    /// Returns the given text with the preedit text of the input method inserted at the cursor,
+    /// together with the byte position of the caret and the byte range of the preedit text.
+    fn display_text(&self, text: &str) -> (String, usize, Option<Range<usize>>) {
+        let mut text = text.to_string();
+        let pos = clamp_to_char(&text, self.cursor);
+        let mut cursor = pos;
+
+        let preedit = self.preedit.as_ref().map(|(preedit, preedit_cursor)| {
+            text.insert_str(pos, preedit);
+
+            if let Some((_, end)) = preedit_cursor {
+                cursor = pos + end;
+            }
+
+            pos..pos + preedit.len()
+        });
+
+        (text, cursor, preedit)
+    }
+
+    /// Scroll the text, so the given caret area is visible inside the given layout.
+    ///
+    /// Returns if the scroll offset changed.
+    fn scroll_to(&mut self, caret: Rect, layout: &LayoutNode) -> bool {
+        let inner = Vector2::new(
+            (layout.layout.size.width - PADDING * 2.0).max(0.0),
+            (layout.layout.size.height - PADDING * 2.0).max(0.0),
+        );
+
+        let old = self.scroll;
+
+        self.scroll.x = self
+            .scroll
+            .x
+            .max(caret.x1 as f32 - inner.x)
+            .min(caret.x0 as f32);
+
+        self.scroll.y = self
+            .scroll
+            .y
+            .max(caret.y1 as f32 - inner.y)
+            .min(caret.y0 as f32);
+
+        old != self.scroll
+    }
+
+    /// Returns the position of the text origin inside the given layout.
+    fn origin(&self, layout: &LayoutNode) -> Vector2<f32> {
+        Vector2::new(
+            layout.layout.location.x + PADDING - self.scroll.x,
+            layout.layout.location.y + PADDING - self.scroll.y,
+        )
+    }
+
+    /// Handle a pressed key. Returns if the text has changed.
+    fn key_pressed(
+        &mut self,
+        text: &mut String,
+        key: &KeyInput,
+        info: &AppInfo,
+        font: &Font,
+        font_size: f32,
+        submit: &mut bool,
+    ) -> bool {
+        let shift = info.modifiers.shift_key();
+        let ctrl = info.modifiers.control_key() || info.modifiers.super_key();
+
+        match &key.logical_key {
+            Key::Named(NamedKey::ArrowLeft) => {
+                let pos = if !shift && self.cursor != self.anchor {
+                    self.selection().start
+                } else if ctrl {
+                    prev_word(text, self.cursor)
+                } else {
+                    layout_text(text, font, font_size, info).prev_caret(self.cursor)
+                };
+
+                self.move_to(pos, shift);
+            },
+
+            Key::Named(NamedKey::ArrowRight) => {
+                let pos = if !shift && self.cursor != self.anchor {
+                    self.selection().end
+                } else if ctrl {
+                    next_word(text, self.cursor)
+                } else {
+                    layout_text(text, font, font_size, info).next_caret(self.cursor)
+                };
+
+                self.move_to(pos, shift);
+            },
+
+            Key::Named(NamedKey::ArrowUp) | Key::Named(NamedKey::ArrowDown) if self.multiline => {
+                // the text may have been edited by previous keys, so lay it out again
+                let layout = layout_text(text, font, font_size, info);
+                let line = layout.line_at(self.cursor);
+                let x = layout.lines()[line].caret_x(self.cursor);
+
+                let line = if key.logical_key == Key::Named(NamedKey::ArrowUp) {
+                    line.checked_sub(1)
+                } else {
+                    Some(line + 1).filter(|line| *line < layout.lines().len())
+                };
+
+                let pos = match line {
+                    Some(line) => layout.lines()[line].hit(x),
+                    None if key.logical_key == Key::Named(NamedKey::ArrowUp) => 0,
+                    None => text.len(),
+                };
+
+                self.move_to(pos, shift);
+            },
+
+            Key::Named(NamedKey::Home) => {
+                let pos = if ctrl {
+                    0
+                } else {
+                    text[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+                };
+
+                self.move_to(pos, shift);
+            },
+
+            Key::Named(NamedKey::End) => {
+                let pos = if ctrl {
+                    text.len()
+                } else {
+                    text[self.cursor..]
+                        .find('\n')
+                        .map_or(text.len(), |i| self.cursor + i)
+                };
+
+                self.move_to(pos, shift);
+            },
+
+            Key::Named(NamedKey::Backspace) => {
+                let pos = if ctrl {
+                    prev_word(text, self.cursor)
+                } else {
+                    layout_text(text, font, font_size, info).prev_caret(self.cursor)
+                };
+
+                self.delete_to(text, pos);
+                return true;
+            },
+
+            Key::Named(NamedKey::Delete) => {
+                let pos = if ctrl {
+                    next_word(text, self.cursor)
+                } else {
+                    layout_text(text, font, font_size, info).next_caret(self.cursor)
+                };
+
+                self.delete_to(text, pos);
+                return true;
+            },
+
+            Key::Named(NamedKey::Enter) => {
+                if self.multiline {
+                    self.insert(text, "\n");
+                    return true;
+                } else {
+                    *submit = true;
+                }
+            },
+
+            Key::Character(c) if ctrl && c.eq_ignore_ascii_case("a") => {
+                self.anchor = 0;
+                self.cursor = text.len();
+            },
+
+            _ => {
+                if let Some(key_text) = &key.text {
+                    if !ctrl && !key_text.chars().any(char::is_control) {
+                        self.insert(text, key_text);
+                        return true;
+                    }
+                }
+            },
+        }
+
+        false
+    }
+}
+
+impl<S: State> Widget<S> for TextInput<S> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        let (font, font_size) = self.font(info, state);
+        let focused = self.focus.is_some_and(|focus| info.focus.is_focused(focus));
+
+        let context = theme.globals().context().clone();
+
+        let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+        let defaults = theme.defaults();
+        let color = |key: &StyleKey<Color>| {
+            // the background keeps its color, so the input stays readable
+            if context.disabled && key.name() != COLOR_BACKGROUND.name() {
+                context.color(defaults.interactive().disabled())
+            } else {
+                context.color(style.resolve(key, &defaults))
+            }
+        };
+
+        let color_background = color(&COLOR_BACKGROUND);
+        let color_border = if focused {
+            color(&COLOR_BORDER_FOCUSED)
+        } else {
+            color(&COLOR_BORDER)
+        };
+        let color_text = color(&COLOR_TEXT);
+        let color_caret = color(&COLOR_CARET);
+        let color_selection = color(&COLOR_SELECTION);
+
+        let bounds = Rect::new(
+            layout_node.layout.location.x as f64,
+            layout_node.layout.location.y as f64,
+            (layout_node.layout.location.x + layout_node.layout.size.width) as f64,
+            (layout_node.layout.location.y + layout_node.layout.size.height) as f64,
+        );
+
+        let shape = RoundedRect::from_rect(bounds, RoundedRectRadii::from_single_radius(5.0));
+
+        scene.fill(
+            Fill::NonZero,
+            Affine::default(),
+            &Brush::Solid(color_background),
+            None,
+            &shape,
+        );
+
+        // display the preedit text of the input method at the cursor
+        let text = self.text.get_ref(state).clone();
+        let (text, cursor, preedit) = self.display_text(&text);
+        let text_layout = layout_text(&text, &font, font_size, info);
+
+        // the text is scrolled during the update, so the caret is visible
+        let caret = caret_area(&text_layout, cursor);
+        let origin = self.origin(layout_node);
+
+        scene.push_layer(Mix::Clip, 1.0, Affine::default(), &shape);
+
+        // selection
+        if preedit.is_none() && self.cursor != self.anchor {
+            let selection = self.selection();
+
+            for line in text_layout.lines() {
+                let range = line.range();
+                let mut pieces = line.selection(selection.clone());
+
+                // show a selected line break
+                if text[range.clone()].ends_with('\n') && selection.contains(&(range.end - 1)) {
+                    let x = line.caret_x(range.end - 1);
+
+                    pieces.push((x, x + 4.0));
+                }
+
+                let y = origin.y + line.top();
+
+                for (left, right) in pieces {
+                    scene.fill(
+                        Fill::NonZero,
+                        Affine::default(),
+                        &Brush::Solid(color_selection),
+                        None,
+                        &Rect::new(
+                            (origin.x + left) as f64,
+                            y as f64,
+                            (origin.x + right) as f64,
+                            (y + line.height()) as f64,
+                        ),
+                    );
+                }
+            }
+        }
+
+        text_layout.draw(scene, origin, true, |_| Brush::Solid(color_text));
+
+        // underline the preedit text
+        if let Some(preedit) = preedit {
+            let line = &text_layout.lines()[text_layout.line_at(preedit.start)];
+            let start_x = line.caret_x(preedit.start);
+            let end_x = line.caret_x(preedit.end);
+            let y = origin.y + line.top() + line.height() - 2.0;
+
+            scene.stroke(
+                &Stroke::new(1.0),
+                Affine::default(),
+                &Brush::Solid(color_text),
+                None,
+                &Line::new(
+                    Point::new((origin.x + start_x) as f64, y as f64),
+                    Point::new((origin.x + end_x) as f64, y as f64),
+                ),
+            );
+        }
+
+        // caret
+        if focused {
+            scene.stroke(
+                &Stroke::new(1.5),
+                Affine::default(),
+                &Brush::Solid(color_caret),
+                None,
+                &Line::new(
+                    Point::new(caret.x0 + origin.x as f64, caret.y0 + origin.y as f64),
+                    Point::new(caret.x0 + origin.x as f64, caret.y1 + origin.y as f64),
+                ),
+            );
+        }
+
+        scene.pop_layer();
+
+        scene.stroke(
+            &Stroke::new(if focused { 2.0 } else { 1.0 }),
+            Affine::default(),
+            &Brush::Solid(color_border),
+            None,
+            &shape,
+        );
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        let key = (
+            self.text.get_ref(state).clone(),
+            self.font.get_ref(state).clone(),
+            *self.font_size.get_ref(state),
+        );
+
+        StyleNode {
+            style: self.layout_style.get_ref(state).clone(),
+            children: Vec::new(),
+            // fit the text by default
+            key: None,
+            measure: Some(self.measure.get(key, |(text, font, font_size)| {
+                let (text, font, font_size) = (text.clone(), font.clone(), *font_size);
+
+                Rc::new(move |known, _, info| {
+                    let font = match &font {
+                        Some(name) => info.font_context.get_or_default(name),
+                        None => info.font_context.default_font().clone(),
+                    };
+
+                    let size = layout_text(&text, &font, font_size, info).size();
+
+                    Vector2::new(
+                        known.x.unwrap_or(size.x + PADDING * 2.0),
+                        known.y.unwrap_or(size.y + PADDING * 2.0),
+                    )
+                })
+            })),
+        }
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.layout_style.invalidate();
+        self.theme_style.invalidate();
+        self.text.invalidate();
+        self.font.invalidate();
+        self.font_size.invalidate();
+
+        let mut update = Update::empty();
+
+        // disabled inputs can't be focused or edited
+        if info.style.context().disabled {
+            self.focus = None;
+            self.selecting = false;
+            self.preedit = None;
+
+            return update;
+        }
+
+        let focus = info.focus.register();
+
+        self.focus = Some(focus);
+
+        let mut text = self.text.get_ref(state).clone();
+
+        // the text may have been changed from outside
+        self.cursor = clamp_to_char(&text, self.cursor);
+        self.anchor = clamp_to_char(&text, self.anchor);
+
+        let (font, font_size) = self.font(info, state);
+        let text_layout = layout_text(&text, &font, font_size, info);
+
+        let origin = self.origin(layout);
+
+        let hit = |cursor: Vector2<f64>| {
+            text_layout.hit(Vector2::new(
+                cursor.x as f32 - origin.x,
+                cursor.y as f32 - origin.y,
+            ))
+        };
+
+        if let Some(cursor) = info.cursor_pos {
+            let hovered = cursor.x as f32 >= layout.layout.location.x
+                && cursor.x as f32 <= layout.layout.location.x + layout.layout.size.width
+                && cursor.y as f32 >= layout.layout.location.y
+                && cursor.y as f32 <= layout.layout.location.y + layout.layout.size.height;
+
+            for (_, btn, el) in &info.buttons {
+                if *btn != MouseButton::Left {
+                    continue;
+                }
+
+                match el {
+                    ElementState::Pressed if hovered => {
+                        info.focus.request_focus(focus);
+
+                        self.move_to(hit(cursor), info.modifiers.shift_key());
+                        self.selecting = true;
+                        update.insert(Update::DRAW);
+                    },
+
+                    ElementState::Released => self.selecting = false,
+
+                    _ => (),
+                }
+            }
+
+            // extend the selection while dragging
+            if self.selecting && info.buttons.is_empty() {
+                let pos = hit(cursor);
+
+                if pos != self.cursor {
+                    self.move_to(pos, true);
+                    update.insert(Update::DRAW);
+                }
+            }
+        } else {
+            self.selecting = false;
+        }
+
+        let focused = info.focus.is_focused(focus);
+
+        let mut changed = false;
+        let mut submit = false;
+
+        if focused {
+            for (_, key) in &info.keys {
+                if key.state == ElementState::Pressed {
+                    changed |=
+                        self.key_pressed(&mut text, key, info, &font, font_size, &mut submit);
+
+                    update.insert(Update::DRAW);
+                }
+            }
+
+            for ime in &info.ime {
+                match ime {
+                    Ime::Preedit(preedit, cursor) if !preedit.is_empty() => {
+                        self.preedit = Some((preedit.clone(), *cursor));
+                    },
+
+                    Ime::Preedit(_, _) | Ime::Disabled => self.preedit = None,
+
+                    Ime::Commit(commit) => {
+                        self.preedit = None;
+                        self.insert(&mut text, commit);
+                        changed = true;
+                    },
+
+                    Ime::Enabled => (),
+                }
+
+                update.insert(Update::DRAW);
+            }
+        } else {
+            self.preedit = None;
+        }
+
+        // scroll, so the caret is always visible
+        let (display, cursor, _) = self.display_text(&text);
+        let caret = caret_area(&layout_text(&display, &font, font_size, info), cursor);
+
+        if self.scroll_to(caret, layout) {
+            update.insert(Update::DRAW);
+        }
+
+        // place the candidate window of the input method next to the caret
+        let origin = info.origin + self.origin(layout);
+
+        info.input_method
+            .request(focus, caret + Vec2::new(origin.x as f64, origin.y as f64));
+
+        if changed {
+            update.insert((self.on_change)(state, text));
+        }
+
+        if submit {
+            update.insert((self.on_submit)(state));
+        }
+
+        update
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        self.id.clone()
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let role = if self.multiline {
+            Role::MultilineTextInput
+        } else {
+            Role::TextInput
+        };
+
+        let mut node = AccessNode::new(role).with_value(self.text.get_ref(state));
+
+        if let Some(focus) = self.focus {
+            node = node.with_focus(focus);
+        }
+
+        node
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert(EditState {
+            cursor: self.cursor,
+            anchor: self.anchor,
+            selecting: self.selecting,
+            preedit: self.preedit.take(),
+            scroll: self.scroll,
+        });
+    }
+
+    fn restore_state(&mut self, _: &S, store: &mut InstanceStore) {
+        if let Some(edit) = store.take::<EditState>() {
+            self.cursor = edit.cursor;
+            self.anchor = edit.anchor;
+            self.selecting = edit.selecting;
+            self.preedit = edit.preedit;
+            self.scroll = edit.scroll;
+        }
+    }
+}
+
+/// The editing state of a text input, that is kept when the input gets rebuilt.
+///
+/// The cursor and anchor get clamped to the text on the next update.
+struct EditState {
+    cursor: usize,
+    anchor: usize,
+    selecting: bool,
+    preedit: Option<(String, Option<(usize, usize)>)>,
+    scroll: Vector2<f32>,
+}
+
+/// Returns the area of the caret at the given byte position, relative to the text origin.
+fn caret_area(text_layout: &TextLayout, cursor: usize) -> Rect {
+    let line = &text_layout.lines()[text_layout.line_at(cursor)];
+    let x = line.caret_x(cursor) as f64;
+    let y = line.top() as f64;
+
+    Rect::new(x, y, x + 1.0, y + line.height() as f64)
+}
+
+/// Shape and lay out the given text without wrapping, so lines are only broken at line breaks.
+fn layout_text(text: &str, font: &Font, font_size: f32, info: &AppInfo) -> TextLayout {
+    ShapedText::new(&[TextSpan::new(text)], &info.font_context, font, font_size).layout(None)
+}
+
+/// Clamps the given byte position to the text length and the previous character boundary.
+fn clamp_to_char(text: &str, mut pos: usize) -> usize {
+    pos = pos.min(text.len());
+
+    while !text.is_char_boundary(pos) {
+        pos -= 1;
+    }
+
+    pos
+}
+
+/// Returns the byte position of the start of the previous word.
+fn prev_word(text: &str, pos: usize) -> usize {
+    let mut result = 0;
+    let mut in_word = false;
+
+    for (i, c) in text[..pos].char_indices().rev() {
+        if c.is_alphanumeric() {
+            in_word = true;
+        } else if in_word {
+            break;
+        }
+
+        result = i;
+    }
+
+    result
+}
+
+/// Returns the byte position of the end of the next word.
+fn next_word(text: &str, pos: usize) -> usize {
+    let mut in_word = false;
+
+    for (i, c) in text[pos..].char_indices() {
+        if c.is_alphanumeric() {
+            in_word = true;
+        } else if in_word {
+            return pos + i;
+        }
+    }
+
+    text.len()
+}
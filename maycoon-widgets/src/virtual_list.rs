@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use maycoon_core::accesskit::Role;
+use maycoon_core::app::accessibility::AccessNode;
+use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::inspector::InspectNode;
+use maycoon_core::app::update::Update;
+use maycoon_core::instance::{InstanceStore, WidgetKey};
+use maycoon_core::layout::{
+    Dimension, LayoutNode, LayoutStyle, LengthPercentageAuto, Overflow, Position, Rect, StyleNode,
+};
+use maycoon_core::state::{State, Val};
+use maycoon_core::vg::kurbo::Affine;
+use maycoon_core::vg::peniko::Mix;
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::style::Style;
+use maycoon_theme::theme::Theme;
+use nalgebra::Vector2;
+
+use crate::scroll_view::{viewport, Scroller};
+
+/// The minimum height of rows, so the visible rows of a list are always limited.
+pub const MIN_ROW_HEIGHT: f32 = 1.0;
+
+/// A scrollable list, that only builds, lays out and renders the rows inside its viewport.
+///
+/// Use it to display very large data sets, like logs or tables with thousands of rows.
+/// Rows are built using a builder function, when they scroll into view, and are kept and reused
+/// until they leave the viewport. Row widgets should use state dependent values (see [Val::new_state])
+/// to show changing data. The instance state of rows, that leave the viewport (e.g. the cursor of a text input),
+/// is kept by their index and restored, when they're built again.
+///
+/// By default, all rows have the same height (see [VirtualList::with_row_height]).
+/// Use [VirtualList::with_measured_rows] for rows with different heights.
+///
+/// ### Theming
+/// You can style the scrollbar of the list using the following properties:
+/// - `color_track` - The color of the scrollbar track.
+/// - `color_thumb` - The color of the scrollbar thumb.
+/// - `color_thumb_active` - The color of the scrollbar thumb, when hovered or dragged.
+///
+/// The properties are the same as of the [ScrollView](crate::scroll_view::ScrollView) and declared in [scroll_view::STYLE_KEYS](crate::scroll_view::STYLE_KEYS).
+///
+/// The style can be overridden for a single list using [VirtualList::with_style]
+/// and a style variant of the theme can be chosen using [VirtualList::with_class].
+pub struct VirtualList<S: State> {
+    count: Val<S, usize>,
+    builder: RowBuilder<S>,
+    layout_style: Val<S, LayoutStyle>,
+    id: WidgetId,
+    theme_style: Val<S, Option<Style>>,
+    row_height: RowHeight,
+    overscan: usize,
+    scroller: Scroller,
+    rows: Vec<Row<S>>,
+    saved: HashMap<usize, InstanceStore>,
+    heights: Vec<f32>,
+    offsets: Vec<f32>,
+}
+
+/// The builder function of the virtual list, that builds the row with the given index.
+type RowBuilder<S> = Box<dyn Fn(&S, usize) -> Box<dyn Widget<S>>>;
+
+impl<S: State> VirtualList<S> {
+    /// Create a new virtual list with the given row count and a builder function for the rows.
+    ///
+    /// The builder gets called with the app state and the index of the row to build.
+    pub fn new(
+        count: impl Into<Val<S, usize>>,
+        builder: impl Fn(&S, usize) -> Box<dyn Widget<S>> + 'static,
+    ) -> Self {
+        Self {
+            count: count.into(),
+            builder: Box::new(builder),
+            layout_style: LayoutStyle {
+                overflow: (Overflow::Scroll, Overflow::Scroll),
+                ..Default::default()
+            }
+            .into(),
+            id: WidgetId::new("maycoon-widgets", "VirtualList"),
+            theme_style: None.into(),
+            row_height: RowHeight::Fixed(30.0),
+            overscan: 2,
+            scroller: Scroller::new(false, true),
+            rows: Vec::new(),
+            saved: HashMap::new(),
+            heights: Vec::new(),
+            offsets: vec![0.0],
+        }
+    }
+
+    /// Set the height of every row. Defaults to `30` points.
+    ///
+    /// Heights below [MIN_ROW_HEIGHT] are clamped.
+    pub fn with_row_height(mut self, height: f32) -> Self {
+        self.row_height = RowHeight::Fixed(height.max(MIN_ROW_HEIGHT));
+        self
+    }
+
+    /// Measure the height of every row using its layout.
+    ///
+    /// Rows, that were not visible yet, are estimated to have the given height.
+    /// Estimates below [MIN_ROW_HEIGHT] are clamped.
+    pub fn with_measured_rows(mut self, estimate: f32) -> Self {
+        self.row_height = RowHeight::Measured(estimate.max(MIN_ROW_HEIGHT));
+        self
+    }
+
+    /// Set how many rows should be built above and below the viewport. Defaults to `2`.
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Set if the content can be dragged with the mouse, keeping its momentum when released.
+    /// Disabled by default.
+    pub fn with_drag_scroll(mut self, drag_scroll: bool) -> Self {
+        self.scroller.drag_scroll = drag_scroll;
+        self
+    }
+
+    /// Set the layout style of the list.
+    ///
+    /// The list doesn't grow with its rows, so it should get a size from its layout.
+    pub fn with_layout_style(mut self, layout_style: impl Into<Val<S, LayoutStyle>>) -> Self {
+        self.layout_style = layout_style.into();
+        self
+    }
+
+    /// Sets the style of this list, which is merged over the style of the theme.
+    ///
+    /// Only the values, that should differ from the theme, need to be set.
+    pub fn with_style(mut self, style: impl Into<Val<S, Style>>) -> Self {
+        self.theme_style = style.into().map(Some);
+        self
+    }
+
+    /// Sets the class of the style variant, that the theme should use for this list (e.g. `subtle` for `VirtualList.subtle`).
+    pub fn with_class(mut self, class: impl ToString) -> Self {
+        self.id = self.id.with_class(class);
+        self
+    }
+
+    /// Returns the current scroll offset.
+    pub fn offset(&self) -> f32 {
+        self.scroller.offset.y
+    }
+
+    /// Scroll to the given offset. Gets clamped to the content size on the next update.
+    pub fn scroll_to(&mut self, offset: f32) {
+        self.scroller.scroll_to(Vector2::new(0.0, offset));
+    }
+
+    /// Scroll to the top of the row with the given index.
+    pub fn scroll_to_row(&mut self, index: usize) {
+        self.scroll_to(self.row_top(index));
+    }
+
+    /// Returns the indices of the currently built rows.
+    pub fn visible_rows(&self) -> Range<usize> {
+        match (self.rows.first(), self.rows.last()) {
+            (Some(first), Some(last)) => first.index..last.index + 1,
+            _ => 0..0,
+        }
+    }
+
+    /// Returns the top position of the row with the given index inside the content.
+    fn row_top(&self, index: usize) -> f32 {
+        match self.row_height {
+            RowHeight::Fixed(height) => index as f32 * height,
+            RowHeight::Measured(_) => self.offsets[index.min(self.offsets.len() - 1)],
+        }
+    }
+
+    /// Returns the index of the row at the given position inside the content.
+    fn row_at(&self, y: f32) -> usize {
+        match self.row_height {
+            RowHeight::Fixed(height) => (y / height).max(0.0) as usize,
+            RowHeight::Measured(_) => self
+                .offsets
+                .partition_point(|offset| *offset <= y)
+                .saturating_sub(1),
+        }
+    }
+
+    /// Save the instance state of the given row, which left the viewport.
+    fn save_row(&mut self, mut row: Row<S>) {
+        let mut store = InstanceStore::new();
+
+        row.widget.save_state(&mut store);
+
+        if !store.is_empty() {
+            self.saved.insert(row.index, store);
+        }
+    }
+
+    /// Update the row heights to the given row count and recompute the row offsets, if necessary.
+    fn resize(&mut self, count: usize) {
+        if let RowHeight::Measured(estimate) = self.row_height {
+            if self.heights.len() != count {
+                self.heights.resize(count, estimate);
+                self.update_offsets();
+            }
+        }
+    }
+
+    /// Recompute the row offsets from the row heights.
+    fn update_offsets(&mut self) {
+        self.offsets.clear();
+        self.offsets.push(0.0);
+
+        let mut top = 0.0;
+
+        for height in &self.heights {
+            top += height;
+            self.offsets.push(top);
+        }
+    }
+}
+
+impl<S: State> Widget<S> for VirtualList<S> {
+    fn render(
+        &mut self,
+        scene: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        layout_node: &LayoutNode,
+        state: &S,
+    ) {
+        let count = *self.count.get_ref(state);
+        let viewport = viewport(&layout_node.layout);
+        let content = Vector2::new(viewport.width() as f32, self.row_top(count));
+
+        scene.push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &viewport);
+
+        {
+            let mut child_scene = Scene::new();
+
+            for (row, layout) in self.rows.iter_mut().zip(&layout_node.children) {
+                row.widget
+                    .render(&mut child_scene, theme, info, layout, state);
+            }
+
+            scene.append(
+                &child_scene,
+                Some(self.scroller.content_transform(layout_node)),
+            );
+        }
+
+        scene.pop_layer();
+
+        let style = theme.style_of(self.widget_id(), self.theme_style.get_ref(state).as_ref());
+
+        self.scroller
+            .render(scene, theme, &style, viewport, content);
+    }
+
+    fn layout_style(&mut self, state: &S) -> StyleNode {
+        let mut children = Vec::with_capacity(self.rows.len());
+
+        for i in 0..self.rows.len() {
+            let top = self.row_top(self.rows[i].index);
+            let mut child = self.rows[i].widget.layout_style(state);
+
+            // place the row inside the content, stretched across the list
+            child.style.position = Position::Absolute;
+            child.style.inset = Rect {
+                left: LengthPercentageAuto::Length(0.0),
+                right: LengthPercentageAuto::Length(0.0),
+                top: LengthPercentageAuto::Length(top),
+                bottom: LengthPercentageAuto::Auto,
+            };
+
+            if let RowHeight::Fixed(height) = self.row_height {
+                child.style.size.y = Dimension::Length(height);
+            }
+
+            // rows keep their layout while scrolling
+            child.key = Some(WidgetKey::new(self.rows[i].index));
+
+            children.push(child);
+        }
+
+        StyleNode {
+            style: self.layout_style.get_ref(state).clone(),
+            children,
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, layout: &LayoutNode, state: &mut S, info: &AppInfo) -> Update {
+        self.layout_style.invalidate();
+        self.theme_style.invalidate();
+        self.count.invalidate();
+
+        let mut update = Update::empty();
+        let count = *self.count.get_ref(state);
+
+        self.resize(count);
+
+        // measure the laid out rows
+        if matches!(self.row_height, RowHeight::Measured(_))
+            && layout.children.len() == self.rows.len()
+        {
+            let mut changed = false;
+
+            for (row, layout) in self.rows.iter().zip(&layout.children) {
+                // the row count may have changed since the layout
+                if row.index < count
+                    && (self.heights[row.index] - layout.layout.size.height).abs() > 0.5
+                {
+                    self.heights[row.index] = layout.layout.size.height;
+                    changed = true;
+                }
+            }
+
+            if changed {
+                self.update_offsets();
+                update.insert(Update::LAYOUT);
+            }
+        }
+
+        let viewport = viewport(&layout.layout);
+        let old_offset = self.scroller.offset;
+
+        update.insert(self.scroller.update(
+            viewport,
+            Vector2::new(viewport.width() as f32, self.row_top(count)),
+            info,
+        ));
+
+        if self.scroller.offset != old_offset {
+            update.insert(Update::DRAW);
+        }
+
+        // update the rows, that match the current layout
+        if layout.children.len() == self.rows.len() {
+            let child_info = self.scroller.child_info(layout, info);
+
+            // rows keep their focus ids while scrolling
+            for (row, layout) in self.rows.iter_mut().zip(&layout.children) {
+                info.focus.push_scope(WidgetKey::new(row.index));
+                update.insert(row.widget.update(layout, state, &child_info));
+                info.focus.pop_scope();
+            }
+        }
+
+        // build the rows inside the viewport and reuse the ones, that are still visible
+        let offset = self.scroller.offset.y;
+        let first = self.row_at(offset).saturating_sub(self.overscan);
+        let last = (self.row_at(offset + viewport.height() as f32) + 1 + self.overscan).min(count);
+        let visible = first.min(last)..last;
+
+        if visible != self.visible_rows() {
+            let mut old = std::mem::take(&mut self.rows).into_iter().peekable();
+
+            for index in visible {
+                while let Some(row) = old.next_if(|row| row.index < index) {
+                    self.save_row(row);
+                }
+
+                let widget = match old.next_if(|row| row.index == index) {
+                    Some(row) => row.widget,
+
+                    None => {
+                        let mut widget = (self.builder)(state, index);
+
+                        // continue with the state of the row, when it was visible before
+                        if let Some(mut store) = self.saved.remove(&index) {
+                            widget.restore_state(state, &mut store);
+                        }
+
+                        widget
+                    },
+                };
+
+                self.rows.push(Row { index, widget });
+            }
+
+            for row in old {
+                self.save_row(row);
+            }
+
+            // drop the state of removed rows
+            self.saved.retain(|index, _| *index < count);
+
+            update.insert(Update::LAYOUT);
+        }
+
+        update
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        self.id.clone()
+    }
+
+    fn inspect(&mut self, state: &S) -> InspectNode {
+        let children = self
+            .rows
+            .iter_mut()
+            .map(|row| row.widget.inspect(state))
+            .collect();
+
+        InspectNode::new(self.widget_id())
+            .with_offset(-self.scroller.offset)
+            .with_children(children)
+    }
+
+    fn accessibility(&mut self, state: &S) -> AccessNode {
+        let children = self
+            .rows
+            .iter_mut()
+            .map(|row| row.widget.accessibility(state))
+            .collect();
+
+        AccessNode::new(Role::List)
+            .with_offset(-self.scroller.offset)
+            .with_children(children)
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        self.scroller.save_state(store);
+
+        // the rows are built again by the new list
+        for row in std::mem::take(&mut self.rows) {
+            self.save_row(row);
+        }
+
+        store
+            .child(WidgetKey::new("rows"))
+            .insert(std::mem::take(&mut self.saved));
+    }
+
+    fn restore_state(&mut self, _: &S, store: &mut InstanceStore) {
+        self.scroller.restore_state(store);
+
+        if let Some(saved) = store
+            .take_child(WidgetKey::new("rows"))
+            .and_then(|mut rows| rows.take())
+        {
+            self.saved = saved;
+        }
+    }
+}
+
+/// How the height of the rows is determined.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum RowHeight {
+    /// Every row has the given height.
+    Fixed(f32),
+    /// The rows are measured using their layout. Unmeasured rows are estimated to have the given height.
+    Measured(f32),
+}
+
+/// A built row of the virtual list.
+struct Row<S: State> {
+    index: usize,
+    widget: Box<dyn Widget<S>>,
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use maycoon_core::app::font_ctx::FontContext;
+use maycoon_core::app::offscreen::OffscreenRenderer;
+use maycoon_core::config::RenderConfig;
+use maycoon_core::snapshot::Snapshots;
+use maycoon_core::vg::peniko::{Blob, Font};
+
+/// Create an offscreen renderer, falling back to a software adapter if no GPU is available.
+#[allow(dead_code)]
+pub fn renderer() -> OffscreenRenderer {
+    OffscreenRenderer::new(&RenderConfig::default(), false)
+        .or_else(|_| OffscreenRenderer::new(&RenderConfig::default(), true))
+        .expect("Failed to create offscreen renderer")
+}
+
+/// Create a font context with a bundled default font, so text renders the same on every machine.
+#[allow(dead_code)]
+pub fn font_context() -> FontContext {
+    FontContext::new(Font::new(
+        Blob::new(Arc::new(
+            include_bytes!("../assets/DejaVuSansMono.ttf").to_vec(),
+        )),
+        0,
+    ))
+}
+
+/// The snapshot tester for widget snapshots.
+#[allow(dead_code)]
+pub fn snapshots() -> Snapshots {
+    Snapshots::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"))
+        .with_tolerance(16)
+        .with_max_mismatched_pixels(8)
+}
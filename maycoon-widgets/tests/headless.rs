@@ -0,0 +1,2375 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use maycoon_core::accesskit::{
+    Action, ActionData, ActionRequest, Node, NodeId, Role, Toggled, TreeUpdate,
+};
+use maycoon_core::app::diagnostics::FrameTimes;
+use maycoon_core::app::headless::HeadlessApp;
+use maycoon_core::app::info::{AppInfo, KeyInput};
+use maycoon_core::app::overlay::{OverlayConfig, OverlayKey};
+use maycoon_core::app::reload::HotReload;
+use maycoon_core::app::update::Update;
+use maycoon_core::app::windows::WindowKey;
+use maycoon_core::config::WindowConfig;
+use maycoon_core::instance::{InstanceStore, KeyedChildren, WidgetKey};
+use maycoon_core::layout::{
+    AlignSelf, Dimension, FlexDirection, Layout, LayoutNode, LayoutStyle, LengthPercentageAuto,
+    MeasureCache, MeasureFn, Overflow, Rect, StyleNode,
+};
+use maycoon_core::state::{State, Val};
+use maycoon_core::vg::peniko::{Blob, Color, Font, Format};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_core::window::{
+    ControlFlow, ElementState, Ime, Key, ModifiersState, MouseButton, MouseScrollDelta, NamedKey,
+};
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::theme::celeste::CelesteTheme;
+use maycoon_theme::theme::file::FileTheme;
+use maycoon_theme::theme::{ColorScheme, Theme};
+use maycoon_widgets::button::Button;
+use maycoon_widgets::checkbox::Checkbox;
+use maycoon_widgets::container::Container;
+use maycoon_widgets::dummy::DummyWidget;
+use maycoon_widgets::image::{Image, ImageData};
+use maycoon_widgets::schema::schema;
+use maycoon_widgets::scroll_view::ScrollView;
+use maycoon_widgets::slider::Slider;
+use maycoon_widgets::text::Text;
+use maycoon_widgets::text_area::TextArea;
+use maycoon_widgets::text_input::TextInput;
+use maycoon_widgets::virtual_list::VirtualList;
+use nalgebra::Vector2;
+
+struct TestState {
+    count: i32,
+    checked: bool,
+    value: f32,
+    text: String,
+    items: Vec<(u32, f32)>,
+}
+
+impl State for TestState {}
+
+impl Default for TestState {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            checked: false,
+            value: 0.0,
+            text: String::new(),
+            items: Vec::new(),
+        }
+    }
+}
+
+fn app<W: maycoon_core::widget::Widget<TestState>>(
+    widget: W,
+) -> HeadlessApp<CelesteTheme, W, TestState> {
+    HeadlessApp::new(
+        CelesteTheme::light(),
+        widget,
+        TestState::default(),
+        Vector2::new(200.0, 100.0),
+    )
+}
+
+#[test]
+fn button_fires_on_release() {
+    let mut app = app(
+        Button::new(DummyWidget).with_on_pressed(|state: &mut TestState| {
+            state.count += 1;
+            Update::DRAW
+        }),
+    );
+
+    app.move_cursor(Vector2::new(5.0, 5.0));
+    app.update();
+
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+    assert_eq!(app.state().count, 0);
+
+    app.mouse_input(MouseButton::Left, ElementState::Released);
+    assert!(app.update().contains(Update::DRAW));
+    assert_eq!(app.state().count, 1);
+
+    app.click(Vector2::new(5.0, 5.0), MouseButton::Left);
+    assert_eq!(app.state().count, 2);
+}
+
+#[test]
+fn button_ignores_clicks_outside() {
+    let mut app = app(
+        Button::new(DummyWidget).with_on_pressed(|state: &mut TestState| {
+            state.count += 1;
+            Update::DRAW
+        }),
+    );
+
+    let width = app.layout().layout.size.width as f64;
+
+    app.click(Vector2::new(width + 10.0, 5.0), MouseButton::Left);
+    app.click(Vector2::new(5.0, 5.0), MouseButton::Right);
+
+    assert_eq!(app.state().count, 0);
+}
+
+#[test]
+fn checkbox_toggles_on_click() {
+    let mut app = app(
+        Checkbox::new(Val::new_state(|state: &TestState| state.checked)).with_on_change(
+            |state: &mut TestState| {
+                state.checked = !state.checked;
+                Update::DRAW
+            },
+        ),
+    );
+
+    let layout = app.layout().layout;
+    assert_eq!(layout.size.width, 20.0);
+    assert_eq!(layout.size.height, 20.0);
+
+    app.click(Vector2::new(10.0, 10.0), MouseButton::Left);
+    assert!(app.state().checked);
+
+    app.click(Vector2::new(10.0, 10.0), MouseButton::Left);
+    assert!(!app.state().checked);
+
+    app.click(Vector2::new(150.0, 80.0), MouseButton::Left);
+    assert!(!app.state().checked);
+}
+
+#[test]
+fn slider_follows_drag() {
+    let mut app = app(Slider::new(
+        Val::new_state(|state: &TestState| state.value),
+        |state: &mut TestState, value| {
+            state.value = value;
+            Update::DRAW
+        },
+    ));
+
+    let layout = app.layout().layout;
+    let y = (layout.location.y + layout.size.height / 2.0) as f64;
+    let x = |value: f32| (layout.location.x + layout.size.width * value) as f64;
+
+    app.move_cursor(Vector2::new(x(0.5), y));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+    assert!((app.state().value - 0.5).abs() < 0.01);
+
+    app.move_cursor(Vector2::new(x(0.75), y));
+    app.update();
+    assert!((app.state().value - 0.75).abs() < 0.01);
+
+    app.mouse_input(MouseButton::Left, ElementState::Released);
+    app.update();
+
+    app.move_cursor(Vector2::new(x(0.25), y));
+    app.update();
+    assert!((app.state().value - 0.75).abs() < 0.01);
+}
+
+#[test]
+fn rebuilt_widgets_keep_instance_state() {
+    let mut app = app(Container::new(vec![Val::new_state(|_: &TestState| {
+        Box::new(Slider::new(
+            Val::new_state(|state: &TestState| state.value),
+            |state: &mut TestState, value| {
+                state.value = value;
+                Update::DRAW
+            },
+        )) as Box<dyn Widget<TestState>>
+    })]));
+
+    let layout = app.layout().children[0].layout;
+    let y = (layout.location.y + layout.size.height / 2.0) as f64;
+    let x = |value: f32| (layout.location.x + layout.size.width * value) as f64;
+
+    app.move_cursor(Vector2::new(x(0.5), y));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+    assert!((app.state().value - 0.5).abs() < 0.01);
+
+    // the slider gets rebuilt on every update, but keeps dragging
+    app.move_cursor(Vector2::new(x(0.75), y));
+    app.update();
+    assert!((app.state().value - 0.75).abs() < 0.01);
+}
+
+fn keyed_sliders(state: &TestState) -> KeyedChildren<TestState> {
+    state
+        .items
+        .iter()
+        .map(|(id, _)| {
+            let id = *id;
+            let slider = Slider::new(
+                Val::new_state(move |state: &TestState| {
+                    state.items.iter().find(|(i, _)| *i == id).unwrap().1
+                }),
+                move |state: &mut TestState, value| {
+                    state.items.iter_mut().find(|(i, _)| *i == id).unwrap().1 = value;
+                    Update::DRAW
+                },
+            );
+
+            (
+                WidgetKey::new(id),
+                Box::new(slider) as Box<dyn Widget<TestState>>,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn keyed_children_keep_state_when_reordered() {
+    let mut app = app(Container::new(Vec::new())
+        .with_keyed_children(Val::new_state(keyed_sliders))
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }));
+
+    app.state_mut().items.push((1, 0.0));
+    app.update();
+
+    let row = |app: &mut HeadlessApp<_, _, TestState>, i: usize| app.layout().children[i].layout;
+    let first = row(&mut app, 0);
+    let y = (first.location.y + first.size.height / 2.0) as f64;
+
+    app.move_cursor(Vector2::new(
+        (first.location.x + first.size.width * 0.5) as f64,
+        y,
+    ));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+    assert_eq!(app.state().items, vec![(1, 0.5)]);
+
+    // insert a new item before the dragged one
+    app.state_mut().items.insert(0, (0, 0.0));
+    app.update();
+    assert_eq!(app.layout().children.len(), 2);
+
+    let second = row(&mut app, 1);
+
+    app.move_cursor(Vector2::new(
+        (second.location.x + second.size.width * 0.75) as f64,
+        (second.location.y + second.size.height / 2.0) as f64,
+    ));
+    app.update();
+    assert_eq!(app.state().items[0], (0, 0.0));
+    assert_eq!(app.state().items[1].0, 1);
+    assert!((app.state().items[1].1 - 0.75).abs() < 0.01);
+}
+
+#[test]
+fn keyed_children_keep_focus_when_inserted_before() {
+    let mut app = app(Container::new(Vec::new())
+        .with_keyed_children(Val::new_state(keyed_sliders))
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }));
+
+    app.state_mut().items.push((1, 0.0));
+    app.update();
+    app.press_key(Key::Named(NamedKey::Tab));
+
+    app.state_mut().items.insert(0, (0, 0.0));
+    app.update();
+
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    assert_eq!(focused(&app), Some(1));
+    assert_eq!(app.state().items[0], (0, 0.0));
+    assert!(app.state().items[1].1 > 0.0);
+}
+
+/// A button, a checkbox and a slider, that can be focused in this order.
+fn focus_widgets() -> Vec<Val<TestState, Box<dyn Widget<TestState>>>> {
+    vec![
+        Val::new_val(Box::new(Button::new(DummyWidget).with_on_pressed(
+            |state: &mut TestState| {
+                state.count += 1;
+                Update::DRAW
+            },
+        ))),
+        Val::new_val(Box::new(
+            Checkbox::new(Val::new_state(|state: &TestState| state.checked)).with_on_change(
+                |state: &mut TestState| {
+                    state.checked = !state.checked;
+                    Update::DRAW
+                },
+            ),
+        )),
+        Val::new_val(Box::new(
+            Slider::new(
+                Val::new_state(|state: &TestState| state.value),
+                |state: &mut TestState, value| {
+                    state.value = value;
+                    Update::DRAW
+                },
+            )
+            .with_step(0.25),
+        )),
+    ]
+}
+
+fn focus_app() -> HeadlessApp<CelesteTheme, Container<TestState>, TestState> {
+    app(
+        Container::new(focus_widgets()).with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+    )
+}
+
+/// Returns the position of the focused widget in the focus order.
+fn focused<W: Widget<TestState>>(app: &HeadlessApp<CelesteTheme, W, TestState>) -> Option<usize> {
+    let focus = &app.info().focus;
+
+    focus.focused().and_then(|id| focus.position(id))
+}
+
+#[test]
+fn tab_traverses_focus() {
+    let mut app = focus_app();
+
+    app.update();
+    assert_eq!(app.info().focus.focused(), None);
+
+    assert!(app
+        .press_key(Key::Named(NamedKey::Tab))
+        .contains(Update::DRAW));
+    assert_eq!(focused(&app), Some(0));
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(2));
+
+    // wraps around
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(0));
+
+    app.set_modifiers(ModifiersState::SHIFT);
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(2));
+}
+
+#[test]
+fn keyboard_activates_focused_widgets() {
+    let mut app = focus_app();
+
+    app.update();
+
+    // nothing is focused, so nothing happens
+    app.press_key(Key::Named(NamedKey::Enter));
+    assert_eq!(app.state().count, 0);
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Enter));
+    app.press_key(Key::Named(NamedKey::Space));
+    assert_eq!(app.state().count, 2);
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Space));
+    assert!(app.state().checked);
+    assert_eq!(app.state().count, 2);
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    assert_eq!(app.state().value, 0.5);
+
+    app.press_key(Key::Named(NamedKey::End));
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    assert_eq!(app.state().value, 1.0);
+
+    app.press_key(Key::Named(NamedKey::Home));
+    assert_eq!(app.state().value, 0.0);
+    assert!(app.state().checked);
+}
+
+#[test]
+fn slider_adds_up_key_presses_of_one_frame() {
+    let mut app = focus_app();
+
+    app.update();
+
+    for _ in 0..3 {
+        app.press_key(Key::Named(NamedKey::Tab));
+    }
+
+    for _ in 0..3 {
+        app.key_input(KeyInput::new(
+            Key::Named(NamedKey::ArrowRight),
+            ElementState::Pressed,
+        ));
+    }
+
+    app.update();
+    assert_eq!(app.state().value, 0.75);
+}
+
+#[test]
+fn click_moves_focus() {
+    let mut app = focus_app();
+
+    let checkbox = app.layout().children[1].layout;
+
+    app.click(
+        Vector2::new(
+            (checkbox.location.x + 5.0) as f64,
+            (checkbox.location.y + 5.0) as f64,
+        ),
+        MouseButton::Left,
+    );
+    assert!(app.state().checked);
+    assert_eq!(focused(&app), Some(1));
+
+    app.click(Vector2::new(190.0, 90.0), MouseButton::Left);
+    assert_eq!(app.info().focus.focused(), None);
+}
+
+fn text_input() -> TextInput<TestState> {
+    TextInput::new(Val::new_state(|state: &TestState| state.text.clone())).with_on_change(
+        |state: &mut TestState, text| {
+            state.text = text;
+            Update::DRAW
+        },
+    )
+}
+
+#[test]
+fn text_input_edits_text() {
+    let mut app = app(text_input().with_on_submit(|state: &mut TestState| {
+        state.count += 1;
+        Update::empty()
+    }));
+
+    // not focused yet
+    app.type_text("ignored");
+    assert_eq!(app.state().text, "");
+
+    app.click(Vector2::new(20.0, 20.0), MouseButton::Left);
+    assert_eq!(focused(&app), Some(0));
+
+    app.type_text("hello");
+    assert_eq!(app.state().text, "hello");
+    assert_eq!(app.widget().cursor(), 5);
+
+    app.press_key(Key::Named(NamedKey::Backspace));
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    app.type_text("y");
+    assert_eq!(app.state().text, "helyl");
+
+    app.press_key(Key::Named(NamedKey::Home));
+    app.press_key(Key::Named(NamedKey::Delete));
+    app.type_text("H");
+    app.press_key(Key::Named(NamedKey::End));
+    app.type_text(" wörld");
+    assert_eq!(app.state().text, "Helyl wörld");
+
+    app.set_modifiers(ModifiersState::CONTROL);
+    app.press_key(Key::Named(NamedKey::Backspace));
+    assert_eq!(app.state().text, "Helyl ");
+
+    app.set_modifiers(ModifiersState::empty());
+    app.press_key(Key::Named(NamedKey::Enter));
+    assert_eq!(app.state().text, "Helyl ");
+    assert_eq!(app.state().count, 1);
+}
+
+#[test]
+fn text_input_moves_by_glyph_clusters() {
+    let mut app = app(text_input());
+
+    app.click(Vector2::new(20.0, 20.0), MouseButton::Left);
+    app.type_text("ae\u{301}b");
+
+    // the combining mark belongs to the cluster of the `e`
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    assert_eq!(app.widget().cursor(), 1);
+
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    assert_eq!(app.widget().cursor(), 4);
+
+    app.press_key(Key::Named(NamedKey::Backspace));
+    assert_eq!(app.state().text, "ab");
+
+    app.state_mut().text = String::from("ae\u{301}b");
+    app.press_key(Key::Named(NamedKey::Home));
+    app.press_key(Key::Named(NamedKey::ArrowRight));
+    app.press_key(Key::Named(NamedKey::Delete));
+    assert_eq!(app.state().text, "ab");
+}
+
+#[test]
+fn text_input_selects_text() {
+    let mut app = app(text_input());
+
+    app.state_mut().text = "hello world".to_string();
+    app.click(Vector2::new(20.0, 20.0), MouseButton::Left);
+
+    app.press_key(Key::Named(NamedKey::End));
+    app.set_modifiers(ModifiersState::SHIFT);
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    assert_eq!(app.widget().selection(), 9..11);
+
+    app.set_modifiers(ModifiersState::SHIFT | ModifiersState::CONTROL);
+    app.press_key(Key::Named(NamedKey::ArrowLeft));
+    assert_eq!(app.widget().selection(), 6..11);
+
+    app.set_modifiers(ModifiersState::empty());
+    app.type_text("there");
+    assert_eq!(app.state().text, "hello there");
+
+    app.set_modifiers(ModifiersState::CONTROL);
+    app.press_key(Key::Character("a".into()));
+    assert_eq!(app.widget().selection(), 0..11);
+
+    app.set_modifiers(ModifiersState::empty());
+    app.press_key(Key::Named(NamedKey::Backspace));
+    assert_eq!(app.state().text, "");
+}
+
+#[test]
+fn text_input_selects_with_mouse() {
+    let mut app = app(text_input());
+
+    app.state_mut().text = "hello world".to_string();
+
+    let layout = app.layout().layout;
+    let y = (layout.location.y + layout.size.height / 2.0) as f64;
+
+    app.move_cursor(Vector2::new((layout.location.x + 2.0) as f64, y));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+    assert_eq!(app.widget().cursor(), 0);
+
+    app.move_cursor(Vector2::new(
+        (layout.location.x + layout.size.width - 2.0) as f64,
+        y,
+    ));
+    app.update();
+    app.mouse_input(MouseButton::Left, ElementState::Released);
+    app.update();
+    assert_eq!(app.widget().selection(), 0..11);
+
+    app.type_text("x");
+    assert_eq!(app.state().text, "x");
+}
+
+#[test]
+fn text_input_hits_scrolled_text() {
+    let mut app = app(text_input());
+    let text = "hello world ".repeat(5);
+
+    app.state_mut().text = text.clone();
+
+    let layout = app.layout().layout;
+    let y = (layout.location.y + layout.size.height / 2.0) as f64;
+
+    // the text is scrolled during the update, so the end of the text is visible
+    app.click(
+        Vector2::new((layout.location.x + 10.0) as f64, y),
+        MouseButton::Left,
+    );
+    app.press_key(Key::Named(NamedKey::End));
+
+    app.click(
+        Vector2::new((layout.location.x + layout.size.width - 8.0) as f64, y),
+        MouseButton::Left,
+    );
+    assert!(app.widget().cursor() >= text.len() - 1);
+
+    app.click(
+        Vector2::new((layout.location.x + 8.0) as f64, y),
+        MouseButton::Left,
+    );
+    assert!(app.widget().cursor() > 0);
+}
+
+#[test]
+fn text_input_hits_right_to_left_text() {
+    let mut app = app(text_input());
+
+    app.state_mut().text = "שלום".to_string();
+
+    let layout = app.layout().layout;
+    let y = (layout.location.y + layout.size.height / 2.0) as f64;
+
+    // the visual start of right-to-left text is its logical end
+    app.click(
+        Vector2::new((layout.location.x + 2.0) as f64, y),
+        MouseButton::Left,
+    );
+    assert_eq!(app.widget().cursor(), "שלום".len());
+
+    app.click(
+        Vector2::new((layout.location.x + layout.size.width - 2.0) as f64, y),
+        MouseButton::Left,
+    );
+    assert_eq!(app.widget().cursor(), 0);
+
+    app.type_text("א");
+    assert_eq!(app.state().text, "אשלום");
+}
+
+#[test]
+fn text_input_supports_ime() {
+    let mut app = app(text_input());
+
+    app.update();
+    assert_eq!(app.info().input_method.area(), None);
+
+    // the input method is enabled at the caret, while the input is focused
+    app.click(Vector2::new(20.0, 20.0), MouseButton::Left);
+    app.type_text("a");
+
+    let layout = app.layout().layout;
+    let area = app.info().input_method.area().unwrap();
+
+    assert!(area.x0 > layout.location.x as f64 && area.x0 < 40.0);
+    assert!(area.y0 >= layout.location.y as f64);
+
+    app.ime_input(Ime::Enabled);
+    app.ime_input(Ime::Preedit("に".to_string(), Some((0, 3))));
+    app.update();
+    assert_eq!(app.state().text, "a");
+
+    app.ime_input(Ime::Preedit(String::new(), None));
+    app.ime_input(Ime::Commit("日本".to_string()));
+    app.update();
+    assert_eq!(app.state().text, "a日本");
+    assert_eq!(app.widget().cursor(), "a日本".len());
+
+    // the margin of the input is outside of it
+    app.click(Vector2::new(1.0, 1.0), MouseButton::Left);
+    assert_eq!(app.info().input_method.area(), None);
+}
+
+#[test]
+fn text_area_inserts_lines() {
+    let mut app = app(
+        TextArea::new(Val::new_state(|state: &TestState| state.text.clone())).with_on_change(
+            |state: &mut TestState, text| {
+                state.text = text;
+                Update::DRAW
+            },
+        ),
+    );
+
+    app.click(Vector2::new(20.0, 20.0), MouseButton::Left);
+    app.type_text("first");
+    app.press_key(Key::Named(NamedKey::Enter));
+    app.type_text("second line");
+    assert_eq!(app.state().text, "first\nsecond line");
+
+    app.press_key(Key::Named(NamedKey::ArrowUp));
+    assert_eq!(app.widget().cursor(), 5);
+
+    app.press_key(Key::Named(NamedKey::Home));
+    app.press_key(Key::Named(NamedKey::ArrowDown));
+    assert_eq!(app.widget().cursor(), 6);
+
+    app.press_key(Key::Named(NamedKey::End));
+    assert_eq!(app.widget().cursor(), "first\nsecond line".len());
+}
+
+fn access_app() -> HeadlessApp<CelesteTheme, Container<TestState>, TestState> {
+    let image = ImageData::new(vec![0; 4 * 4 * 4], Vector2::new(4, 4), Format::Rgba8);
+
+    HeadlessApp::new(
+        CelesteTheme::light(),
+        Container::new(vec![
+            Val::new_val(Box::new(
+                Button::new(Text::new("Add".to_string()).with_font_size(12.0)).with_on_pressed(
+                    |state: &mut TestState| {
+                        state.count += 1;
+                        Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(
+                Checkbox::new(Val::new_state(|state: &TestState| state.checked)).with_on_change(
+                    |state: &mut TestState| {
+                        state.checked = !state.checked;
+                        Update::DRAW
+                    },
+                ),
+            )),
+            Val::new_val(Box::new(
+                Slider::new(
+                    Val::new_state(|state: &TestState| state.value),
+                    |state: &mut TestState, value| {
+                        state.value = value;
+                        Update::DRAW
+                    },
+                )
+                .with_step(0.25),
+            )),
+            Val::new_val(Box::new(Image::new(image).with_alt("Logo".to_string()))),
+        ])
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+        TestState::default(),
+        Vector2::new(200.0, 300.0),
+    )
+}
+
+fn access_node(tree: &TreeUpdate, role: Role) -> (NodeId, &Node) {
+    tree.nodes
+        .iter()
+        .find(|(_, node)| node.role() == role)
+        .map(|(id, node)| (*id, node))
+        .unwrap_or_else(|| panic!("no {role:?} node"))
+}
+
+fn access_request(target: NodeId, action: Action, data: Option<ActionData>) -> ActionRequest {
+    ActionRequest {
+        action,
+        target,
+        data,
+    }
+}
+
+#[test]
+fn accessibility_tree_describes_widgets() {
+    let mut app = access_app();
+
+    app.state_mut().checked = true;
+    app.state_mut().value = 0.5;
+    app.update();
+
+    let tree = app.access_tree();
+    let root = tree.tree.as_ref().unwrap().root;
+
+    assert_eq!(
+        tree.nodes
+            .iter()
+            .find(|(id, _)| *id == root)
+            .unwrap()
+            .1
+            .role(),
+        Role::Window
+    );
+    assert_eq!(tree.focus, root);
+
+    let (_, button) = access_node(&tree, Role::Button);
+    let (label, _) = access_node(&tree, Role::Label);
+    assert!(button.supports_action(Action::Click));
+    assert!(button.supports_action(Action::Focus));
+    assert_eq!(button.children(), &[label]);
+    assert_eq!(access_node(&tree, Role::Label).1.value(), Some("Add"));
+
+    let (_, checkbox) = access_node(&tree, Role::CheckBox);
+    let layout = app.layout().children[1].layout;
+    let bounds = checkbox.bounds().unwrap();
+    assert_eq!(checkbox.toggled(), Some(Toggled::True));
+    assert_eq!(
+        (bounds.x0, bounds.y0, bounds.width(), bounds.height()),
+        (
+            layout.location.x as f64,
+            layout.location.y as f64,
+            20.0,
+            20.0
+        )
+    );
+
+    let (_, slider) = access_node(&tree, Role::Slider);
+    assert_eq!(slider.numeric_value(), Some(0.5));
+    assert_eq!(slider.min_numeric_value(), Some(0.0));
+    assert_eq!(slider.max_numeric_value(), Some(1.0));
+    assert_eq!(slider.numeric_value_step(), Some(0.25));
+
+    assert_eq!(access_node(&tree, Role::Image).1.label(), Some("Logo"));
+}
+
+#[test]
+fn accessibility_actions_control_widgets() {
+    let mut app = access_app();
+
+    app.update();
+
+    let tree = app.access_tree();
+    let (button, _) = access_node(&tree, Role::Button);
+    let (checkbox, _) = access_node(&tree, Role::CheckBox);
+    let (slider, _) = access_node(&tree, Role::Slider);
+    let (image, _) = access_node(&tree, Role::Image);
+
+    app.access_action(access_request(button, Action::Click, None));
+    app.access_action(access_request(checkbox, Action::Click, None));
+    app.access_action(access_request(image, Action::Click, None));
+    app.update();
+    assert_eq!(app.state().count, 1);
+    assert!(app.state().checked);
+
+    app.access_action(access_request(
+        slider,
+        Action::SetValue,
+        Some(ActionData::NumericValue(0.5)),
+    ));
+    app.update();
+    assert_eq!(app.state().value, 0.5);
+
+    app.access_action(access_request(slider, Action::Increment, None));
+    app.access_action(access_request(slider, Action::Focus, None));
+    app.update();
+    assert_eq!(app.state().value, 0.75);
+
+    let tree = app.access_tree();
+    assert_eq!(tree.focus, slider);
+    assert_eq!(
+        access_node(&tree, Role::Slider).1.numeric_value(),
+        Some(0.75)
+    );
+}
+
+#[test]
+fn text_wraps_to_container_width() {
+    let mut app = app(Text::new("Some text, that wraps".to_string())
+        .with_font_size(16.0)
+        .with_layout(LayoutStyle {
+            align_self: Some(AlignSelf::FlexStart),
+            ..Default::default()
+        }));
+
+    let single_line = app.layout().layout.size;
+    assert!(single_line.width > 100.0);
+
+    app.resize(Vector2::new(100.0, 100.0));
+
+    let wrapped = app.layout().layout.size;
+    assert!(wrapped.width <= 100.0);
+    assert!(wrapped.height > single_line.height * 1.5);
+}
+
+#[test]
+fn text_lays_out_again_when_font_size_or_wrapping_change() {
+    let mut app = app(Text::new("Some text, that wraps".to_string())
+        .with_font_size(Val::new_state(|state: &TestState| state.value))
+        .with_wrap(Val::new_state(|state: &TestState| state.checked))
+        .with_layout(LayoutStyle {
+            align_self: Some(AlignSelf::FlexStart),
+            ..Default::default()
+        }));
+
+    app.update();
+
+    app.state_mut().value = 16.0;
+    assert!(app.update().contains(Update::LAYOUT));
+
+    let small = app.layout().layout.size;
+    assert!(!app.update().contains(Update::LAYOUT));
+
+    app.state_mut().value = 32.0;
+    assert!(app.update().contains(Update::LAYOUT));
+    assert!(app.layout().layout.size.height > small.height * 1.5);
+
+    app.state_mut().checked = true;
+    assert!(app.update().contains(Update::LAYOUT));
+    assert!(app.layout().layout.size.width <= 200.0);
+}
+
+#[test]
+fn image_sizes_from_pixels() {
+    let image = || ImageData::new(vec![0; 40 * 20 * 4], Vector2::new(40, 20), Format::Rgba8);
+
+    let mut natural = app(Image::new(image()).with_style(LayoutStyle {
+        align_self: Some(AlignSelf::FlexStart),
+        ..Default::default()
+    }));
+
+    let size = natural.layout().layout.size;
+    assert_eq!((size.width, size.height), (40.0, 20.0));
+
+    let mut scaled = app(Image::new(image()).with_style(LayoutStyle {
+        size: Vector2::new(Dimension::Length(80.0), Dimension::Auto),
+        align_self: Some(AlignSelf::FlexStart),
+        ..Default::default()
+    }));
+
+    let size = scaled.layout().layout.size;
+    assert_eq!((size.width, size.height), (80.0, 40.0));
+}
+
+#[test]
+fn windows_open_and_close_after_update() {
+    let mut app = app(DummyWidget);
+    let windows = app.info().windows.clone();
+
+    assert_eq!(windows.open_windows(), vec![WindowKey::MAIN]);
+
+    let key = windows.open::<TestState, _>(
+        WindowConfig {
+            title: "Settings".to_string(),
+            ..Default::default()
+        },
+        DummyWidget,
+    );
+
+    // requests are applied after the update
+    assert!(!windows.is_open(key));
+
+    app.update();
+
+    assert!(windows.is_open(key));
+    assert_eq!(windows.open_windows(), vec![WindowKey::MAIN, key]);
+    assert_eq!(app.window_config(key).unwrap().title, "Settings");
+    assert_eq!(
+        app.window_widget_mut(key).unwrap().widget_id(),
+        WidgetId::new("maycoon-widgets", "DummyWidget")
+    );
+
+    windows.close(key);
+    app.update();
+
+    assert!(!windows.is_open(key));
+    assert!(app.window_config(key).is_none());
+    assert!(!app.is_exited());
+
+    // closing the main window exits the app
+    windows.close(WindowKey::MAIN);
+    app.update();
+
+    assert!(app.is_exited());
+}
+
+/// Requests a single update after the given delay on its first update.
+struct Delayed {
+    delay: Option<Duration>,
+}
+
+impl Widget<TestState> for Delayed {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, info: &AppInfo) -> Update {
+        if let Some(delay) = self.delay.take() {
+            info.scheduler.request_update_after(delay);
+        }
+
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Delayed")
+    }
+}
+
+#[test]
+fn scheduled_updates_wait_until_deadline() {
+    let mut delayed = app(Delayed {
+        delay: Some(Duration::from_secs(60)),
+    });
+
+    assert_eq!(delayed.control_flow(), ControlFlow::Wait);
+
+    let before = Instant::now();
+
+    delayed.update();
+
+    let ControlFlow::WaitUntil(at) = delayed.control_flow() else {
+        panic!("expected to wait until the requested update");
+    };
+
+    assert!(at >= before + Duration::from_secs(60));
+
+    // the deadline is kept until it's due
+    delayed.update();
+    assert_eq!(delayed.control_flow(), ControlFlow::WaitUntil(at));
+
+    // once the update is due, the app sleeps until the next event
+    let mut due = app(Delayed {
+        delay: Some(Duration::ZERO),
+    });
+
+    due.update();
+    due.update();
+    assert_eq!(due.control_flow(), ControlFlow::Wait);
+}
+
+/// Counts how often its content gets measured and requests a layout update on every update.
+struct Measured {
+    measured: Rc<Cell<usize>>,
+    measure: MeasureCache<i32>,
+}
+
+impl Widget<TestState> for Measured {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, state: &TestState) -> StyleNode {
+        let measured = self.measured.clone();
+
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: Some(self.measure.get(state.count, |&count| {
+                Rc::new(move |_, _, _| {
+                    measured.set(measured.get() + 1);
+                    Vector2::new(10.0 * count as f32, 10.0)
+                })
+            })),
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, _: &AppInfo) -> Update {
+        Update::LAYOUT
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Measured")
+    }
+}
+
+#[test]
+fn layout_only_measures_changed_nodes() {
+    let measured = Rc::new(Cell::new(0));
+
+    let mut app = app(Container::new(vec![
+        Val::new_val(Box::new(Measured {
+            measured: measured.clone(),
+            measure: MeasureCache::default(),
+        }) as Box<dyn Widget<TestState>>),
+        Val::new_val(Box::new(Text::new("Hello".to_string()))),
+    ]));
+
+    app.update();
+    let initial = measured.get();
+    assert!(initial > 0);
+
+    // nothing changed, so the cached layout is reused
+    app.update();
+    app.update();
+    assert_eq!(measured.get(), initial);
+
+    // the content changed, so it is measured again
+    app.state_mut().count = 2;
+    app.update();
+    assert!(measured.get() > initial);
+    assert_eq!(app.layout().children[0].layout.size.width, 20.0);
+}
+
+/// A leaf widget with a fixed measure function.
+struct Leaf {
+    measure: MeasureFn,
+}
+
+impl Widget<TestState> for Leaf {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: Some(self.measure.clone()),
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, _: &AppInfo) -> Update {
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Leaf")
+    }
+}
+
+#[test]
+fn layout_keeps_keyed_nodes_when_inserted_before() {
+    let measured = Rc::new([Cell::new(0), Cell::new(0), Cell::new(0)]);
+
+    // one measure function per item, so rebuilt children compare equal
+    let measures = (0..3)
+        .map(|id| {
+            let measured = measured.clone();
+
+            Rc::new(move |_, _, _: &AppInfo| {
+                measured[id].set(measured[id].get() + 1);
+                Vector2::new(10.0, 10.0 * (id + 1) as f32)
+            }) as MeasureFn
+        })
+        .collect::<Vec<_>>();
+
+    let mut app = app(Container::new(Vec::new())
+        .with_keyed_children(Val::new_state(move |state: &TestState| {
+            state
+                .items
+                .iter()
+                .map(|(id, _)| {
+                    let leaf = Leaf {
+                        measure: measures[*id as usize].clone(),
+                    };
+
+                    (
+                        WidgetKey::new(*id),
+                        Box::new(leaf) as Box<dyn Widget<TestState>>,
+                    )
+                })
+                .collect()
+        }))
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }));
+
+    app.state_mut().items = vec![(1, 0.0), (2, 0.0)];
+    app.update();
+    let before = [measured[1].get(), measured[2].get()];
+    assert!(before.iter().all(|&count| count > 0));
+
+    // only the new item is measured, its siblings keep their cached layout
+    app.state_mut().items.insert(0, (0, 0.0));
+    app.update();
+    app.update();
+    assert!(measured[0].get() > 0);
+    assert_eq!([measured[1].get(), measured[2].get()], before);
+
+    let heights = app
+        .layout()
+        .children
+        .iter()
+        .map(|child| child.layout.size.height)
+        .collect::<Vec<_>>();
+    assert_eq!(heights, vec![10.0, 20.0, 30.0]);
+}
+
+/// A scroll view with a 200x400 column of content inside a 200x100 window.
+///
+/// The content contains a 150 points high spacer and a button, that counts clicks.
+fn scroll_view() -> ScrollView<TestState, Container<TestState>> {
+    let size = |height| LayoutStyle {
+        size: Vector2::new(Dimension::Length(200.0), Dimension::Length(height)),
+        ..Default::default()
+    };
+
+    ScrollView::new(
+        Container::new(vec![
+            Val::new_val(Box::new(
+                Container::new(Vec::new()).with_layout_style(size(150.0)),
+            )),
+            Val::new_val(Box::new(
+                Button::new(DummyWidget)
+                    .with_layout_style(size(50.0))
+                    .with_on_pressed(|state: &mut TestState| {
+                        state.count += 1;
+                        Update::DRAW
+                    }),
+            )),
+            Val::new_val(Box::new(
+                Container::new(Vec::new()).with_layout_style(size(200.0)),
+            )),
+        ])
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+    )
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        flex_direction: FlexDirection::Column,
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn scroll_view_scrolls_with_wheel() {
+    let mut app = app(scroll_view());
+
+    app.update();
+    assert_eq!(app.layout().layout.size.height, 100.0);
+
+    app.move_cursor(Vector2::new(50.0, 50.0));
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -1.0));
+    assert!(app.update().contains(Update::DRAW));
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 40.0));
+
+    // the offset is clamped to the content
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -100.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 300.0));
+
+    // the wheel is ignored outside the viewport
+    app.leave_cursor();
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 300.0));
+}
+
+#[test]
+fn nested_scroll_views_consume_wheel() {
+    let size = |height| LayoutStyle {
+        size: Vector2::new(Dimension::Length(200.0), Dimension::Length(height)),
+        flex_shrink: 0.0,
+        ..Default::default()
+    };
+
+    // an 80 points high inner scroll view with 200 points of content above a 50 points high spacer
+    let inner = ScrollView::new(Container::new(Vec::new()).with_layout_style(size(200.0)))
+        .with_layout_style(LayoutStyle {
+            overflow: (Overflow::Scroll, Overflow::Scroll),
+            ..size(80.0)
+        })
+        .with_on_scroll(|state: &mut TestState, offset| {
+            state.value = offset.y;
+            Update::DRAW
+        });
+
+    let mut app = app(ScrollView::new(
+        Container::new(vec![
+            Val::new_val(Box::new(inner) as Box<dyn Widget<TestState>>),
+            Val::new_val(Box::new(
+                Container::new(Vec::new()).with_layout_style(size(50.0)),
+            )),
+        ])
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+    )
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        flex_direction: FlexDirection::Column,
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+    app.move_cursor(Vector2::new(50.0, 40.0));
+
+    // the outer scroll view consumes the wheel until it reaches its end
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -1.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 30.0));
+    assert_eq!(app.state().value, 0.0);
+
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -1.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 30.0));
+    assert_eq!(app.state().value, 40.0);
+
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 0.0));
+    assert_eq!(app.state().value, 40.0);
+}
+
+#[test]
+fn scroll_view_translates_cursor_for_children() {
+    let mut app = app(scroll_view());
+
+    // the button is below the viewport
+    app.click(Vector2::new(50.0, 75.0), MouseButton::Left);
+    assert_eq!(app.state().count, 0);
+
+    app.move_cursor(Vector2::new(50.0, 50.0));
+    app.mouse_wheel(MouseScrollDelta::PixelDelta((0.0, -100.0).into()));
+    app.update();
+
+    // the button is now at the bottom half of the viewport
+    app.click(Vector2::new(50.0, 75.0), MouseButton::Left);
+    assert_eq!(app.state().count, 1);
+
+    app.click(Vector2::new(50.0, 25.0), MouseButton::Left);
+    assert_eq!(app.state().count, 1);
+}
+
+#[test]
+fn scroll_view_scrolls_programmatically() {
+    let mut app = app(
+        scroll_view().with_scroll_to(Val::new_state(|state: &TestState| {
+            Some(Vector2::new(0.0, state.value))
+        })),
+    );
+
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 0.0));
+
+    app.state_mut().value = 120.0;
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 120.0));
+
+    // scrolling by hand is kept, until the requested offset changes again
+    app.move_cursor(Vector2::new(50.0, 50.0));
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, 1.0));
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 80.0));
+
+    app.state_mut().value = 1000.0;
+    app.update();
+    assert_eq!(app.widget().offset(), Vector2::new(0.0, 300.0));
+}
+
+#[test]
+fn scroll_view_drags_scrollbar() {
+    let mut app = app(scroll_view());
+
+    app.update();
+
+    // the thumb is a quarter of the track at the right edge of the viewport
+    app.move_cursor(Vector2::new(194.0, 10.0));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+
+    app.move_cursor(Vector2::new(194.0, 10.0 + 72.0 / 2.0));
+    app.update();
+    assert!((app.widget().offset().y - 150.0).abs() < 1.0);
+
+    app.mouse_input(MouseButton::Left, ElementState::Released);
+    app.update();
+    assert_eq!(app.state().count, 0);
+}
+
+#[test]
+fn scroll_view_keeps_momentum() {
+    let mut app = app(scroll_view().with_drag_scroll(true));
+
+    app.move_cursor(Vector2::new(50.0, 50.0));
+    app.mouse_input(MouseButton::Left, ElementState::Pressed);
+    app.update();
+
+    for y in [45.0, 40.0, 35.0] {
+        app.move_cursor(Vector2::new(50.0, y));
+        app.advance(Duration::from_millis(5));
+    }
+
+    app.mouse_input(MouseButton::Left, ElementState::Released);
+    app.update();
+
+    let released = app.widget().offset().y;
+    assert!(released >= 15.0);
+
+    // keeps scrolling after the release, until the momentum stops
+    let mut updates = 0;
+
+    loop {
+        if !app.advance(Duration::from_millis(5)).contains(Update::EVAL) {
+            break;
+        }
+
+        updates += 1;
+        assert!(updates < 1000, "Momentum did not stop");
+    }
+
+    assert!(app.widget().offset().y > released);
+}
+
+#[test]
+fn virtual_list_only_builds_visible_rows() {
+    let built = Rc::new(Cell::new(0));
+    let counter = built.clone();
+
+    let mut app = app(VirtualList::new(100_000, move |_: &TestState, _| {
+        counter.set(counter.get() + 1);
+        Box::new(DummyWidget) as Box<dyn Widget<TestState>>
+    })
+    .with_row_height(20.0)
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+
+    // 5 rows inside the 100 points high viewport, a partially visible one and the overscan
+    assert_eq!(app.widget().visible_rows(), 0..8);
+    assert_eq!(app.layout().children.len(), 8);
+    assert_eq!(app.layout().children[3].layout.location.y, 60.0);
+    assert_eq!(built.get(), 8);
+
+    // the rows, that are still visible, are reused
+    app.move_cursor(Vector2::new(50.0, 50.0));
+    app.mouse_wheel(MouseScrollDelta::LineDelta(0.0, -1.0));
+    app.update();
+    assert_eq!(app.widget().visible_rows(), 0..10);
+    assert_eq!(built.get(), 10);
+
+    app.widget_mut().scroll_to_row(99_999);
+    app.update();
+    assert_eq!(app.widget().offset(), 100_000.0 * 20.0 - 100.0);
+    assert_eq!(app.widget().visible_rows(), 99_993..100_000);
+}
+
+#[test]
+fn virtual_list_measures_rows() {
+    let mut app = app(VirtualList::new(1000, |_: &TestState, index| {
+        let height = if index % 2 == 0 { 10.0 } else { 30.0 };
+
+        Box::new(Container::new(Vec::new()).with_layout_style(LayoutStyle {
+            size: Vector2::new(Dimension::Auto, Dimension::Length(height)),
+            ..Default::default()
+        })) as Box<dyn Widget<TestState>>
+    })
+    .with_measured_rows(20.0)
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+    app.update();
+
+    let rows = &app.layout().children;
+    assert_eq!(rows[0].layout.size.width, 200.0);
+    assert_eq!(rows[1].layout.location.y, 10.0);
+    assert_eq!(rows[2].layout.location.y, 40.0);
+    assert_eq!(rows[3].layout.location.y, 50.0);
+}
+
+#[test]
+fn virtual_list_translates_cursor_for_rows() {
+    let mut app = app(VirtualList::new(100, |_: &TestState, index| {
+        Box::new(
+            Button::new(DummyWidget).with_on_pressed(move |state: &mut TestState| {
+                state.count = index as i32;
+                Update::DRAW
+            }),
+        ) as Box<dyn Widget<TestState>>
+    })
+    .with_row_height(20.0)
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+    app.widget_mut().scroll_to(200.0);
+    app.update();
+
+    app.click(Vector2::new(50.0, 50.0), MouseButton::Left);
+    assert_eq!(app.state().count, 12);
+}
+
+#[test]
+fn virtual_list_clamps_row_height() {
+    let mut app = app(VirtualList::new(1000, |_: &TestState, _| {
+        Box::new(DummyWidget) as Box<dyn Widget<TestState>>
+    })
+    .with_row_height(0.0)
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+
+    // 1 point high rows inside the 100 points high viewport, a partially visible one and the overscan
+    assert_eq!(app.widget().visible_rows(), 0..103);
+}
+
+/// Remembers the count of the state, when it was first updated, and writes it into `value`.
+struct Remember {
+    count: Option<i32>,
+}
+
+impl Widget<TestState> for Remember {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, state: &mut TestState, _: &AppInfo) -> Update {
+        state.value = *self.count.get_or_insert(state.count) as f32;
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Remember")
+    }
+
+    fn save_state(&mut self, store: &mut InstanceStore) {
+        store.insert(self.count);
+    }
+
+    fn restore_state(&mut self, _: &TestState, store: &mut InstanceStore) {
+        if let Some(count) = store.take() {
+            self.count = count;
+        }
+    }
+}
+
+#[test]
+fn virtual_list_keeps_state_of_hidden_rows() {
+    let mut app = app(VirtualList::new(1000, |_: &TestState, index| {
+        // only the first row remembers the count
+        if index == 0 {
+            Box::new(Remember { count: None }) as Box<dyn Widget<TestState>>
+        } else {
+            Box::new(DummyWidget)
+        }
+    })
+    .with_row_height(20.0)
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+    app.update();
+    assert_eq!(app.state().value, 0.0);
+
+    app.state_mut().count = 5;
+    app.widget_mut().scroll_to(1000.0);
+    app.update();
+    assert!(!app.widget().visible_rows().contains(&0));
+
+    // the first row is built again and continues with its previous state
+    app.widget_mut().scroll_to(0.0);
+    app.update();
+    app.update();
+    assert_eq!(app.widget().visible_rows().start, 0);
+    assert_eq!(app.state().value, 0.0);
+}
+
+/// Fills the window, counts mouse presses in `value` and opens an overlay with a counter button on the first update.
+struct Opener {
+    config: fn(&AppInfo) -> OverlayConfig,
+    when: fn(&TestState) -> bool,
+    overlay: Option<OverlayKey>,
+}
+
+impl Opener {
+    fn new(config: fn(&AppInfo) -> OverlayConfig) -> Self {
+        Self::when(config, |_| true)
+    }
+
+    /// Opens the overlay on the first update, where `when` returns true.
+    fn when(config: fn(&AppInfo) -> OverlayConfig, when: fn(&TestState) -> bool) -> Self {
+        Self {
+            config,
+            when,
+            overlay: None,
+        }
+    }
+}
+
+impl Widget<TestState> for Opener {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle {
+                size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+                ..Default::default()
+            },
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, state: &mut TestState, info: &AppInfo) -> Update {
+        if info.cursor_pos.is_some() {
+            state.value += info
+                .buttons
+                .iter()
+                .filter(|(_, _, el)| *el == ElementState::Pressed)
+                .count() as f32;
+        }
+
+        if self.overlay.is_none() && (self.when)(state) {
+            self.overlay = Some(info.overlays.open(
+                (self.config)(info),
+                Button::new(Text::new("Ok".to_string())).with_on_pressed(
+                    |state: &mut TestState| {
+                        state.count += 1;
+                        Update::DRAW
+                    },
+                ),
+            ));
+        }
+
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Opener")
+    }
+}
+
+/// A popup below a 50x20 anchor at `(10, 10)`.
+fn popup(info: &AppInfo) -> OverlayConfig {
+    let mut anchor = Layout::new();
+
+    anchor.location.x = 10.0;
+    anchor.location.y = 10.0;
+    anchor.size.width = 50.0;
+    anchor.size.height = 20.0;
+
+    OverlayConfig::popup(&anchor, info)
+}
+
+#[test]
+fn overlay_receives_input_before_widgets() {
+    let mut app = app(Opener::new(popup));
+
+    app.update();
+    assert!(app.info().overlays.is_open(app.widget().overlay.unwrap()));
+
+    // the popup is placed below the anchor and consumes the click
+    app.click(Vector2::new(15.0, 40.0), MouseButton::Left);
+    assert_eq!(app.state().count, 1);
+    assert_eq!(app.state().value, 0.0);
+}
+
+#[test]
+fn overlay_dismisses_on_outside_click() {
+    let mut app = app(Opener::new(popup));
+
+    app.update();
+    app.click(Vector2::new(190.0, 90.0), MouseButton::Left);
+
+    // the click still reaches the widgets below
+    assert!(!app.info().overlays.is_open(app.widget().overlay.unwrap()));
+    assert_eq!(app.state().value, 1.0);
+
+    app.click(Vector2::new(15.0, 40.0), MouseButton::Left);
+    assert_eq!(app.state().count, 0);
+}
+
+#[test]
+fn overlay_dismisses_on_escape() {
+    let mut app = app(Opener::new(popup));
+
+    app.update();
+    app.press_key(Key::Named(NamedKey::Escape));
+
+    assert!(app.info().overlays.is_empty());
+}
+
+#[test]
+fn overlay_is_part_of_accessibility_tree() {
+    let mut app = app(Opener::new(popup));
+
+    // the popup button gets registered on the second update
+    app.update();
+    app.update();
+    app.press_key(Key::Named(NamedKey::Tab));
+
+    let tree = app.access_tree();
+    let root = tree.tree.as_ref().unwrap().root;
+    let (button, node) = access_node(&tree, Role::Button);
+    let window = &tree.nodes.iter().find(|(id, _)| *id == root).unwrap().1;
+
+    // the popup is placed below the anchor, above the widget tree
+    let bounds = node.bounds().unwrap();
+    assert_eq!(window.children().len(), 2);
+    assert_eq!(window.children()[1], button);
+    assert_eq!((bounds.x0, bounds.y0), (10.0, 34.0));
+    assert_eq!(tree.focus, button);
+}
+
+#[test]
+fn modal_overlay_blocks_input() {
+    let mut app = app(Opener::new(|_| OverlayConfig::modal()));
+
+    app.update();
+    app.click(Vector2::new(5.0, 5.0), MouseButton::Left);
+
+    assert!(!app.info().overlays.is_empty());
+    assert_eq!(app.state().value, 0.0);
+
+    app.press_key(Key::Named(NamedKey::Escape));
+    assert!(app.info().overlays.is_empty());
+}
+
+fn overlay_focus_app(
+    config: fn(&AppInfo) -> OverlayConfig,
+) -> HeadlessApp<CelesteTheme, Container<TestState>, TestState> {
+    let mut widgets = focus_widgets();
+
+    // opens the overlay, when the checkbox gets checked
+    widgets.insert(
+        0,
+        Val::new_val(Box::new(Opener::when(config, |state| state.checked))),
+    );
+
+    app(Container::new(widgets))
+}
+
+#[test]
+fn popup_keeps_focus_of_widgets() {
+    let mut app = overlay_focus_app(popup);
+
+    app.update();
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Space));
+    assert!(app.state().checked);
+
+    // the popup button is registered after the widgets below
+    app.update();
+    assert!(!app.info().overlays.is_empty());
+    assert_eq!(focused(&app), Some(1));
+
+    app.press_key(Key::Named(NamedKey::Space));
+    assert!(!app.state().checked);
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(3));
+
+    app.press_key(Key::Named(NamedKey::Enter));
+    assert_eq!(app.state().count, 1);
+}
+
+#[test]
+fn modal_overlay_traps_focus() {
+    let mut app = overlay_focus_app(|_| OverlayConfig::modal());
+
+    app.update();
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Tab));
+    app.press_key(Key::Named(NamedKey::Space));
+    app.update();
+    assert!(!app.info().overlays.is_empty());
+
+    // the focus moves into the dialog and stays there
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(3));
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(3));
+
+    app.set_modifiers(ModifiersState::SHIFT);
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(3));
+    app.set_modifiers(ModifiersState::empty());
+
+    app.press_key(Key::Named(NamedKey::Enter));
+    assert_eq!(app.state().count, 1);
+
+    // the focus is released with the dialog
+    app.press_key(Key::Named(NamedKey::Escape));
+    assert!(app.info().overlays.is_empty());
+    assert_eq!(focused(&app), None);
+
+    app.press_key(Key::Named(NamedKey::Tab));
+    assert_eq!(focused(&app), Some(0));
+}
+
+/// A 50x20 dropdown with a top margin of 120 points, that opens a popup below itself when clicked.
+struct Dropdown;
+
+impl Widget<TestState> for Dropdown {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle {
+                size: Vector2::new(Dimension::Length(50.0), Dimension::Length(20.0)),
+                margin: Rect {
+                    left: LengthPercentageAuto::Length(0.0),
+                    right: LengthPercentageAuto::Length(0.0),
+                    top: LengthPercentageAuto::Length(120.0),
+                    bottom: LengthPercentageAuto::Length(0.0),
+                },
+                ..Default::default()
+            },
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, layout: &LayoutNode, _: &mut TestState, info: &AppInfo) -> Update {
+        let Some(cursor) = info.cursor_pos else {
+            return Update::empty();
+        };
+
+        let l = &layout.layout;
+
+        if cursor.x as f32 >= l.location.x
+            && cursor.x as f32 <= l.location.x + l.size.width
+            && cursor.y as f32 >= l.location.y
+            && cursor.y as f32 <= l.location.y + l.size.height
+            && info
+                .buttons
+                .iter()
+                .any(|(_, _, el)| *el == ElementState::Released)
+        {
+            info.overlays.open(
+                OverlayConfig::popup(l, info),
+                Button::new(Text::new("Ok".to_string()).with_font_size(12.0)).with_on_pressed(
+                    |state: &mut TestState| {
+                        state.count += 1;
+                        Update::DRAW
+                    },
+                ),
+            );
+        }
+
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "Dropdown")
+    }
+}
+
+#[test]
+fn popup_inside_scroll_view_is_anchored_in_window() {
+    let mut app = app(ScrollView::new(
+        Container::new(vec![
+            Val::new_val(Box::new(Dropdown)),
+            Val::new_val(Box::new(Container::new(Vec::new()).with_layout_style(
+                LayoutStyle {
+                    size: Vector2::new(Dimension::Length(200.0), Dimension::Length(100.0)),
+                    ..Default::default()
+                },
+            ))),
+        ])
+        .with_layout_style(LayoutStyle {
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+    )
+    .with_layout_style(LayoutStyle {
+        size: Vector2::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+        flex_direction: FlexDirection::Column,
+        overflow: (Overflow::Scroll, Overflow::Scroll),
+        ..Default::default()
+    }));
+
+    app.update();
+    app.widget_mut().scroll_to(Vector2::new(0.0, 100.0));
+    app.update();
+
+    // the dropdown is drawn at (0, 20) inside the window
+    app.click(Vector2::new(10.0, 30.0), MouseButton::Left);
+    assert!(!app.info().overlays.is_empty());
+
+    // the popup is placed below the dropdown, starting at (0, 44)
+    app.click(Vector2::new(5.0, 50.0), MouseButton::Left);
+    assert_eq!(app.state().count, 1);
+}
+
+#[test]
+fn button_animates_until_settled() {
+    let mut app = app(Button::new(DummyWidget));
+
+    app.update();
+    app.move_cursor(Vector2::new(5.0, 5.0));
+    assert!(app.update().contains(Update::EVAL));
+
+    // the hover transition requests redraws, until it's finished
+    let mut frames = 0;
+
+    while app
+        .advance(Duration::from_millis(16))
+        .contains(Update::EVAL)
+    {
+        frames += 1;
+        assert!(frames < 100, "Animation did not settle");
+    }
+
+    assert!(frames >= 5);
+    assert!(!app.update().contains(Update::EVAL));
+}
+
+#[test]
+fn tasks_post_messages_from_other_threads() {
+    let mut app = app(DummyWidget);
+    let tasks = app.info().tasks.clone();
+
+    std::thread::spawn(move || {
+        tasks.post(|state: &mut TestState| {
+            state.count = 42;
+            Update::DRAW
+        })
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(app.state().count, 0);
+    assert!(app.update().contains(Update::DRAW));
+    assert_eq!(app.state().count, 42);
+}
+
+#[test]
+fn tasks_run_spawned_futures() {
+    let mut app = app(DummyWidget);
+    let tasks = app.info().tasks.clone();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    tasks.spawn({
+        let tasks = tasks.clone();
+
+        async move {
+            tasks.post(|state: &mut TestState| {
+                state.text = String::from("loaded");
+                Update::DRAW
+            });
+
+            sender.send(()).unwrap();
+        }
+    });
+
+    receiver.recv().unwrap();
+    app.update();
+
+    assert_eq!(app.state().text, "loaded");
+}
+
+#[test]
+fn tasks_run_timers() {
+    let mut app = app(DummyWidget);
+    let tasks = app.info().tasks.clone();
+
+    tasks.set_timeout(Duration::ZERO, |state: &mut TestState| {
+        state.checked = true;
+        Update::DRAW
+    });
+
+    let interval = tasks.set_interval(Duration::ZERO, |state: &mut TestState| {
+        state.count += 1;
+        Update::DRAW
+    });
+
+    app.update();
+    assert!(app.state().checked);
+    assert_eq!(app.state().count, 1);
+
+    app.update();
+    assert_eq!(app.state().count, 2);
+
+    tasks.cancel(interval);
+
+    assert!(app.update().is_empty());
+    assert_eq!(app.state().count, 2);
+}
+
+#[test]
+fn tasks_cancel_running_timers() {
+    let mut app = app(DummyWidget);
+    let tasks = app.info().tasks.clone();
+    let id = Arc::new(Mutex::new(None));
+
+    // the interval cancels itself on its second run
+    let interval = {
+        let tasks = tasks.clone();
+        let id = id.clone();
+
+        tasks
+            .clone()
+            .set_interval(Duration::ZERO, move |state: &mut TestState| {
+                state.count += 1;
+
+                if state.count == 2 {
+                    tasks.cancel(id.lock().unwrap().unwrap());
+                }
+
+                Update::DRAW
+            })
+    };
+
+    *id.lock().unwrap() = Some(interval);
+
+    app.update();
+    app.update();
+    app.update();
+    assert_eq!(app.state().count, 2);
+
+    // the first timeout cancels the second one, which is due as well
+    let second = Arc::new(Mutex::new(None));
+
+    {
+        let tasks = tasks.clone();
+        let second = second.clone();
+
+        tasks
+            .clone()
+            .set_timeout(Duration::ZERO, move |_: &mut TestState| {
+                tasks.cancel(second.lock().unwrap().unwrap());
+                Update::DRAW
+            });
+    }
+
+    *second.lock().unwrap() = Some(tasks.set_timeout(Duration::ZERO, |state: &mut TestState| {
+        state.checked = true;
+        Update::DRAW
+    }));
+
+    app.update();
+    assert!(!app.state().checked);
+
+    // cancelling a timer, that already fired, does nothing
+    tasks.cancel(second.lock().unwrap().unwrap());
+    tasks.set_timeout(Duration::ZERO, |state: &mut TestState| {
+        state.checked = true;
+        Update::DRAW
+    });
+
+    app.update();
+    assert!(app.state().checked);
+}
+
+#[test]
+fn diagnostics_describe_recent_updates() {
+    let mut app = app(Container::new(vec![
+        Val::new_val(Box::new(DummyWidget)),
+        Val::new_val(Box::new(DummyWidget)),
+    ]));
+
+    for _ in 0..10 {
+        app.update();
+    }
+
+    let diagnostics = app.info().diagnostics;
+    let frame_times = diagnostics.frame_times;
+
+    assert_eq!(diagnostics.nodes, 3);
+    assert!(diagnostics.updates + diagnostics.updates_per_sec >= 10);
+
+    // updates without a rendered frame don't count as frames
+    assert_eq!(diagnostics.frames, 0);
+    assert_eq!(frame_times, FrameTimes::default());
+}
+
+#[test]
+fn inspector_toggles_with_key_chord() {
+    let mut app = app(DummyWidget);
+
+    app.update();
+    app.move_cursor(Vector2::new(5.0, 5.0));
+    assert!(app.update().is_empty());
+
+    app.set_modifiers(ModifiersState::SHIFT | ModifiersState::CONTROL);
+    assert!(app
+        .press_key(Key::Character("I".into()))
+        .contains(Update::DRAW));
+
+    // follows the cursor, while enabled
+    app.set_modifiers(ModifiersState::empty());
+    assert!(app.update().contains(Update::DRAW));
+
+    app.set_modifiers(ModifiersState::SHIFT | ModifiersState::CONTROL);
+    app.press_key(Key::Character("I".into()));
+
+    app.set_modifiers(ModifiersState::empty());
+    assert!(app.update().is_empty());
+}
+
+#[test]
+fn inspector_dumps_widget_tree() {
+    let mut app = app(Container::new(vec![
+        Val::new_val(Box::new(Button::new(DummyWidget))),
+        Val::new_val(Box::new(DummyWidget)),
+    ]));
+
+    let dump = app.dump_tree();
+    let lines = dump.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("maycoon-widgets:Container [x: 0, y: 0, w: 24, h: 100"));
+    assert!(lines[1].starts_with("├── maycoon-widgets:Button"));
+    assert!(lines[2].starts_with("│   └── maycoon-widgets:DummyWidget"));
+    assert!(lines[3].starts_with("└── maycoon-widgets:DummyWidget"));
+}
+
+#[test]
+fn celeste_themes_provide_all_style_keys() {
+    let schema = schema();
+
+    for theme in [
+        CelesteTheme::light(),
+        CelesteTheme::dark(),
+        CelesteTheme::high_contrast(),
+    ] {
+        assert_eq!(schema.validate(&theme), Vec::new());
+    }
+}
+
+/// Counts how often its layout style gets requested.
+struct StyleCounter {
+    requested: Rc<Cell<usize>>,
+}
+
+impl Widget<TestState> for StyleCounter {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        self.requested.set(self.requested.get() + 1);
+
+        StyleNode {
+            style: LayoutStyle {
+                size: Vector2::new(Dimension::Length(30.0), Dimension::Length(20.0)),
+                ..Default::default()
+            },
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, _: &AppInfo) -> Update {
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "StyleCounter")
+    }
+}
+
+#[test]
+fn inspector_reuses_the_layout_tree() {
+    let requested = Rc::new(Cell::new(0));
+    let mut app = app(StyleCounter {
+        requested: requested.clone(),
+    });
+
+    app.update();
+    let count = requested.get();
+
+    let dump = app.dump_tree();
+
+    assert!(dump.starts_with("maycoon-widgets:StyleCounter [x: 0, y: 0, w: 30, h: 20"));
+    assert!(dump.contains("size: Length(30.0) × Length(20.0)"));
+    assert_eq!(requested.get(), count);
+}
+
+/// A theme file with the given window background.
+fn theme_file(background: &str) -> String {
+    format!(
+        r##"
+        window_background = "{}"
+
+        [defaults.text]
+        foreground = "black"
+        background = "white"
+
+        [defaults.container]
+        foreground = "black"
+        background = "white"
+
+        [defaults.interactive]
+        active = "blue"
+        inactive = "gray"
+        hover = "lightblue"
+        disabled = "darkgray"
+        "##,
+        background
+    )
+}
+
+#[test]
+fn hot_reload_swaps_theme() {
+    let path = std::env::temp_dir().join(format!("maycoon-reload-{}.toml", std::process::id()));
+
+    std::fs::write(&path, theme_file("white")).unwrap();
+
+    let mut reload = HotReload::new().with_theme(&path, |path| FileTheme::load(path));
+    let mut app = HeadlessApp::new(
+        FileTheme::load(&path).unwrap(),
+        Text::new("Theme".to_string()),
+        TestState::default(),
+        Vector2::new(200.0, 100.0),
+    );
+
+    app.update();
+
+    // nothing changed yet
+    assert!(app.reload(&mut reload).unwrap().is_empty());
+
+    std::fs::write(&path, theme_file("#102030")).unwrap();
+
+    assert_eq!(app.reload(&mut reload), Ok(Update::FORCE));
+    assert_eq!(
+        app.theme().window_background(),
+        Color::rgb8(0x10, 0x20, 0x30)
+    );
+
+    // invalid files keep the old theme
+    std::fs::write(&path, "window_background = ").unwrap();
+
+    let errors = app.reload(&mut reload).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, path);
+    assert_eq!(
+        app.theme().window_background(),
+        Color::rgb8(0x10, 0x20, 0x30)
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn missing_fonts_fall_back_to_the_default_font() {
+    let size = app(Text::new("Fallback".to_string())).layout().layout.size;
+
+    let mut missing = app(Text::new("Fallback".to_string()).with_font("missing".to_string()));
+
+    missing.update();
+    assert_eq!(missing.layout().layout.size, size);
+
+    let mut input = app(text_input().with_font("missing".to_string()));
+
+    input.state_mut().text = "Fallback".to_string();
+    input.update();
+
+    // fonts with invalid data fall back as well
+    let mut invalid = app(Text::new("Fallback".to_string()).with_font("invalid".to_string()));
+
+    invalid
+        .info_mut()
+        .font_context
+        .insert("invalid", Font::new(Blob::new(Arc::new(vec![0; 16])), 0));
+
+    invalid.update();
+    assert_eq!(invalid.layout().layout.size, size);
+}
+
+#[test]
+fn hot_reload_swaps_fonts() {
+    let font = include_bytes!("assets/DejaVuSansMono.ttf");
+    let path = std::env::temp_dir().join(format!("maycoon-reload-{}.ttf", std::process::id()));
+
+    std::fs::write(&path, font).unwrap();
+
+    let mut reload = HotReload::<CelesteTheme>::new().with_font("mono", &path);
+    let mut app = app(Text::new("Reloaded".to_string())
+        .with_font("mono".to_string())
+        .with_font_size(20.0));
+
+    app.info_mut()
+        .font_context
+        .insert("mono", Font::new(Blob::new(Arc::new(font.to_vec())), 0));
+
+    let size = app.layout().layout.size;
+    let generation = app.info().font_context.generation();
+
+    // the font file is saved again
+    std::fs::write(&path, [font.as_slice(), &[0; 4]].concat()).unwrap();
+
+    assert_eq!(app.reload(&mut reload), Ok(Update::FORCE));
+    assert!(app.info().font_context.generation() > generation);
+
+    // the text is measured again with the reloaded font
+    app.update();
+
+    assert_eq!(app.layout().layout.size, size);
+
+    // a half-saved font file keeps the old font
+    let generation = app.info().font_context.generation();
+
+    std::fs::write(&path, &font[..64]).unwrap();
+
+    let errors = app.reload(&mut reload).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, path);
+    assert_eq!(app.info().font_context.generation(), generation);
+
+    app.update();
+    assert_eq!(app.layout().layout.size, size);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn hot_reload_keeps_color_scheme() {
+    let path = std::env::temp_dir().join(format!("maycoon-scheme-{}.toml", std::process::id()));
+
+    std::fs::write(&path, "").unwrap();
+
+    let mut reload = HotReload::new().with_theme(&path, |_| Ok::<_, String>(CelesteTheme::light()));
+    let mut app = app(Text::new("Theme".to_string()));
+
+    app.set_color_scheme(ColorScheme::Dark);
+
+    let dark = app.theme().window_background();
+
+    std::fs::write(&path, "changed").unwrap();
+
+    assert_eq!(app.reload(&mut reload), Ok(Update::FORCE));
+    assert_eq!(app.theme().window_background(), dark);
+
+    std::fs::remove_file(&path).unwrap();
+}
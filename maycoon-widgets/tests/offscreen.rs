@@ -0,0 +1,54 @@
+use maycoon_core::app::headless::HeadlessApp;
+use maycoon_core::app::update::Update;
+use maycoon_core::state::{State, Val};
+use maycoon_theme::theme::celeste::CelesteTheme;
+use maycoon_theme::theme::Theme;
+use maycoon_widgets::checkbox::Checkbox;
+use nalgebra::Vector2;
+
+mod common;
+
+struct TestState {
+    checked: bool,
+}
+
+impl State for TestState {}
+
+#[test]
+fn checkbox_renders_offscreen() {
+    let mut renderer = common::renderer();
+
+    let mut app = HeadlessApp::new(
+        CelesteTheme::light(),
+        Checkbox::new(Val::new_state(|state: &TestState| state.checked)).with_on_change(
+            |state: &mut TestState| {
+                state.checked = !state.checked;
+                Update::DRAW
+            },
+        ),
+        TestState { checked: false },
+        Vector2::new(40.0, 30.0),
+    );
+
+    let background = CelesteTheme::light().window_background().to_premul_u32();
+
+    let unchecked = app.render(&mut renderer).expect("Failed to render");
+    assert_eq!(unchecked.size(), Vector2::new(40, 30));
+    assert_eq!(
+        u32::from_be_bytes(unchecked.pixel(10, 10).unwrap()),
+        background
+    );
+
+    app.state_mut().checked = true;
+    app.update();
+
+    let checked = app.render(&mut renderer).expect("Failed to render");
+    assert_ne!(
+        u32::from_be_bytes(checked.pixel(10, 10).unwrap()),
+        background
+    );
+    assert_eq!(
+        u32::from_be_bytes(checked.pixel(35, 25).unwrap()),
+        background
+    );
+}
@@ -0,0 +1,562 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use maycoon_core::app::headless::HeadlessApp;
+use maycoon_core::app::info::AppInfo;
+use maycoon_core::app::overlay::OverlayConfig;
+use maycoon_core::app::update::Update;
+use maycoon_core::layout::{
+    AlignItems, Dimension, FlexDirection, LayoutNode, LayoutStyle, LengthPercentage, Overflow,
+    Rect, StyleNode,
+};
+use maycoon_core::state::{State, Val};
+use maycoon_core::text::{TextAlign, TextSpan};
+use maycoon_core::vg::peniko::{Color, Format};
+use maycoon_core::vg::Scene;
+use maycoon_core::widget::Widget;
+use maycoon_core::window::{Key, ModifiersState, MouseButton};
+use maycoon_theme::globals::{StyleContext, StyleOverrides};
+use maycoon_theme::id::WidgetId;
+use maycoon_theme::style::{Style, StyleVal};
+use maycoon_theme::theme::celeste::CelesteTheme;
+use maycoon_theme::theme::{ColorScheme, Theme};
+use maycoon_widgets::button::Button;
+use maycoon_widgets::checkbox::Checkbox;
+use maycoon_widgets::container::Container;
+use maycoon_widgets::image::{Image, ImageData};
+use maycoon_widgets::scroll_view::ScrollView;
+use maycoon_widgets::slider::Slider;
+use maycoon_widgets::styled::Styled;
+use maycoon_widgets::text::Text;
+use maycoon_widgets::text_area::TextArea;
+use maycoon_widgets::text_input::TextInput;
+use nalgebra::Vector2;
+
+mod common;
+
+struct TestState {
+    checked: bool,
+    value: f32,
+}
+
+impl State for TestState {}
+
+fn app<W: Widget<TestState>>(
+    widget: W,
+    size: Vector2<f32>,
+) -> HeadlessApp<CelesteTheme, W, TestState> {
+    HeadlessApp::with_font_context(
+        CelesteTheme::light(),
+        widget,
+        TestState {
+            checked: false,
+            value: 0.25,
+        },
+        size,
+        common::font_context(),
+    )
+}
+
+fn assert_snapshot<W: Widget<TestState>>(
+    name: &str,
+    app: &mut HeadlessApp<CelesteTheme, W, TestState>,
+) {
+    let image = app
+        .render(&mut common::renderer())
+        .expect("Failed to render");
+
+    common::snapshots().assert_snapshot(name, &image);
+}
+
+#[test]
+fn text() {
+    let mut app = app(
+        Text::new("Hello World!".to_string()).with_font_size(20.0),
+        Vector2::new(260.0, 40.0),
+    );
+
+    assert_snapshot("text", &mut app);
+}
+
+#[test]
+fn text_wrapped() {
+    let mut app = app(
+        Text::rich(vec![
+            TextSpan::new("Wrapped and "),
+            TextSpan::new("centered").with_color(Color::rgb8(200, 40, 40)),
+            TextSpan::new(" text"),
+        ])
+        .with_font_size(16.0)
+        .with_align(TextAlign::Center)
+        .with_layout(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Auto),
+            ..Default::default()
+        }),
+        Vector2::new(100.0, 80.0),
+    );
+
+    assert_snapshot("text_wrapped", &mut app);
+}
+
+#[test]
+fn button() {
+    let mut app = app(
+        Button::new(Text::new("Press me".to_string()).with_font_size(20.0)),
+        Vector2::new(200.0, 40.0),
+    );
+
+    assert_snapshot("button_idle", &mut app);
+
+    // let the hover transition finish
+    app.move_cursor(Vector2::new(10.0, 10.0));
+    app.update();
+    app.advance(Duration::from_millis(500));
+    assert_snapshot("button_hovered", &mut app);
+}
+
+#[test]
+fn button_style() {
+    let mut app = app(
+        Button::new(Text::new("Delete".to_string()).with_font_size(20.0))
+            .with_class("danger")
+            .with_style(Style::from_values([(
+                "color_idle".to_string(),
+                StyleVal::Color(Color::rgb8(220, 60, 60)),
+            )])),
+        Vector2::new(200.0, 40.0),
+    );
+
+    assert_eq!(
+        app.widget_mut().widget_id().to_string(),
+        "maycoon-widgets:Button.danger"
+    );
+
+    assert_snapshot("button_style", &mut app);
+}
+
+#[test]
+fn checkbox() {
+    let mut app = app(
+        Checkbox::new(Val::new_state(|state: &TestState| state.checked)).with_on_change(
+            |state: &mut TestState| {
+                state.checked = !state.checked;
+                Update::DRAW
+            },
+        ),
+        Vector2::new(24.0, 24.0),
+    );
+
+    assert_snapshot("checkbox_unchecked", &mut app);
+
+    // let the check transition finish
+    app.click(Vector2::new(10.0, 10.0), MouseButton::Left);
+    app.advance(Duration::from_millis(500));
+    assert_snapshot("checkbox_checked", &mut app);
+}
+
+#[test]
+fn slider() {
+    let mut app = app(
+        Slider::new(
+            Val::new_state(|state: &TestState| state.value),
+            |state: &mut TestState, value| {
+                state.value = value;
+                Update::DRAW
+            },
+        ),
+        Vector2::new(130.0, 30.0),
+    );
+
+    assert_snapshot("slider", &mut app);
+}
+
+#[test]
+fn text_input() {
+    let mut app = app(
+        TextInput::new("Hello World!".to_string()).with_font_size(16.0),
+        Vector2::new(210.0, 50.0),
+    );
+
+    assert_snapshot("text_input_idle", &mut app);
+
+    app.click(Vector2::new(100.0, 20.0), MouseButton::Left);
+    assert_snapshot("text_input_focused", &mut app);
+}
+
+#[test]
+fn text_area() {
+    let mut app = app(
+        TextArea::new("Hello\nWorld!".to_string())
+            .with_font_size(16.0)
+            .with_layout_style(LayoutStyle {
+                size: Vector2::<Dimension>::new(Dimension::Length(150.0), Dimension::Length(70.0)),
+                ..Default::default()
+            }),
+        Vector2::new(150.0, 70.0),
+    );
+
+    assert_snapshot("text_area", &mut app);
+}
+
+#[test]
+fn image() {
+    let pixels = (0..16 * 16)
+        .flat_map(|i| {
+            let (x, y) = (i % 16, i / 16);
+            [x as u8 * 16, y as u8 * 16, 128, 255]
+        })
+        .collect();
+
+    let mut app = app(
+        Image::new(ImageData::new(pixels, Vector2::new(16, 16), Format::Rgba8)),
+        Vector2::new(20.0, 20.0),
+    );
+
+    assert_snapshot("image", &mut app);
+}
+
+#[test]
+fn container() {
+    let mut app = app(
+        Container::new(vec![
+            Val::new_val(Box::new(
+                Text::new("Column".to_string()).with_font_size(16.0),
+            )),
+            Val::new_val(Box::new(Checkbox::new(Val::new_val(true)))),
+            Val::new_val(Box::new(Checkbox::new(Val::new_val(false)))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        }),
+        Vector2::new(120.0, 80.0),
+    );
+
+    assert_snapshot("container", &mut app);
+
+    let diagnostics = app.info().diagnostics;
+
+    assert_eq!(diagnostics.frames, 1);
+    assert!(diagnostics.scene_paths > 0);
+    assert!(diagnostics.scene_bytes > 0);
+
+    // the rendered frame is the only one
+    let frame_times = diagnostics.frame_times;
+
+    assert!(frame_times.max > Duration::ZERO);
+    assert_eq!(frame_times.p50, frame_times.max);
+}
+
+#[test]
+fn inspector() {
+    let mut app = app(
+        Container::new(vec![
+            Val::new_val(Box::new(Button::new(Text::new("Inspect".to_string())))),
+            Val::new_val(Box::new(Checkbox::new(Val::new_val(true)))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            padding: Rect {
+                left: LengthPercentage::Length(10.0),
+                right: LengthPercentage::Length(10.0),
+                top: LengthPercentage::Length(10.0),
+                bottom: LengthPercentage::Length(10.0),
+            },
+            gap: Vector2::new(
+                LengthPercentage::Length(10.0),
+                LengthPercentage::Length(0.0),
+            ),
+            align_items: Some(AlignItems::Start),
+            ..Default::default()
+        }),
+        Vector2::new(220.0, 120.0),
+    );
+
+    app.update();
+    app.set_modifiers(ModifiersState::SHIFT | ModifiersState::CONTROL);
+    app.press_key(Key::Character("I".into()));
+    app.set_modifiers(ModifiersState::empty());
+
+    // hover the text of the button
+    app.move_cursor(Vector2::new(30.0, 20.0));
+    app.update();
+
+    assert_snapshot("inspector", &mut app);
+}
+
+#[test]
+fn scroll_view() {
+    let mut app = app(
+        ScrollView::new(
+            Container::new(
+                ["One", "Two", "Three", "Four", "Five"]
+                    .into_iter()
+                    .map(|text| {
+                        Val::new_val(Box::new(Text::new(text.to_string()).with_font_size(16.0))
+                            as Box<dyn Widget<TestState>>)
+                    })
+                    .collect(),
+            )
+            .with_layout_style(LayoutStyle {
+                flex_direction: FlexDirection::Column,
+                ..Default::default()
+            }),
+        )
+        .with_scroll_to(Val::new_val(Some(Vector2::new(0.0, 30.0))))
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            overflow: (Overflow::Scroll, Overflow::Scroll),
+            ..Default::default()
+        }),
+        Vector2::new(120.0, 60.0),
+    );
+
+    app.update();
+
+    assert_snapshot("scroll_view", &mut app);
+}
+
+/// Opens a modal dialog on the first update.
+struct ModalOpener(bool);
+
+impl Widget<TestState> for ModalOpener {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        _: &mut dyn Theme,
+        _: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, info: &AppInfo) -> Update {
+        if !self.0 {
+            self.0 = true;
+
+            info.overlays.open(
+                OverlayConfig::modal(),
+                Text::<TestState>::new("Dialog".to_string()).with_font_size(16.0),
+            );
+        }
+
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "ModalOpener")
+    }
+}
+
+#[test]
+fn overlay() {
+    let mut app = app(
+        Container::new(vec![
+            Val::new_val(Box::new(ModalOpener(false)) as Box<dyn Widget<TestState>>),
+            Val::new_val(Box::new(Button::new(Text::new("Behind".to_string())))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            ..Default::default()
+        }),
+        Vector2::new(120.0, 80.0),
+    );
+
+    app.update();
+
+    assert_snapshot("overlay", &mut app);
+}
+
+#[test]
+fn dark() {
+    let mut app = app(
+        Container::new(vec![
+            Val::new_val(Box::new(Text::new("Dark".to_string()).with_font_size(16.0))),
+            Val::new_val(Box::new(Button::new(
+                Text::new("Button".to_string()).with_font_size(12.0),
+            ))),
+            Val::new_val(Box::new(Checkbox::new(Val::new_val(true)))),
+            Val::new_val(Box::new(Slider::new(
+                Val::new_state(|state: &TestState| state.value),
+                |state: &mut TestState, value| {
+                    state.value = value;
+                    Update::DRAW
+                },
+            ))),
+        ])
+        .with_layout_style(LayoutStyle {
+            size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        }),
+        Vector2::new(160.0, 140.0),
+    );
+
+    assert_eq!(app.set_color_scheme(ColorScheme::Dark), Update::DRAW);
+    assert_eq!(app.set_color_scheme(ColorScheme::Dark), Update::empty());
+
+    assert_snapshot("dark", &mut app);
+}
+
+#[test]
+fn styled() {
+    let mut app = app(
+        Styled::new(
+            Container::new(vec![
+                Val::new_val(Box::new(Text::new("Faded".to_string()))),
+                Val::new_val(Box::new(Button::new(Text::new("Button".to_string())))),
+                Val::new_val(Box::new(Checkbox::new(Val::new_val(true)))),
+                Val::new_val(Box::new(Styled::new(
+                    Text::new("Red".to_string()),
+                    StyleOverrides::new()
+                        .with_text_color(Color::rgb8(200, 40, 40))
+                        .with_font_size(16.0)
+                        .with_disabled(false),
+                ))),
+            ])
+            .with_layout_style(LayoutStyle {
+                size: Vector2::<Dimension>::new(Dimension::Percent(1.0), Dimension::Percent(1.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: Some(AlignItems::Center),
+                ..Default::default()
+            }),
+            StyleOverrides::new()
+                .with_font_size(12.0)
+                .with_disabled(true)
+                .with_opacity(0.5),
+        ),
+        Vector2::new(160.0, 100.0),
+    );
+
+    // the inherited font sizes are applied by the first update
+    assert!(app.update().contains(Update::LAYOUT));
+    assert!(!app.update().contains(Update::LAYOUT));
+
+    assert_snapshot("styled", &mut app);
+}
+
+#[test]
+fn disabled_button() {
+    let mut app = app(
+        Styled::new(
+            Button::new(Text::new("Disabled".to_string()).with_font_size(20.0)).with_on_pressed(
+                |state: &mut TestState| {
+                    state.checked = true;
+                    Update::DRAW
+                },
+            ),
+            Val::new_state(|state: &TestState| {
+                StyleOverrides::new().with_disabled(state.value < 0.5)
+            }),
+        ),
+        Vector2::new(200.0, 40.0),
+    );
+
+    // the button ignores input without being rendered first
+    app.click(Vector2::new(10.0, 10.0), MouseButton::Left);
+    assert!(!app.state().checked);
+
+    app.state_mut().value = 1.0;
+    app.click(Vector2::new(10.0, 10.0), MouseButton::Left);
+    assert!(app.state().checked);
+}
+
+/// Records the inherited style of the theme and the app info, when it's updated and rendered.
+struct StyleProbe {
+    contexts: Rc<RefCell<Vec<StyleContext>>>,
+}
+
+impl Widget<TestState> for StyleProbe {
+    fn render(
+        &mut self,
+        _: &mut Scene,
+        theme: &mut dyn Theme,
+        info: &AppInfo,
+        _: &LayoutNode,
+        _: &TestState,
+    ) {
+        let mut contexts = self.contexts.borrow_mut();
+
+        contexts.push(theme.globals().context().clone());
+        contexts.push(info.style.context());
+    }
+
+    fn layout_style(&mut self, _: &TestState) -> StyleNode {
+        StyleNode {
+            style: LayoutStyle::default(),
+            children: Vec::new(),
+            key: None,
+            measure: None,
+        }
+    }
+
+    fn update(&mut self, _: &LayoutNode, _: &mut TestState, info: &AppInfo) -> Update {
+        self.contexts.borrow_mut().push(info.style.context());
+        Update::empty()
+    }
+
+    fn widget_id(&mut self) -> WidgetId {
+        WidgetId::new("maycoon-widgets", "StyleProbe")
+    }
+}
+
+#[test]
+fn styled_children_inherit_same_style_in_update_and_render() {
+    let contexts = Rc::new(RefCell::new(Vec::new()));
+
+    let mut app = app(
+        Styled::new(
+            Button::new(StyleProbe {
+                contexts: contexts.clone(),
+            }),
+            StyleOverrides::new().with_disabled(true).with_opacity(0.5),
+        ),
+        Vector2::new(100.0, 40.0),
+    );
+
+    app.update();
+    app.render(&mut common::renderer())
+        .expect("Failed to render");
+
+    let contexts = contexts.borrow();
+
+    assert_eq!(contexts.len(), 3);
+    assert!(contexts[0].disabled);
+    assert!(contexts[0].invert_text_color);
+    assert_eq!(contexts[0].opacity, 0.5);
+    assert_eq!(contexts[1], contexts[0]);
+    assert_eq!(contexts[2], contexts[0]);
+}
+
+#[test]
+fn diagnostics_overlay() {
+    let mut renderer = common::renderer();
+
+    let mut plain = app(Checkbox::new(Val::new_val(true)), Vector2::new(200.0, 80.0));
+    let mut shown =
+        app(Checkbox::new(Val::new_val(true)), Vector2::new(200.0, 80.0)).with_diagnostics(true);
+
+    plain.update();
+    shown.update();
+
+    // headless apps draw the same overlays as windows
+    assert_ne!(
+        plain.render(&mut renderer).expect("Failed to render"),
+        shown.render(&mut renderer).expect("Failed to render"),
+    );
+}